@@ -1,6 +1,8 @@
 use dasp_sample::{Duplex, Sample};
 use divan::Bencher;
 use rodio::conversions::SampleTypeConverter;
+use rodio::source::UniformSourceIterator;
+use rodio::Source;
 
 mod shared;
 
@@ -21,3 +23,14 @@ fn from_sample<S: Duplex<f32>>(bencher: Bencher) {
             SampleTypeConverter::<_, rodio::Sample>::new(source).for_each(divan::black_box_drop)
         })
 }
+
+#[divan::bench]
+fn uniform_matching_format(bencher: Bencher) {
+    bencher
+        .with_inputs(shared::music_wav)
+        .bench_values(|source| {
+            let (channels, sample_rate) = (source.channels(), source.sample_rate());
+            UniformSourceIterator::new(source, channels, sample_rate)
+                .for_each(divan::black_box_drop)
+        })
+}
@@ -12,12 +12,17 @@
 //!
 
 use crate::common::{ChannelCount, SampleRate};
+use crate::math;
 use crate::source::{SeekError, UniformSourceIterator};
 use crate::{Sample, Source};
 use std::sync::Arc;
 use std::time::Duration;
 
 /// A buffer of samples treated as a source.
+///
+/// The samples are shared between clones, so cloning is cheap. This makes it a good fit for
+/// sounds that are played many times, such as game sound effects: decode them once with
+/// [`Source::record`] and append a clone every time the sound should play.
 #[derive(Debug, Clone)]
 pub struct SamplesBuffer {
     data: Arc<[Sample]>,
@@ -33,6 +38,8 @@ impl SamplesBuffer {
     /// # Panics
     ///
     /// - Panics if the samples rate is zero.
+    /// - Panics if the length of the buffer is not a multiple of the channel count, the samples
+    ///   must form whole frames.
     /// - Panics if the length of the buffer is larger than approximately 16 billion elements.
     ///   This is because the calculation of the duration would overflow.
     ///
@@ -41,6 +48,11 @@ impl SamplesBuffer {
         D: Into<Vec<Sample>>,
     {
         let data: Arc<[f32]> = data.into().into();
+        assert!(
+            data.len().is_multiple_of(channels.get() as usize),
+            "{} samples do not form whole frames of {channels} channels",
+            data.len()
+        );
         let duration_ns = 1_000_000_000u64.checked_mul(data.len() as u64).unwrap()
             / sample_rate.get() as u64
             / channels.get() as u64;
@@ -58,18 +70,81 @@ impl SamplesBuffer {
         }
     }
 
+    /// Returns the buffer without the silence at its start and end.
+    ///
+    /// A frame is silent if none of its samples exceed `threshold_db` (in dBFS, relative to a
+    /// sample of `1.0`). Silent frames in between louder ones are kept. The whole buffer is
+    /// trimmed regardless of how much of it has been played.
+    pub fn trim_silence(self, threshold_db: f32) -> SamplesBuffer {
+        let threshold = math::db_to_linear(threshold_db);
+        let channels = self.channels.get() as usize;
+        let is_loud = |frame: &[Sample]| frame.iter().any(|sample| sample.abs() > threshold);
+        let start = self
+            .data
+            .chunks_exact(channels)
+            .position(is_loud)
+            .unwrap_or(0);
+        let end = self
+            .data
+            .chunks_exact(channels)
+            .rposition(is_loud)
+            .map_or(0, |last| last + 1);
+        SamplesBuffer::new(
+            self.channels,
+            self.sample_rate,
+            &self.data[start * channels..end * channels],
+        )
+    }
+
     pub(crate) fn record_source(source: impl Source) -> Self {
         let channel_count = source.channels();
         let sample_rate = source.sample_rate();
         let source = UniformSourceIterator::new(source, channel_count, sample_rate);
-        Self::new(
-            source.channels(),
-            source.sample_rate(),
-            source.into_iter().collect::<Vec<_>>(),
-        )
+        let channels = source.channels();
+        let sample_rate = source.sample_rate();
+        let mut samples: Vec<_> = source.into_iter().collect();
+        // Drop an incomplete last frame of a source that ended early.
+        samples.truncate(samples.len() - samples.len() % channels.get() as usize);
+        Self::new(channels, sample_rate, samples)
     }
 }
 
+/// Interleaves planar audio, one `Vec` per channel, into frames as used by [`SamplesBuffer`].
+///
+/// # Panics
+///
+/// Panics if the channels do not all have the same length.
+pub fn interleave(channels: &[Vec<Sample>]) -> Vec<Sample> {
+    let len = channels.first().map_or(0, Vec::len);
+    assert!(
+        channels.iter().all(|channel| channel.len() == len),
+        "all channels must have the same length"
+    );
+    (0..len)
+        .flat_map(|frame| channels.iter().map(move |channel| channel[frame]))
+        .collect()
+}
+
+/// Splits interleaved samples of `n` channels into one `Vec` per channel, the opposite of
+/// [`interleave`].
+///
+/// # Panics
+///
+/// - Panics if `n` is zero.
+/// - Panics if the length of `samples` is not a multiple of `n`, the samples must form whole
+///   frames.
+pub fn deinterleave(samples: &[Sample], n: usize) -> Vec<Vec<Sample>> {
+    assert!(n > 0, "the channel count must be greater than zero");
+    assert!(
+        samples.len().is_multiple_of(n),
+        "{} samples do not form whole frames of {n} channels",
+        samples.len()
+    );
+    (0..n)
+        .map(|channel| samples.iter().skip(channel).step_by(n).copied().collect())
+        .collect()
+}
+
 impl Source for SamplesBuffer {
     #[inline]
     fn current_span_len(&self) -> Option<usize> {
@@ -112,6 +187,13 @@ impl Source for SamplesBuffer {
         self.pos = new_pos;
         Ok(())
     }
+
+    #[inline]
+    fn try_seek_samples(&mut self, frame: u64) -> Result<(), SeekError> {
+        let channels = self.channels().get() as u64;
+        self.pos = frame.saturating_mul(channels).min(self.data.len() as u64) as usize;
+        Ok(())
+    }
 }
 
 impl Iterator for SamplesBuffer {
@@ -141,6 +223,12 @@ mod tests {
         let _ = SamplesBuffer::new(nz!(1), nz!(44100), vec![0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
     }
 
+    #[test]
+    #[should_panic(expected = "whole frames")]
+    fn rejects_incomplete_frame() {
+        let _ = SamplesBuffer::new(nz!(2), nz!(44100), vec![0.0, 0.0, 0.0]);
+    }
+
     #[test]
     fn duration_basic() {
         let buf = SamplesBuffer::new(nz!(2), nz!(2), vec![0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
@@ -161,6 +249,62 @@ mod tests {
         assert_eq!(buf.next(), None);
     }
 
+    #[test]
+    fn trims_silent_edges() {
+        use std::time::Duration;
+
+        let mut samples = vec![0.0; 2 * 100];
+        // Below -40 dB counts as silence.
+        samples.extend([0.005, -0.005]);
+        samples.extend((0..100).flat_map(|i| [(i as f32 * 0.1).sin() + 0.5; 2]));
+        samples.extend([0.0; 2 * 10]);
+        samples.extend([0.2, 0.0]);
+        samples.extend([0.001; 2 * 300]);
+        let buf = SamplesBuffer::new(nz!(2), nz!(1000), samples);
+
+        let trimmed = buf.trim_silence(-40.0);
+        assert_eq!(trimmed.total_duration(), Some(Duration::from_millis(111)));
+        let output: Vec<f32> = trimmed.collect();
+        assert_eq!(output[..2], [0.5, 0.5]);
+        // Silence in between is kept.
+        assert_eq!(output[200..220], [0.0; 20]);
+        assert_eq!(output[220..], [0.2, 0.0]);
+
+        let silent = SamplesBuffer::new(nz!(1), nz!(1000), vec![0.0; 10]);
+        assert_eq!(silent.trim_silence(-60.0).count(), 0);
+    }
+
+    #[test]
+    fn interleave_round_trips() {
+        use crate::buffer::{deinterleave, interleave};
+
+        let planar = vec![vec![1.0, 2.0, 3.0], vec![-1.0, -2.0, -3.0]];
+        let interleaved = interleave(&planar);
+        assert_eq!(interleaved, [1.0, -1.0, 2.0, -2.0, 3.0, -3.0]);
+        assert_eq!(deinterleave(&interleaved, 2), planar);
+        assert_eq!(interleave(&[]), Vec::<f32>::new());
+    }
+
+    #[test]
+    fn clones_play_independently() {
+        use crate::mixer;
+
+        let recorded = SamplesBuffer::new(nz!(1), nz!(48000), vec![1.0, 2.0, 3.0, 4.0]).record();
+        let (tx, mut rx) = mixer::mixer(nz!(1), nz!(48000));
+
+        tx.add(recorded.clone());
+        assert_eq!(rx.next(), Some(1.0));
+        tx.add(recorded.clone());
+        assert_eq!(rx.next(), Some(3.0));
+        assert_eq!(rx.next(), Some(5.0));
+        assert_eq!(rx.next(), Some(7.0));
+        assert_eq!(rx.next(), Some(4.0));
+        assert_eq!(rx.next(), None);
+
+        // The original is unaffected by playing its clones.
+        assert_eq!(recorded.count(), 4);
+    }
+
     #[cfg(test)]
     mod try_seek {
         use super::*;
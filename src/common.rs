@@ -7,6 +7,112 @@ pub type SampleRate = NonZero<u32>;
 /// Number of channels in a stream. Can never be Zero
 pub type ChannelCount = NonZero<u16>;
 
+/// The speaker a channel is meant to be played on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChannelPosition {
+    /// The only channel of a mono stream.
+    Mono,
+    /// Front left.
+    FrontLeft,
+    /// Front right.
+    FrontRight,
+    /// Front center.
+    FrontCenter,
+    /// Low frequency effects, the subwoofer.
+    LowFrequency,
+    /// Back left, also called rear or surround left.
+    BackLeft,
+    /// Back right, also called rear or surround right.
+    BackRight,
+    /// Side left.
+    SideLeft,
+    /// Side right.
+    SideRight,
+}
+
+/// A common speaker arrangement, defining which speaker each channel maps to.
+///
+/// The channels are ordered as in WAVE and most operating system audio APIs, see
+/// [`ChannelLayout::positions`].
+///
+/// When an output stream has a layout, sources are up or downmixed to it by the speaker of
+/// each channel: a mono source plays on the center speaker, a stereo source on the front left
+/// and right with nothing on the LFE, and a 5.1 source played in stereo is folded down with
+/// the center and back channels at -3 dB and the LFE dropped. The layout of a source is
+/// taken from its channel count, see [`ChannelLayout::from_channel_count`].
+///
+/// Without a layout, for other channel counts and for mono output, the channel conversions
+/// work on channel indices: a mono source plays on the first two channels and channels beyond
+/// the output's count are dropped. Route channels explicitly, for example with
+/// [`ChannelVolume`](crate::source::ChannelVolume), when that is not what you want.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChannelLayout {
+    /// A single channel.
+    Mono,
+    /// Front left, front right.
+    Stereo,
+    /// Front left, front right, back left, back right.
+    Quad,
+    /// 5.1 surround: front left, front right, front center, low frequency, back left, back right.
+    Surround5_1,
+    /// 7.1 surround: front left, front right, front center, low frequency, back left, back right,
+    /// side left, side right.
+    Surround7_1,
+}
+
+impl ChannelLayout {
+    /// The speaker of each channel, in channel order.
+    pub fn positions(&self) -> &'static [ChannelPosition] {
+        use ChannelPosition::*;
+        match self {
+            ChannelLayout::Mono => &[Mono],
+            ChannelLayout::Stereo => &[FrontLeft, FrontRight],
+            ChannelLayout::Quad => &[FrontLeft, FrontRight, BackLeft, BackRight],
+            ChannelLayout::Surround5_1 => &[
+                FrontLeft,
+                FrontRight,
+                FrontCenter,
+                LowFrequency,
+                BackLeft,
+                BackRight,
+            ],
+            ChannelLayout::Surround7_1 => &[
+                FrontLeft,
+                FrontRight,
+                FrontCenter,
+                LowFrequency,
+                BackLeft,
+                BackRight,
+                SideLeft,
+                SideRight,
+            ],
+        }
+    }
+
+    /// The number of channels of the layout.
+    pub fn channel_count(&self) -> ChannelCount {
+        ChannelCount::new(self.positions().len() as u16).expect("layouts are never empty")
+    }
+
+    /// The layout assumed for a source with `count` channels: 1, 2, 4, 6 and 8 channels are
+    /// mono, stereo, quad, 5.1 and 7.1. Returns `None` for other counts.
+    pub fn from_channel_count(count: ChannelCount) -> Option<ChannelLayout> {
+        match count.get() {
+            1 => Some(ChannelLayout::Mono),
+            2 => Some(ChannelLayout::Stereo),
+            4 => Some(ChannelLayout::Quad),
+            6 => Some(ChannelLayout::Surround5_1),
+            8 => Some(ChannelLayout::Surround7_1),
+            _ => None,
+        }
+    }
+
+    /// The index of the channel played on `position`, if the layout has that speaker.
+    pub fn channel_of(&self, position: ChannelPosition) -> Option<usize> {
+        self.positions().iter().position(|p| *p == position)
+    }
+}
+
 /// Number of bits per sample. Can never be zero.
 pub type BitDepth = NonZero<u32>;
 
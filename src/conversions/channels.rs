@@ -1,4 +1,6 @@
-use crate::common::ChannelCount;
+use std::f32::consts::FRAC_1_SQRT_2;
+
+use crate::common::{ChannelCount, ChannelLayout, ChannelPosition};
 use crate::Sample;
 
 /// Iterator that converts from a certain channel count to another.
@@ -12,6 +14,15 @@ where
     to: ChannelCount,
     sample_repeat: Option<Sample>,
     next_output_sample_pos: u16,
+    mix: Option<Mix>,
+}
+
+#[derive(Clone, Debug)]
+struct Mix {
+    // The gain of each input channel in each output channel, one row per output channel.
+    gains: Box<[Sample]>,
+    // The input frame being mixed.
+    frame: Box<[Sample]>,
 }
 
 impl<I> ChannelCountConverter<I>
@@ -32,9 +43,33 @@ where
             to,
             sample_repeat: None,
             next_output_sample_pos: 0,
+            mix: None,
         }
     }
 
+    /// Initializes the iterator to mix the input to the speakers of `to`.
+    ///
+    /// The layout of the input is taken from `from`, see [`ChannelLayout::from_channel_count`].
+    /// A mono input plays on the center speaker, a stereo input on the front left and right,
+    /// and channels the output lacks are mixed into the nearest speakers: the center into the
+    /// front left and right at -3 dB, the back channels into the side channels or the front
+    /// at -3 dB. The LFE is dropped if the output has none. If the input has no known layout
+    /// or `to` is mono, this converts like [`ChannelCountConverter::new`].
+    pub fn with_layout(
+        input: I,
+        from: ChannelCount,
+        to: ChannelLayout,
+    ) -> ChannelCountConverter<I> {
+        let mut converter = ChannelCountConverter::new(input, from, to.channel_count());
+        converter.mix = ChannelLayout::from_channel_count(from)
+            .and_then(|from| mix_gains(from, to))
+            .map(|gains| Mix {
+                gains,
+                frame: vec![0.0; from.get() as usize].into(),
+            });
+        converter
+    }
+
     /// Destroys this iterator and returns the underlying iterator.
     #[inline]
     pub fn into_inner(self) -> I {
@@ -55,6 +90,27 @@ where
     type Item = I::Item;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if let Some(mix) = &mut self.mix {
+            if self.next_output_sample_pos == 0 {
+                for (pos, sample) in mix.frame.iter_mut().enumerate() {
+                    *sample = match self.input.next() {
+                        Some(value) => value,
+                        None if pos == 0 => return None,
+                        None => 0.0,
+                    };
+                }
+            }
+            let from = mix.frame.len();
+            let row = self.next_output_sample_pos as usize * from;
+            let value = mix.gains[row..row + from]
+                .iter()
+                .zip(mix.frame.iter())
+                .map(|(gain, sample)| gain * sample)
+                .sum();
+            self.next_output_sample_pos = (self.next_output_sample_pos + 1) % self.to.get();
+            return Some(value);
+        }
+
         let result = match self.next_output_sample_pos {
             0 => {
                 // save first sample for mono -> stereo conversion
@@ -88,7 +144,12 @@ where
     fn size_hint(&self) -> (usize, Option<usize>) {
         let (min, max) = self.input.size_hint();
 
-        let consumed = std::cmp::min(self.from.get(), self.next_output_sample_pos) as usize;
+        // A mixed frame is read whole before its first sample is returned.
+        let consumed = if self.mix.is_some() && self.next_output_sample_pos > 0 {
+            self.from.get() as usize
+        } else {
+            std::cmp::min(self.from.get(), self.next_output_sample_pos) as usize
+        };
 
         let min = ((min + consumed) / self.from.get() as usize * self.to.get() as usize)
             .saturating_sub(self.next_output_sample_pos as usize);
@@ -104,10 +165,71 @@ where
 
 impl<I> ExactSizeIterator for ChannelCountConverter<I> where I: ExactSizeIterator<Item = Sample> {}
 
+/// The gains of a mix from `from` to `to`, or `None` if mixing by channel index does the same
+/// or `to` is mono.
+fn mix_gains(from: ChannelLayout, to: ChannelLayout) -> Option<Box<[Sample]>> {
+    use ChannelPosition::*;
+
+    if from == to || to == ChannelLayout::Mono {
+        return None;
+    }
+
+    let inputs = from.positions().len();
+    let mut gains = vec![0.0; inputs * to.positions().len()];
+    for (input, position) in from.positions().iter().enumerate() {
+        let mut add = |output, gain| match to.channel_of(output) {
+            Some(output) => {
+                gains[output * inputs + input] += gain;
+                true
+            }
+            None => false,
+        };
+        if add(*position, 1.0) {
+            continue;
+        }
+        match position {
+            Mono => {
+                if !add(FrontCenter, 1.0) {
+                    add(FrontLeft, 1.0);
+                    add(FrontRight, 1.0);
+                }
+            }
+            FrontCenter => {
+                add(FrontLeft, FRAC_1_SQRT_2);
+                add(FrontRight, FRAC_1_SQRT_2);
+            }
+            BackLeft | SideLeft => {
+                let other = if *position == BackLeft {
+                    SideLeft
+                } else {
+                    BackLeft
+                };
+                if !add(other, 1.0) {
+                    add(FrontLeft, FRAC_1_SQRT_2);
+                }
+            }
+            BackRight | SideRight => {
+                let other = if *position == BackRight {
+                    SideRight
+                } else {
+                    BackRight
+                };
+                if !add(other, 1.0) {
+                    add(FrontRight, FRAC_1_SQRT_2);
+                }
+            }
+            // Every layout but mono has the front left and right.
+            LowFrequency | FrontLeft | FrontRight => {}
+        }
+    }
+    Some(gains.into())
+}
+
 #[cfg(test)]
 mod test {
     use super::ChannelCountConverter;
     use crate::common::ChannelCount;
+    use crate::common::ChannelLayout::{Stereo, Surround5_1};
     use crate::math::nz;
     use crate::Sample;
 
@@ -142,6 +264,49 @@ mod test {
         assert_eq!(output, [1.0, 2.0, 0.0, 0.0, 3.0, 4.0, 0.0, 0.0]);
     }
 
+    #[test]
+    fn mix_to_layout() {
+        use std::f32::consts::FRAC_1_SQRT_2 as C;
+
+        let input = vec![1.0, 2.0];
+        let output = ChannelCountConverter::with_layout(input.into_iter(), nz!(1), Surround5_1)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            output,
+            [0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 0.0]
+        );
+
+        let input = vec![1.0, 2.0];
+        let output = ChannelCountConverter::with_layout(input.into_iter(), nz!(2), Surround5_1)
+            .collect::<Vec<_>>();
+        assert_eq!(output, [1.0, 2.0, 0.0, 0.0, 0.0, 0.0]);
+
+        // Front left, front right, center, LFE, back left, back right.
+        let input = vec![1.0, 2.0, 4.0, 8.0, 16.0, 32.0];
+        let output = ChannelCountConverter::with_layout(input.into_iter(), nz!(6), Stereo)
+            .collect::<Vec<_>>();
+        assert_eq!(output, [1.0 + C * 4.0 + C * 16.0, 2.0 + C * 4.0 + C * 32.0]);
+
+        // Without a known layout the channels are converted by index.
+        let input = vec![1.0, 2.0, 3.0];
+        let output = ChannelCountConverter::with_layout(input.into_iter(), nz!(3), Stereo)
+            .collect::<Vec<_>>();
+        assert_eq!(output, [1.0, 2.0]);
+    }
+
+    #[test]
+    fn mixed_size_hint() {
+        let input = [
+            1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0,
+        ];
+        let mut converter =
+            ChannelCountConverter::with_layout(input.iter().copied(), nz!(6), Stereo);
+        for left in (0..=4).rev() {
+            assert_eq!(converter.size_hint(), (left, Some(left)));
+            converter.next();
+        }
+    }
+
     #[test]
     fn size_hint() {
         fn test(input: &[Sample], from: ChannelCount, to: ChannelCount) {
@@ -0,0 +1,484 @@
+//! Feeds bytes produced by async code, such as an HTTP response body, to a [`Decoder`].
+//!
+//! This does not depend on any async runtime or `AsyncRead` trait. The async side copies the
+//! chunks it receives into an [`AsyncStreamSender`], which waits whenever the buffer is full.
+//! A background thread decodes them from the matching [`AsyncStreamReader`] into a small
+//! queue of samples, played by an [`AsyncStreamDecoder`] without ever blocking the audio
+//! thread.
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TryRecvError};
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+use super::{Decoder, DecoderError};
+use crate::common::{ChannelCount, SampleRate};
+use crate::spawn_blocking::spawn_blocking;
+use crate::{Sample, Source};
+
+/// Frames per chunk of decoded samples.
+const CHUNK_FRAMES: usize = 1024;
+/// Chunks decoded ahead of playback, about 0.4 seconds at 44.1 kHz.
+const QUEUED_CHUNKS: usize = 16;
+
+/// Creates a byte stream from async code to a decoder, buffering up to `capacity` bytes.
+///
+/// The buffer decides how much network jitter playback survives: once it runs dry the
+/// [`AsyncStreamDecoder`] plays silence until more bytes arrive, so it should hold a few
+/// seconds of audio.
+///
+/// # Panics
+///
+/// Panics if `capacity` is zero.
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn example(mut body: Vec<Vec<u8>>) -> Result<(), Box<dyn std::error::Error>> {
+/// use rodio::decoder::async_stream;
+///
+/// let (sender, reader) = async_stream(256 * 1024);
+/// let feed = async move {
+///     // Chunks of an HTTP body, read with whichever client the app uses.
+///     for chunk in body.drain(..) {
+///         sender.send(&chunk).await?;
+///     }
+///     // Dropping the sender ends the stream.
+///     Ok::<(), std::io::Error>(())
+/// };
+/// // Run `feed` concurrently, for example by spawning it on the runtime, then:
+/// let decoder = reader.into_decoder().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn async_stream(capacity: usize) -> (AsyncStreamSender, AsyncStreamReader) {
+    assert!(capacity > 0, "the capacity must be greater than zero");
+    let shared = Arc::new(Shared {
+        buffer: Mutex::new(Buffer {
+            bytes: VecDeque::with_capacity(capacity),
+            sender_done: false,
+            reader_done: false,
+            waker: None,
+        }),
+        readable: Condvar::new(),
+        capacity,
+        retain: AtomicBool::new(true),
+    });
+    let reader = AsyncStreamReader {
+        shared: shared.clone(),
+        history: Vec::new(),
+        history_start: 0,
+        pos: 0,
+    };
+    (AsyncStreamSender { shared }, reader)
+}
+
+struct Shared {
+    buffer: Mutex<Buffer>,
+    readable: Condvar,
+    capacity: usize,
+    // Whether the reader keeps what it read, so the format can be sniffed and rewound.
+    retain: AtomicBool,
+}
+
+struct Buffer {
+    bytes: VecDeque<u8>,
+    sender_done: bool,
+    reader_done: bool,
+    // Wakes a sender waiting for room.
+    waker: Option<Waker>,
+}
+
+/// The async end of an [`async_stream`], dropping it ends the stream.
+pub struct AsyncStreamSender {
+    shared: Arc<Shared>,
+}
+
+impl AsyncStreamSender {
+    /// Queues `bytes`, waiting while the buffer is full.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`io::ErrorKind::BrokenPipe`] error once the reader is dropped.
+    pub fn send<'a>(&'a self, bytes: &'a [u8]) -> SendFuture<'a> {
+        SendFuture {
+            shared: &self.shared,
+            bytes,
+        }
+    }
+}
+
+impl Drop for AsyncStreamSender {
+    fn drop(&mut self) {
+        self.shared.buffer.lock().unwrap().sender_done = true;
+        self.shared.readable.notify_one();
+    }
+}
+
+/// Future returned by [`AsyncStreamSender::send`].
+pub struct SendFuture<'a> {
+    shared: &'a Shared,
+    bytes: &'a [u8],
+}
+
+impl Future for SendFuture<'_> {
+    type Output = io::Result<()>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut buffer = self.shared.buffer.lock().unwrap();
+        if buffer.reader_done {
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+        let room = self.shared.capacity - buffer.bytes.len();
+        let (now, later) = self.bytes.split_at(room.min(self.bytes.len()));
+        buffer.bytes.extend(now);
+        if !now.is_empty() {
+            self.shared.readable.notify_one();
+        }
+        if later.is_empty() {
+            return Poll::Ready(Ok(()));
+        }
+        buffer.waker = Some(cx.waker().clone());
+        drop(buffer);
+        self.bytes = later;
+        Poll::Pending
+    }
+}
+
+/// The blocking end of an [`async_stream`], read by a decoder.
+///
+/// Reads block until the sender provides bytes or is dropped, so do not read it on the audio
+/// thread, [`AsyncStreamReader::into_decoder`] reads it on a thread of its own. Seeking only
+/// works within the bytes read while the format is detected.
+pub struct AsyncStreamReader {
+    shared: Arc<Shared>,
+    // Bytes kept for rewinding, starting at stream position `history_start`.
+    history: Vec<u8>,
+    history_start: u64,
+    pos: u64,
+}
+
+impl AsyncStreamReader {
+    /// Detects the format on a separate thread, so the async runtime does not block while the
+    /// first bytes arrive, then keeps decoding on that thread in the background.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DecoderError::UnrecognizedFormat` if the audio format could not be determined
+    /// or is not supported.
+    pub async fn into_decoder(self) -> Result<AsyncStreamDecoder, DecoderError> {
+        let shared = self.shared.clone();
+        let decoder = spawn_blocking(move || Decoder::builder().with_data(self).build()).await?;
+        // The format is known, stop keeping bytes to rewind to.
+        shared.retain.store(false, Ordering::Relaxed);
+
+        let (chunk_tx, chunks) = mpsc::sync_channel(QUEUED_CHUNKS);
+        // Every chunk fits, see `AsyncStreamDecoder::advance`.
+        let (recycle, recycled) = mpsc::sync_channel(QUEUED_CHUNKS + 2);
+        let current = Chunk {
+            samples: Vec::new(),
+            channels: decoder.channels(),
+            sample_rate: decoder.sample_rate(),
+        };
+        let total_duration = decoder.total_duration();
+        std::thread::Builder::new()
+            .name("rodio async decoder".to_owned())
+            .spawn(move || decode(decoder, chunk_tx, recycled))
+            .map_err(|error| DecoderError::IoError(error.to_string()))?;
+
+        let mut decoder = AsyncStreamDecoder {
+            chunks,
+            recycle,
+            current,
+            pos: 0,
+            silence: 0,
+            ended: false,
+            total_duration,
+        };
+        decoder.advance();
+        Ok(decoder)
+    }
+
+    fn history_end(&self) -> u64 {
+        self.history_start + self.history.len() as u64
+    }
+}
+
+impl Read for AsyncStreamReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos < self.history_end() {
+            let kept = &self.history[(self.pos - self.history_start) as usize..];
+            let len = kept.len().min(buf.len());
+            buf[..len].copy_from_slice(&kept[..len]);
+            self.pos += len as u64;
+            return Ok(len);
+        }
+        let retain = self.shared.retain.load(Ordering::Relaxed);
+        if !retain && !self.history.is_empty() {
+            self.history = Vec::new();
+            self.history_start = self.pos;
+        }
+
+        let mut buffer = self.shared.buffer.lock().unwrap();
+        while buffer.bytes.is_empty() && !buffer.sender_done {
+            buffer = self.shared.readable.wait(buffer).unwrap();
+        }
+        let len = buffer.bytes.len().min(buf.len());
+        for (dest, byte) in buf.iter_mut().zip(buffer.bytes.drain(..len)) {
+            *dest = byte;
+        }
+        if let Some(waker) = buffer.waker.take() {
+            waker.wake();
+        }
+        drop(buffer);
+
+        if retain {
+            self.history.extend_from_slice(&buf[..len]);
+        } else {
+            self.history_start += len as u64;
+        }
+        self.pos += len as u64;
+        Ok(len)
+    }
+}
+
+impl Seek for AsyncStreamReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(pos) => Some(pos),
+            SeekFrom::Current(offset) => self.pos.checked_add_signed(offset),
+            SeekFrom::End(_) => None,
+        };
+        match target {
+            Some(target) if (self.history_start..=self.history_end()).contains(&target) => {
+                self.pos = target;
+                Ok(target)
+            }
+            _ => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "an async stream can only seek within the bytes read to detect the format",
+            )),
+        }
+    }
+}
+
+impl Drop for AsyncStreamReader {
+    fn drop(&mut self) {
+        let mut buffer = self.shared.buffer.lock().unwrap();
+        buffer.reader_done = true;
+        if let Some(waker) = buffer.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+struct Chunk {
+    samples: Vec<Sample>,
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+}
+
+/// Decodes `decoder` into chunks until it ends or the [`AsyncStreamDecoder`] is dropped.
+fn decode(
+    mut decoder: Decoder<AsyncStreamReader>,
+    chunks: SyncSender<Chunk>,
+    recycled: Receiver<Chunk>,
+) {
+    loop {
+        let mut chunk = recycled.try_recv().unwrap_or_else(|_| Chunk {
+            samples: Vec::new(),
+            channels: decoder.channels(),
+            sample_rate: decoder.sample_rate(),
+        });
+        chunk.samples.clear();
+        chunk.channels = decoder.channels();
+        chunk.sample_rate = decoder.sample_rate();
+        // A chunk never spans a format change.
+        let max_len = CHUNK_FRAMES * chunk.channels.get() as usize;
+        let len = decoder
+            .current_span_len()
+            .filter(|len| *len > 0)
+            .map_or(max_len, |len| len.min(max_len));
+        chunk.samples.extend(decoder.by_ref().take(len));
+        if chunk.samples.is_empty() || chunks.send(chunk).is_err() {
+            return;
+        }
+    }
+}
+
+/// Plays the audio of an [`async_stream`], returned by [`AsyncStreamReader::into_decoder`].
+///
+/// Decoding happens on a background thread, a few hundred milliseconds ahead of playback.
+/// Reading samples never blocks: if the sender does not keep up the decoder plays silence
+/// until samples are available again. It ends once the sender was dropped and all its bytes
+/// are played.
+///
+/// The decoder can not seek and its total duration is usually unknown.
+pub struct AsyncStreamDecoder {
+    chunks: Receiver<Chunk>,
+    // Played chunks are sent back so the audio thread neither frees nor allocates memory.
+    recycle: SyncSender<Chunk>,
+    current: Chunk,
+    pos: usize,
+    // Samples of silence left to play after the queue ran dry, a frame at a time.
+    silence: usize,
+    ended: bool,
+    total_duration: Option<Duration>,
+}
+
+impl AsyncStreamDecoder {
+    /// Moves to the next chunk once `current` is played, without waiting for it.
+    fn advance(&mut self) {
+        match self.chunks.try_recv() {
+            Ok(chunk) => {
+                let played = std::mem::replace(&mut self.current, chunk);
+                self.pos = 0;
+                // At most `QUEUED_CHUNKS + 2` chunks exist as the decoding thread only
+                // allocates one when none are recycled, so this never fails.
+                let _ = self.recycle.try_send(played);
+            }
+            Err(TryRecvError::Empty) => self.silence = self.current.channels.get() as usize,
+            Err(TryRecvError::Disconnected) => self.ended = true,
+        }
+    }
+}
+
+impl Iterator for AsyncStreamDecoder {
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        if self.silence > 0 {
+            self.silence -= 1;
+            if self.silence == 0 {
+                self.advance();
+            }
+            return Some(0.0);
+        }
+        if self.ended {
+            return None;
+        }
+        let sample = *self.current.samples.get(self.pos)?;
+        self.pos += 1;
+        if self.pos == self.current.samples.len() {
+            self.advance();
+        }
+        Some(sample)
+    }
+}
+
+impl Source for AsyncStreamDecoder {
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        if self.silence > 0 {
+            Some(self.silence)
+        } else if self.ended {
+            Some(0)
+        } else {
+            Some(self.current.samples.len() - self.pos)
+        }
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.current.channels
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.current.sample_rate
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.total_duration
+    }
+}
+
+#[cfg(all(test, any(feature = "hound", feature = "symphonia-wav")))]
+mod tests {
+    use std::future::Future;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+    use std::thread::Thread;
+
+    use super::async_stream;
+    use crate::{Decoder, Sink, Source};
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(value) => return value,
+                Poll::Pending => std::thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    fn plays_bytes_sent_from_async_code() {
+        let file = std::fs::read("assets/music.wav").unwrap();
+        let expected: Vec<f32> =
+            Decoder::try_from(std::fs::File::open("assets/music.wav").unwrap())
+                .unwrap()
+                .take(20_000)
+                .collect();
+
+        // A buffer smaller than the file makes the sender wait for the reader.
+        let (sender, reader) = async_stream(16 * 1024);
+        let feeder = std::thread::spawn(move || {
+            block_on(async {
+                for chunk in file.chunks(5000) {
+                    // Fails once playback stopped and dropped the reader.
+                    if sender.send(chunk).await.is_err() {
+                        break;
+                    }
+                }
+            })
+        });
+
+        let decoder = block_on(reader.into_decoder()).unwrap();
+        assert_eq!(decoder.channels().get(), 2);
+        let (sink, queue_rx) = Sink::new();
+        sink.append(decoder);
+        // Reading faster than the decoder produces plays silence in between.
+        let played: Vec<f32> = queue_rx.filter(|s| *s != 0.0).take(20_000).collect();
+        let expected: Vec<f32> = expected.into_iter().filter(|s| *s != 0.0).collect();
+        assert_eq!(played[..1000], expected[..1000]);
+
+        sink.stop();
+        drop(sink);
+        feeder.join().unwrap();
+    }
+
+    #[test]
+    fn plays_silence_while_waiting_for_bytes() {
+        let file = std::fs::read("assets/music.wav").unwrap();
+        let (sender, reader) = async_stream(64 * 1024);
+        block_on(sender.send(&file[..64 * 1024])).unwrap();
+
+        let mut decoder = block_on(reader.into_decoder()).unwrap();
+        // The sender is alive but sends nothing more, reading must not block.
+        let tail: Vec<f32> = decoder.by_ref().take(1_000_000).skip(999_000).collect();
+        assert_eq!(tail.len(), 1000);
+        assert!(tail.iter().all(|s| *s == 0.0));
+
+        drop(sender);
+        assert_eq!(decoder.count() % 2, 0);
+    }
+}
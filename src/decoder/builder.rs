@@ -36,7 +36,12 @@
 //! - `seekable` - Whether seeking operations are enabled
 //! - `gapless` - Enable gapless playback
 //! - `coarse_seek` - Use faster but less precise seeking
+//! - `gap_callback` - Report gaps between the decoded packets
+//!
+//! Decoders for formats rodio does not support can be added with
+//! [`DecoderBuilder::with_custom_decoder`].
 
+use std::fmt;
 use std::io::{Read, Seek};
 
 #[cfg(feature = "symphonia")]
@@ -77,6 +82,34 @@ pub struct Settings {
 
     /// Whether the decoder should report as seekable.
     pub(crate) is_seekable: bool,
+
+    /// Called when the timestamps of consecutive packets show that audio is missing.
+    #[cfg(feature = "symphonia")]
+    pub(crate) gap_callback: Option<GapCallback>,
+}
+
+/// A part of the stream that is missing between two decoded packets, see
+/// [`DecoderBuilder::with_gap_callback`].
+#[cfg(feature = "symphonia")]
+#[cfg_attr(docsrs, doc(cfg(feature = "symphonia")))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecodeGap {
+    /// Position in the stream where the missing part starts, according to the timestamps.
+    pub position: Duration,
+    /// Length of the missing part.
+    pub missing: Duration,
+}
+
+/// The callback of [`DecoderBuilder::with_gap_callback`].
+#[cfg(feature = "symphonia")]
+#[derive(Clone)]
+pub(crate) struct GapCallback(pub(crate) Arc<dyn Fn(DecodeGap) + Send + Sync>);
+
+#[cfg(feature = "symphonia")]
+impl fmt::Debug for GapCallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GapCallback").finish_non_exhaustive()
+    }
 }
 
 impl Default for Settings {
@@ -88,10 +121,39 @@ impl Default for Settings {
             hint: None,
             mime_type: None,
             is_seekable: false,
+            #[cfg(feature = "symphonia")]
+            gap_callback: None,
         }
     }
 }
 
+/// Number of bytes at the start of the stream passed to the sniffer of a custom decoder.
+pub const SNIFF_LEN: usize = 64;
+
+type Sniffer = Arc<dyn Fn(&[u8]) -> bool + Send + Sync>;
+type Factory<R> = Arc<dyn Fn(R) -> Result<Box<dyn Source + Send>, DecoderError> + Send + Sync>;
+
+/// A decoder registered with [`DecoderBuilder::with_custom_decoder`].
+struct CustomDecoder<R> {
+    sniff: Sniffer,
+    build: Factory<R>,
+}
+
+impl<R> Clone for CustomDecoder<R> {
+    fn clone(&self) -> Self {
+        Self {
+            sniff: self.sniff.clone(),
+            build: self.build.clone(),
+        }
+    }
+}
+
+impl<R> fmt::Debug for CustomDecoder<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomDecoder").finish_non_exhaustive()
+    }
+}
+
 /// Builder for configuring and creating a decoder.
 ///
 /// This provides a flexible way to configure decoder settings before creating
@@ -121,6 +183,8 @@ pub struct DecoderBuilder<R> {
     data: Option<R>,
     /// Configuration settings for the decoder.
     settings: Settings,
+    /// Decoders tried before the built-in ones.
+    custom_decoders: Vec<CustomDecoder<R>>,
 }
 
 impl<R> Default for DecoderBuilder<R> {
@@ -128,6 +192,7 @@ impl<R> Default for DecoderBuilder<R> {
         Self {
             data: None,
             settings: Settings::default(),
+            custom_decoders: Vec::new(),
         }
     }
 }
@@ -263,9 +328,93 @@ impl<R: Read + Seek + Send + Sync + 'static> DecoderBuilder<R> {
         self
     }
 
+    /// Calls `callback` whenever a packet starts later than the previous one ended, which
+    /// means audio was dropped, for example by a damaged or badly cut file. Disabled by
+    /// default.
+    ///
+    /// Only available with the `symphonia` feature, the other decoders do not report gaps.
+    /// Only formats whose container carries timestamps, such as Ogg and MP4, can be checked.
+    /// Packets that fail to decode are skipped and so are reported as gaps too. Jumps caused
+    /// by seeking are not reported. The callback runs on the thread decoding the audio,
+    /// usually the audio thread, so it should return quickly.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::fs::File;
+    /// use rodio::Decoder;
+    ///
+    /// let decoder = Decoder::builder()
+    ///     .with_data(File::open("live.ogg").unwrap())
+    ///     .with_gap_callback(|gap| {
+    ///         eprintln!("{:?} of audio missing at {:?}", gap.missing, gap.position)
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "symphonia")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "symphonia")))]
+    pub fn with_gap_callback<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(DecodeGap) + Send + Sync + 'static,
+    {
+        self.settings.gap_callback = Some(GapCallback(Arc::new(callback)));
+        self
+    }
+
+    /// Adds a decoder for a format that is not supported by rodio.
+    ///
+    /// Before the built-in decoders are tried, `sniff` is called with up to the first
+    /// [`SNIFF_LEN`] bytes of the stream. If it returns `true` the stream, positioned back at
+    /// its start, is passed to `build` which returns the decoded source. The first matching
+    /// custom decoder is used, the built-in decoders are not tried after it.
+    ///
+    /// Looped decoders restart a custom source by seeking it to the start.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::fs::File;
+    /// use rodio::buffer::SamplesBuffer;
+    /// use rodio::decoder::DecoderError;
+    /// use rodio::{Decoder, Source};
+    ///
+    /// fn decode_wavpack(file: File) -> Result<Box<dyn Source + Send>, DecoderError> {
+    ///     // Call into a WavPack library here.
+    ///     # let _ = file;
+    ///     # let samples = vec![0.0; 2];
+    ///     let channels = std::num::NonZero::new(2).unwrap();
+    ///     let sample_rate = std::num::NonZero::new(44100).unwrap();
+    ///     Ok(Box::new(SamplesBuffer::new(channels, sample_rate, samples)))
+    /// }
+    ///
+    /// let decoder = Decoder::builder()
+    ///     .with_data(File::open("audio.wv").unwrap())
+    ///     .with_custom_decoder(|head| head.starts_with(b"wvpk"), decode_wavpack)
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn with_custom_decoder<S, F>(mut self, sniff: S, build: F) -> Self
+    where
+        S: Fn(&[u8]) -> bool + Send + Sync + 'static,
+        F: Fn(R) -> Result<Box<dyn Source + Send>, DecoderError> + Send + Sync + 'static,
+    {
+        self.custom_decoders.push(CustomDecoder {
+            sniff: Arc::new(sniff),
+            build: Arc::new(build),
+        });
+        self
+    }
+
     /// Creates the decoder implementation with configured settings.
     fn build_impl(self) -> Result<(DecoderImpl<R>, Settings), DecoderError> {
-        let data = self.data.ok_or(DecoderError::UnrecognizedFormat)?;
+        let mut data = self.data.ok_or(DecoderError::UnrecognizedFormat)?;
+
+        if !self.custom_decoders.is_empty() {
+            let head = read_head(&mut data).map_err(|e| DecoderError::IoError(e.to_string()))?;
+            if let Some(custom) = self.custom_decoders.iter().find(|c| (c.sniff)(&head)) {
+                let source = (custom.build)(data)?;
+                return Ok((DecoderImpl::Custom(source, PhantomData), self.settings));
+            }
+        }
 
         #[cfg(all(feature = "hound", not(feature = "symphonia-wav")))]
         let data = match wav::WavDecoder::new(data) {
@@ -302,7 +451,10 @@ impl<R: Read + Seek + Send + Sync + 'static> DecoderBuilder<R> {
         }
 
         #[cfg(not(feature = "symphonia"))]
-        Err(DecoderError::UnrecognizedFormat)
+        {
+            let _ = data;
+            Err(DecoderError::UnrecognizedFormat)
+        }
     }
 
     /// Creates a new decoder with previously configured settings.
@@ -330,3 +482,14 @@ impl<R: Read + Seek + Send + Sync + 'static> DecoderBuilder<R> {
         })
     }
 }
+
+/// Reads the start of the stream for sniffing, then seeks back to where it was.
+fn read_head<R: Read + Seek>(data: &mut R) -> std::io::Result<Vec<u8>> {
+    let start = data.stream_position()?;
+    let mut head = Vec::with_capacity(SNIFF_LEN);
+    data.by_ref()
+        .take(SNIFF_LEN as u64)
+        .read_to_end(&mut head)?;
+    data.seek(SeekFrom::Start(start))?;
+    Ok(head)
+}
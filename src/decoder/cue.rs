@@ -0,0 +1,329 @@
+//! Cue sheets, which describe the tracks of an album or concert stored in a single file.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use rodio::decoder::{split_by_cue, CueSheet};
+//! use rodio::Decoder;
+//! use std::fs::File;
+//!
+//! let cue = CueSheet::parse(&std::fs::read_to_string("concert.cue").unwrap()).unwrap();
+//! let file = File::open(cue.file.as_deref().unwrap()).unwrap();
+//! let decoder = Decoder::try_from(file).unwrap();
+//! let segments = split_by_cue(&decoder, &cue);
+//! // Play the third track.
+//! let track = segments[2].play(decoder).unwrap();
+//! ```
+
+use std::time::Duration;
+
+use crate::source::{SeekError, TakeDuration};
+use crate::Source;
+
+/// The frames of a cue sheet timestamp, a CD sector, per second.
+const FRAMES_PER_SECOND: u64 = 75;
+
+/// A parsed cue sheet. Only sheets that refer to a single audio file are supported.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CueSheet {
+    /// The audio file the tracks are in, as written in the sheet.
+    pub file: Option<String>,
+    /// Title of the whole recording.
+    pub title: Option<String>,
+    /// Performer of the whole recording.
+    pub performer: Option<String>,
+    /// The tracks, in the order of the sheet.
+    pub tracks: Vec<CueTrack>,
+}
+
+/// A track of a [`CueSheet`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CueTrack {
+    /// Track number as written in the sheet.
+    pub number: u32,
+    /// Title of the track.
+    pub title: Option<String>,
+    /// Performer of the track.
+    pub performer: Option<String>,
+    /// Position of the track in the file, from its `INDEX 01`. A pregap before it, marked by
+    /// `INDEX 00`, belongs to the previous track.
+    pub start: Duration,
+}
+
+/// Errors that can occur when parsing a cue sheet.
+#[derive(Debug, thiserror::Error, Clone, PartialEq, Eq)]
+pub enum CueError {
+    /// A `TRACK` or `INDEX` command could not be parsed. Holds the line number, starting at 1.
+    #[error("Line {0} of the cue sheet is malformed")]
+    InvalidLine(usize),
+
+    /// A track has no `INDEX 01`. Holds the track number.
+    #[error("Track {0} of the cue sheet has no start index")]
+    MissingIndex(u32),
+
+    /// The sheet refers to more than one audio file.
+    #[error("The cue sheet refers to more than one file")]
+    MultipleFiles,
+}
+
+impl CueSheet {
+    /// Parses the text of a cue sheet.
+    ///
+    /// Commands that do not affect the track boundaries, such as `REM` or `FLAGS`, are
+    /// ignored.
+    pub fn parse(text: &str) -> Result<CueSheet, CueError> {
+        let mut sheet = CueSheet::default();
+        // The track being parsed, with its start if known.
+        let mut track: Option<(CueTrack, Option<Duration>)> = None;
+
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            let (command, args) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let args = args.trim();
+            match command.to_ascii_uppercase().as_str() {
+                "FILE" => {
+                    if sheet.file.is_some() {
+                        return Err(CueError::MultipleFiles);
+                    }
+                    sheet.file = Some(file_name(args));
+                }
+                "TITLE" => match &mut track {
+                    Some((track, _)) => track.title = Some(quoted(args)),
+                    None => sheet.title = Some(quoted(args)),
+                },
+                "PERFORMER" => match &mut track {
+                    Some((track, _)) => track.performer = Some(quoted(args)),
+                    None => sheet.performer = Some(quoted(args)),
+                },
+                "TRACK" => {
+                    if let Some(finished) = track.take() {
+                        sheet.tracks.push(finish(finished)?);
+                    }
+                    let number = args
+                        .split_whitespace()
+                        .next()
+                        .and_then(|number| number.parse().ok())
+                        .ok_or(CueError::InvalidLine(line_number))?;
+                    let new = CueTrack {
+                        number,
+                        title: None,
+                        performer: None,
+                        start: Duration::ZERO,
+                    };
+                    track = Some((new, None));
+                }
+                "INDEX" => {
+                    let mut args = args.split_whitespace();
+                    let (Some(number), Some(time), None) = (args.next(), args.next(), args.next())
+                    else {
+                        return Err(CueError::InvalidLine(line_number));
+                    };
+                    let number: u32 = number
+                        .parse()
+                        .map_err(|_| CueError::InvalidLine(line_number))?;
+                    let time = timestamp(time).ok_or(CueError::InvalidLine(line_number))?;
+                    let Some((_, start)) = &mut track else {
+                        return Err(CueError::InvalidLine(line_number));
+                    };
+                    if number == 1 {
+                        *start = Some(time);
+                    }
+                }
+                _ => {}
+            }
+        }
+        if let Some(finished) = track {
+            sheet.tracks.push(finish(finished)?);
+        }
+        Ok(sheet)
+    }
+}
+
+/// Sets the start of a track once all its commands have been parsed.
+fn finish((mut track, start): (CueTrack, Option<Duration>)) -> Result<CueTrack, CueError> {
+    track.start = start.ok_or(CueError::MissingIndex(track.number))?;
+    Ok(track)
+}
+
+/// Removes the quotes around an argument, if any.
+fn quoted(arg: &str) -> String {
+    arg.strip_prefix('"')
+        .and_then(|arg| arg.strip_suffix('"'))
+        .unwrap_or(arg)
+        .to_owned()
+}
+
+/// Returns the file name of the arguments of a `FILE` command, which end with the file type.
+fn file_name(args: &str) -> String {
+    match args.strip_prefix('"').and_then(|args| args.split_once('"')) {
+        Some((name, _)) => name.to_owned(),
+        None => args
+            .rsplit_once(char::is_whitespace)
+            .map_or(args, |(name, _)| name.trim_end())
+            .to_owned(),
+    }
+}
+
+/// Parses a `mm:ss:ff` timestamp, with 75 frames per second.
+fn timestamp(time: &str) -> Option<Duration> {
+    let mut parts = time.split(':').map(|part| part.parse::<u64>().ok());
+    let (Some(Some(minutes)), Some(Some(seconds)), Some(Some(frames)), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return None;
+    };
+    if seconds >= 60 || frames >= FRAMES_PER_SECOND {
+        return None;
+    }
+    let frames = (minutes * 60 + seconds) * FRAMES_PER_SECOND + frames;
+    Some(Duration::from_nanos(
+        frames * 1_000_000_000 / FRAMES_PER_SECOND,
+    ))
+}
+
+/// A part of a file holding one track of a [`CueSheet`], see [`split_by_cue`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CueSegment {
+    /// The track the segment holds.
+    pub track: CueTrack,
+    /// Where the segment starts in the file.
+    pub start: Duration,
+    /// Where the segment ends in the file, `None` if it is the last and the length of the
+    /// file is unknown.
+    pub end: Option<Duration>,
+}
+
+impl CueSegment {
+    /// Returns the length of the segment, if known.
+    pub fn duration(&self) -> Option<Duration> {
+        self.end.map(|end| end.saturating_sub(self.start))
+    }
+
+    /// Seeks `source`, which plays the file of the cue sheet, to the start of the segment
+    /// and returns it limited to the segment.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `source` can not seek.
+    pub fn play<S>(&self, mut source: S) -> Result<TakeDuration<S>, SeekError>
+    where
+        S: Source,
+    {
+        source.try_seek(self.start)?;
+        Ok(source.take_duration(self.duration().unwrap_or(Duration::MAX)))
+    }
+}
+
+/// Returns the segments of the tracks of `cue` in `source`, the file the sheet refers to.
+///
+/// Each segment ends where the next track starts, the last one ends at the end of `source`.
+/// Play a segment with [`CueSegment::play`]. A decoder plays one segment at a time, open the
+/// file again to play several at once.
+pub fn split_by_cue<S>(source: &S, cue: &CueSheet) -> Vec<CueSegment>
+where
+    S: Source,
+{
+    let ends = cue
+        .tracks
+        .iter()
+        .skip(1)
+        .map(|next| Some(next.start))
+        .chain([source.total_duration()]);
+    cue.tracks
+        .iter()
+        .zip(ends)
+        .map(|(track, end)| CueSegment {
+            track: track.clone(),
+            start: track.start,
+            end,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{split_by_cue, CueError, CueSheet};
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::Source;
+
+    const SHEET: &str = r#"REM GENRE Live
+PERFORMER "The Band"
+TITLE "Live at the Hall"
+FILE "concert.flac" WAVE
+  TRACK 01 AUDIO
+    TITLE "Opening"
+    INDEX 01 00:00:00
+  TRACK 02 AUDIO
+    TITLE "Encore"
+    PERFORMER "The Band & Guest"
+    INDEX 00 00:02:50
+    INDEX 01 00:03:00
+"#;
+
+    #[test]
+    fn segments_match_index_times() {
+        let cue = CueSheet::parse(SHEET).unwrap();
+        assert_eq!(cue.file.as_deref(), Some("concert.flac"));
+        assert_eq!(cue.title.as_deref(), Some("Live at the Hall"));
+        assert_eq!(cue.tracks.len(), 2);
+        assert_eq!(cue.tracks[1].number, 2);
+        assert_eq!(cue.tracks[1].title.as_deref(), Some("Encore"));
+        assert_eq!(cue.tracks[1].performer.as_deref(), Some("The Band & Guest"));
+
+        // Five seconds of stereo at 1 kHz, counting up frame by frame.
+        let samples: Vec<f32> = (0..5000).flat_map(|i| [i as f32, i as f32]).collect();
+        let source = SamplesBuffer::new(nz!(2), nz!(1000), samples);
+        let segments = split_by_cue(&source, &cue);
+        assert_eq!(segments[0].start, Duration::ZERO);
+        assert_eq!(segments[0].end, Some(Duration::from_secs(3)));
+        assert_eq!(segments[1].start, Duration::from_secs(3));
+        assert_eq!(segments[1].end, Some(Duration::from_secs(5)));
+
+        let second: Vec<f32> = segments[1].play(source).unwrap().collect();
+        assert_eq!(second.len(), 2 * 2000);
+        assert_eq!(second[0], 3000.0);
+        assert_eq!(second[second.len() - 1], 4999.0);
+    }
+
+    #[test]
+    fn last_segment_without_end_plays_to_the_end() {
+        let cue = CueSheet::parse(SHEET).unwrap();
+        let samples: Vec<f32> = (0..5000).flat_map(|i| [i as f32, i as f32]).collect();
+        let source = SamplesBuffer::new(nz!(2), nz!(1000), samples);
+        let mut segment = split_by_cue(&source, &cue).remove(1);
+        segment.end = None;
+
+        let last = segment.play(source).unwrap();
+        assert!(last.current_span_len().is_some());
+        let last: Vec<f32> = last.collect();
+        assert_eq!(last.len(), 2 * 2000);
+        assert_eq!(last[0], 3000.0);
+        assert_eq!(last[last.len() - 1], 4999.0);
+    }
+
+    #[test]
+    fn frames_are_75ths_of_a_second() {
+        let cue = CueSheet::parse("FILE a.wav WAVE\nTRACK 1 AUDIO\nINDEX 01 01:02:15\n").unwrap();
+        assert_eq!(cue.tracks[0].start, Duration::from_millis(62_200));
+    }
+
+    #[test]
+    fn rejects_malformed_sheets() {
+        assert_eq!(
+            CueSheet::parse("TRACK 01 AUDIO\nINDEX 01 00:75:00\n"),
+            Err(CueError::InvalidLine(2))
+        );
+        assert_eq!(
+            CueSheet::parse("TRACK 07 AUDIO\nINDEX 00 00:01:00\n"),
+            Err(CueError::MissingIndex(7))
+        );
+        assert_eq!(
+            CueSheet::parse("FILE a.wav WAVE\nFILE b.wav WAVE\n"),
+            Err(CueError::MultipleFiles)
+        );
+    }
+}
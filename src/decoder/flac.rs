@@ -1,5 +1,6 @@
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{self, Read, Seek, SeekFrom};
 use std::mem;
+use std::sync::Arc;
 use std::time::Duration;
 
 use crate::source::SeekError;
@@ -7,16 +8,39 @@ use crate::Source;
 
 use crate::common::{ChannelCount, Sample, SampleRate};
 
+use claxon::frame::FrameReader;
+use claxon::input::BufferedReader;
 use claxon::FlacReader;
 use dasp_sample::Sample as _;
 use dasp_sample::I24;
 
+/// Metadata block type of a FLAC seek table.
+const SEEKTABLE: u8 = 3;
+/// Sample number used by placeholder seek points.
+const PLACEHOLDER: u64 = u64::MAX;
+
+/// A point from the SEEKTABLE metadata block.
+#[derive(Clone, Copy, Debug)]
+struct SeekPoint {
+    /// Number of the first inter-channel sample in the target frame.
+    sample: u64,
+    /// Offset in bytes of the target frame from the first frame.
+    offset: u64,
+}
+
 /// Decoder for the FLAC format.
+///
+/// Seeking uses the SEEKTABLE of the file, if present, to jump close to the target. Without
+/// one the decoder has to decode all frames from the start of the stream up to the target.
 pub struct FlacDecoder<R>
 where
     R: Read + Seek,
 {
-    reader: FlacReader<R>,
+    // Only `None` while seeking.
+    frames: Option<FrameReader<BufferedReader<R>>>,
+    seek_points: Vec<SeekPoint>,
+    first_frame: u64,
+    total_samples: Option<u64>,
     current_block: Vec<i32>,
     current_block_channel_len: usize,
     current_block_off: usize,
@@ -36,9 +60,21 @@ where
             return Err(data);
         }
 
-        let reader = FlacReader::new(data).expect("should still be flac");
+        let stream_pos = data.stream_position().unwrap_or_default();
+        let Ok((seek_points, first_frame)) = read_metadata(data.by_ref()) else {
+            let _ = data.seek(SeekFrom::Start(stream_pos));
+            return Err(data);
+        };
+        let _ = data.seek(SeekFrom::Start(stream_pos));
 
+        let reader = FlacReader::new(data).expect("should still be flac");
         let spec = reader.streaminfo();
+
+        // Claxon reads ahead, so continue from the first frame with our own frame reader.
+        let mut data = reader.into_inner();
+        if data.seek(SeekFrom::Start(first_frame)).is_err() {
+            return Err(data);
+        }
         let sample_rate = spec.sample_rate;
 
         // `samples` in FLAC means "inter-channel samples" aka frames
@@ -53,7 +89,10 @@ where
         });
 
         Ok(FlacDecoder {
-            reader,
+            frames: Some(FrameReader::new(BufferedReader::new(data))),
+            seek_points,
+            first_frame,
+            total_samples: spec.samples,
             current_block: Vec::with_capacity(
                 spec.max_block_size as usize * spec.channels as usize,
             ),
@@ -74,7 +113,10 @@ where
 
     #[inline]
     pub fn into_inner(self) -> R {
-        self.reader.into_inner()
+        self.frames
+            .expect("only taken while seeking")
+            .into_inner()
+            .into_inner()
     }
 }
 
@@ -102,11 +144,57 @@ where
         self.total_duration
     }
 
-    #[inline]
-    fn try_seek(&mut self, _: Duration) -> Result<(), SeekError> {
-        Err(SeekError::NotSupported {
-            underlying_source: std::any::type_name::<Self>(),
-        })
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        let sample_rate = self.sample_rate.get() as u64;
+        let mut target =
+            pos.as_secs() * sample_rate + pos.subsec_nanos() as u64 * sample_rate / 1_000_000_000;
+        if let Some(total_samples) = self.total_samples {
+            target = target.min(total_samples);
+        }
+
+        let offset = self
+            .seek_points
+            .iter()
+            .rev()
+            .find(|point| point.sample <= target)
+            .map_or(0, |point| point.offset);
+
+        // Remember the current channel, so we can restore it after seeking.
+        let channels = self.channels.get() as usize;
+        let active_channel = self.current_block_off % channels;
+
+        let mut data = self
+            .frames
+            .take()
+            .expect("only taken while seeking")
+            .into_inner()
+            .into_inner();
+        let seek_res = data.seek(SeekFrom::Start(self.first_frame + offset));
+        let mut frames = FrameReader::new(BufferedReader::new(data));
+        self.current_block.clear();
+        self.current_block_off = 0;
+
+        // Decode forward to the frame that contains the target.
+        let mut buffer = mem::take(&mut self.current_block);
+        let res = seek_res
+            .map_err(|e| SeekError::Other(Arc::new(e)))
+            .and_then(|_| loop {
+                match frames.read_next_or_eof(buffer) {
+                    Ok(Some(block)) if block.time() + block.duration() as u64 > target => {
+                        let into_block = (target - block.time()) as usize;
+                        self.current_block_channel_len = block.duration() as usize;
+                        self.current_block_off = into_block * channels + active_channel;
+                        self.current_block = block.into_buffer();
+                        break Ok(());
+                    }
+                    Ok(Some(block)) => buffer = block.into_buffer(),
+                    // Seeked to the end of the stream.
+                    Ok(None) => break Ok(()),
+                    Err(e) => break Err(SeekError::Other(Arc::new(e))),
+                }
+            });
+        self.frames = Some(frames);
+        res
     }
 }
 
@@ -148,7 +236,8 @@ where
             // Load the next block.
             self.current_block_off = 0;
             let buffer = mem::take(&mut self.current_block);
-            match self.reader.blocks().read_next_or_eof(buffer) {
+            let frames = self.frames.as_mut().expect("only taken while seeking");
+            match frames.read_next_or_eof(buffer) {
                 Ok(Some(block)) => {
                     self.current_block_channel_len = (block.len() / block.channels()) as usize;
                     self.current_block = block.into_buffer();
@@ -159,6 +248,43 @@ where
     }
 }
 
+/// Reads the stream header and metadata blocks. Returns the seek table, without placeholders,
+/// and the position of the first frame.
+fn read_metadata<R>(mut data: R) -> io::Result<(Vec<SeekPoint>, u64)>
+where
+    R: Read + Seek,
+{
+    let mut marker = [0; 4];
+    data.read_exact(&mut marker)?;
+
+    let mut seek_points = Vec::new();
+    loop {
+        let mut header = [0; 4];
+        data.read_exact(&mut header)?;
+        let is_last = header[0] & 0x80 != 0;
+        let len = u32::from_be_bytes([0, header[1], header[2], header[3]]);
+
+        if header[0] & 0x7f == SEEKTABLE {
+            let mut point = [0; 18];
+            for _ in 0..len / 18 {
+                data.read_exact(&mut point)?;
+                let sample = u64::from_be_bytes(point[..8].try_into().expect("8 bytes"));
+                let offset = u64::from_be_bytes(point[8..16].try_into().expect("8 bytes"));
+                if sample != PLACEHOLDER {
+                    seek_points.push(SeekPoint { sample, offset });
+                }
+            }
+            data.seek(SeekFrom::Current((len % 18) as i64))?;
+        } else {
+            data.seek(SeekFrom::Current(len as i64))?;
+        }
+
+        if is_last {
+            return Ok((seek_points, data.stream_position()?));
+        }
+    }
+}
+
 /// Returns true if the stream contains FLAC data, then tries to rewind it to where it was.
 fn is_flac<R>(mut data: R) -> bool
 where
@@ -0,0 +1,49 @@
+//! Expansion of G.711 companded samples, as stored in A-law and µ-law WAV files.
+
+use crate::Sample;
+
+/// WAV format tag of A-law samples.
+pub(crate) const FORMAT_ALAW: u16 = 6;
+/// WAV format tag of µ-law samples.
+pub(crate) const FORMAT_MULAW: u16 = 7;
+
+/// Expands one byte of the companded WAV format `format` to a linear sample.
+pub(crate) fn expand(format: u16, code: u8) -> Sample {
+    let linear = if format == FORMAT_ALAW {
+        alaw_to_linear(code)
+    } else {
+        mulaw_to_linear(code)
+    };
+    linear as Sample / 32768.0
+}
+
+/// Expands an A-law code to 16 bit linear PCM.
+fn alaw_to_linear(code: u8) -> i16 {
+    // Even bits are inverted on the line.
+    let code = code ^ 0x55;
+    let mantissa = i16::from(code & 0x0f) << 4;
+    let segment = (code & 0x70) >> 4;
+    let magnitude = match segment {
+        0 => mantissa + 0x8,
+        1 => mantissa + 0x108,
+        _ => (mantissa + 0x108) << (segment - 1),
+    };
+    if code & 0x80 != 0 {
+        magnitude
+    } else {
+        -magnitude
+    }
+}
+
+/// Expands a µ-law code to 16 bit linear PCM.
+fn mulaw_to_linear(code: u8) -> i16 {
+    const BIAS: i16 = 0x84;
+    // All bits are inverted on the line.
+    let code = !code;
+    let magnitude = ((i16::from(code & 0x0f) << 3) + BIAS) << ((code & 0x70) >> 4);
+    if code & 0x80 != 0 {
+        BIAS - magnitude
+    } else {
+        magnitude - BIAS
+    }
+}
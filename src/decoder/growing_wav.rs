@@ -0,0 +1,367 @@
+use std::io::{self, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::g711::{self, FORMAT_ALAW, FORMAT_MULAW};
+use super::DecoderError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::source::SeekError;
+use crate::{Sample, Source};
+
+const FORMAT_PCM: u16 = 1;
+const FORMAT_FLOAT: u16 = 3;
+const FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// Plays a WAV file while it is still being written, like `tail -f`.
+///
+/// When the end of the data is reached the decoder plays silence for the poll interval and
+/// then reads again, it only ends once the `stop` flag is set and all data has been played.
+/// The data length in the WAV header is ignored as writers only update it when they are done.
+/// Waiting never blocks, so the decoder can be played on a device like any other source.
+///
+/// Integer PCM of 8 to 32 bits, 32 or 64 bit float and A-law or µ-law samples are supported.
+///
+/// # Examples
+/// ```no_run
+/// use std::fs::File;
+/// use std::sync::atomic::AtomicBool;
+/// use std::sync::Arc;
+/// use rodio::decoder::GrowingWavDecoder;
+///
+/// let stop = Arc::new(AtomicBool::new(false));
+/// let file = File::open("recording.wav").unwrap();
+/// let decoder = GrowingWavDecoder::new(file, stop.clone()).unwrap();
+/// ```
+pub struct GrowingWavDecoder<R> {
+    data: R,
+    stop: Arc<AtomicBool>,
+    poll_interval: Duration,
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+    format: u16,
+    bytes_per_sample: usize,
+    // Bytes of the next frame read so far.
+    pending: Vec<u8>,
+    frame: Vec<Sample>,
+    frame_pos: usize,
+    // Samples of silence left to play before reading again.
+    silence: usize,
+}
+
+impl<R: Read> GrowingWavDecoder<R> {
+    /// Reads the WAV header, waiting for it to be written if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DecoderError::UnrecognizedFormat` if the data is not a WAV file with a
+    /// supported sample format, or if `stop` is set before the header was complete.
+    pub fn new(mut data: R, stop: Arc<AtomicBool>) -> Result<Self, DecoderError> {
+        let poll_interval = Duration::from_millis(10);
+        let mut header = HeaderReader {
+            data: &mut data,
+            stop: &stop,
+            poll_interval,
+        };
+        let (format, channels, sample_rate, bits) = header.read()?;
+
+        let channels = ChannelCount::new(channels).ok_or(DecoderError::UnrecognizedFormat)?;
+        let sample_rate = SampleRate::new(sample_rate).ok_or(DecoderError::UnrecognizedFormat)?;
+        let supported = match format {
+            FORMAT_PCM => matches!(bits, 8 | 16 | 24 | 32),
+            FORMAT_FLOAT => matches!(bits, 32 | 64),
+            FORMAT_ALAW | FORMAT_MULAW => bits == 8,
+            _ => false,
+        };
+        if !supported {
+            return Err(DecoderError::UnrecognizedFormat);
+        }
+
+        let bytes_per_sample = bits as usize / 8;
+        Ok(GrowingWavDecoder {
+            data,
+            stop,
+            poll_interval,
+            channels,
+            sample_rate,
+            format,
+            bytes_per_sample,
+            pending: Vec::with_capacity(bytes_per_sample * channels.get() as usize),
+            frame: Vec::with_capacity(channels.get() as usize),
+            frame_pos: 0,
+            silence: 0,
+        })
+    }
+
+    /// Sets how long silence is played before checking for new data, 10 ms by default.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Returns the inner reader.
+    pub fn into_inner(self) -> R {
+        self.data
+    }
+
+    /// Reads the next frame if it is complete. Returns `None` if reading failed.
+    fn read_frame(&mut self) -> Option<bool> {
+        let frame_len = self.bytes_per_sample * self.channels.get() as usize;
+        while self.pending.len() < frame_len {
+            let start = self.pending.len();
+            self.pending.resize(frame_len, 0);
+            let read = match self.data.read(&mut self.pending[start..]) {
+                Ok(read) => read,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => 0,
+                Err(_) => return None,
+            };
+            self.pending.truncate(start + read);
+            if read == 0 {
+                return Some(false);
+            }
+        }
+
+        self.frame.clear();
+        for bytes in self.pending.chunks_exact(self.bytes_per_sample) {
+            self.frame.push(decode_sample(self.format, bytes));
+        }
+        self.pending.clear();
+        self.frame_pos = 0;
+        Some(true)
+    }
+}
+
+/// Converts one little endian sample.
+fn decode_sample(format: u16, bytes: &[u8]) -> Sample {
+    match (format, bytes.len()) {
+        (FORMAT_FLOAT, 4) => f32::from_le_bytes(bytes.try_into().unwrap()),
+        (FORMAT_FLOAT, 8) => f64::from_le_bytes(bytes.try_into().unwrap()) as f32,
+        (FORMAT_ALAW | FORMAT_MULAW, _) => g711::expand(format, bytes[0]),
+        (_, 1) => (bytes[0] as f32 - 128.0) / 128.0,
+        (_, len) => {
+            // Sign extend by placing the bytes at the top of an `i32`.
+            let mut padded = [0; 4];
+            padded[4 - len..].copy_from_slice(bytes);
+            i32::from_le_bytes(padded) as f32 / 2_147_483_648.0
+        }
+    }
+}
+
+impl<R: Read> Iterator for GrowingWavDecoder<R> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        if self.frame_pos == self.frame.len() {
+            if self.silence > 0 {
+                self.silence -= 1;
+                return Some(0.0);
+            }
+            // Read the flag first, data written before it was set is still played.
+            let stop = self.stop.load(Ordering::Acquire);
+            if !self.read_frame()? {
+                if stop {
+                    return None;
+                }
+                let frames = self.poll_interval.as_secs_f64() * self.sample_rate.get() as f64;
+                let frames = (frames as usize).max(1);
+                self.silence = frames * self.channels.get() as usize - 1;
+                return Some(0.0);
+            }
+        }
+        let sample = self.frame[self.frame_pos];
+        self.frame_pos += 1;
+        Some(sample)
+    }
+}
+
+impl<R: Read> Source for GrowingWavDecoder<R> {
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.channels
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+
+    #[inline]
+    fn try_seek(&mut self, _: Duration) -> Result<(), SeekError> {
+        Err(SeekError::NotSupported {
+            underlying_source: std::any::type_name::<Self>(),
+        })
+    }
+}
+
+/// Reads the header, waiting at the end of the data until more is written.
+struct HeaderReader<'a, R> {
+    data: &'a mut R,
+    stop: &'a AtomicBool,
+    poll_interval: Duration,
+}
+
+impl<R: Read> HeaderReader<'_, R> {
+    /// Returns the format tag, channel count, sample rate and bits per sample.
+    fn read(&mut self) -> Result<(u16, u16, u32, u16), DecoderError> {
+        let riff = self.bytes::<12>()?;
+        if &riff[..4] != b"RIFF" || &riff[8..] != b"WAVE" {
+            return Err(DecoderError::UnrecognizedFormat);
+        }
+
+        let mut format = None;
+        loop {
+            let chunk = self.bytes::<8>()?;
+            let len = u32::from_le_bytes(chunk[4..].try_into().unwrap()) as usize;
+            // Chunks are padded to an even length.
+            let padded = len + len % 2;
+            match &chunk[..4] {
+                b"fmt " if len >= 16 => {
+                    let fmt = self.bytes::<16>()?;
+                    let field = |at: usize| u16::from_le_bytes([fmt[at], fmt[at + 1]]);
+                    let mut tag = field(0);
+                    let mut rest = padded - 16;
+                    if tag == FORMAT_EXTENSIBLE && len >= 26 {
+                        // The format tag is the start of the sub format GUID.
+                        let extension = self.bytes::<10>()?;
+                        tag = u16::from_le_bytes([extension[8], extension[9]]);
+                        rest -= 10;
+                    }
+                    let sample_rate = u32::from_le_bytes(fmt[4..8].try_into().unwrap());
+                    format = Some((tag, field(2), sample_rate, field(14)));
+                    self.skip(rest)?;
+                }
+                b"data" => return format.ok_or(DecoderError::UnrecognizedFormat),
+                _ => self.skip(padded)?,
+            }
+        }
+    }
+
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N], DecoderError> {
+        let mut buf = [0; N];
+        self.fill(&mut buf)?;
+        Ok(buf)
+    }
+
+    fn skip(&mut self, mut len: usize) -> Result<(), DecoderError> {
+        let mut buf = [0; 64];
+        while len > 0 {
+            let n = len.min(buf.len());
+            self.fill(&mut buf[..n])?;
+            len -= n;
+        }
+        Ok(())
+    }
+
+    fn fill(&mut self, mut buf: &mut [u8]) -> Result<(), DecoderError> {
+        while !buf.is_empty() {
+            match self.data.read(buf) {
+                Ok(0) => {
+                    if self.stop.load(Ordering::Acquire) {
+                        return Err(DecoderError::UnrecognizedFormat);
+                    }
+                    std::thread::sleep(self.poll_interval);
+                }
+                Ok(n) => buf = &mut buf[n..],
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(DecoderError::IoError(e.to_string())),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{self, File};
+    use std::io::Write;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    use super::GrowingWavDecoder;
+    use crate::math::nz;
+    use crate::Source;
+
+    /// Header of a 16 bit stereo WAV file whose length is not known yet.
+    fn header() -> Vec<u8> {
+        let mut header = Vec::new();
+        header.extend(b"RIFF\0\0\0\0WAVE");
+        header.extend(b"fmt \x10\0\0\0");
+        header.extend(1u16.to_le_bytes());
+        header.extend(2u16.to_le_bytes());
+        header.extend(8000u32.to_le_bytes());
+        header.extend(32000u32.to_le_bytes());
+        header.extend(4u16.to_le_bytes());
+        header.extend(16u16.to_le_bytes());
+        header.extend(b"data\0\0\0\0");
+        header
+    }
+
+    #[test]
+    fn follows_file_while_written() {
+        let path = std::env::temp_dir().join(format!("rodio-growing-{}.wav", std::process::id()));
+        let mut file = File::create(&path).unwrap();
+        file.write_all(&header()).unwrap();
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let writer = thread::spawn({
+            let stop = stop.clone();
+            move || {
+                for chunk in 0..10i16 {
+                    // Split frames over writes to check partial frames are kept.
+                    let bytes: Vec<u8> = (1..=25)
+                        .flat_map(|i| (chunk * 100 + i).to_le_bytes())
+                        .collect();
+                    file.write_all(&bytes).unwrap();
+                    file.flush().unwrap();
+                    thread::sleep(Duration::from_millis(5));
+                }
+                stop.store(true, Ordering::Release);
+            }
+        });
+
+        let decoder = GrowingWavDecoder::new(File::open(&path).unwrap(), stop)
+            .unwrap()
+            .with_poll_interval(Duration::from_millis(1));
+        assert_eq!(decoder.channels(), nz!(2));
+        assert_eq!(decoder.sample_rate(), nz!(8000));
+        let samples: Vec<f32> = decoder.collect();
+        writer.join().unwrap();
+        fs::remove_file(&path).unwrap();
+
+        // Waiting plays silent frames, the data is in order and frames stay aligned.
+        let expected: Vec<f32> = (0..10i16)
+            .flat_map(|chunk| (1..=25).map(move |i| (chunk * 100 + i) as f32 / 32768.0))
+            .collect();
+        let played: Vec<f32> = samples.iter().copied().filter(|s| *s != 0.0).collect();
+        assert_eq!(played, expected);
+        assert!(samples
+            .chunks(2)
+            .all(|frame| (frame[0] == 0.0) == (frame[1] == 0.0)));
+    }
+
+    #[test]
+    fn expands_mulaw() {
+        let mut wav = header();
+        // µ-law is one byte per sample.
+        wav[20..22].copy_from_slice(&7u16.to_le_bytes());
+        wav[32..36].copy_from_slice(&[2, 0, 8, 0]);
+        wav.extend([0x00, 0x7F, 0x80, 0xFF]);
+
+        let stop = Arc::new(AtomicBool::new(true));
+        let decoder = GrowingWavDecoder::new(std::io::Cursor::new(wav), stop).unwrap();
+        let samples: Vec<f32> = decoder.map(|s| s * 32768.0).collect();
+        assert_eq!(samples, [-32124.0, 0.0, 32124.0, 0.0]);
+    }
+}
@@ -40,7 +40,9 @@
 //! - `flac` - FLAC format support
 //! - `vorbis` - Vorbis format support
 //! - `mp3` - MP3 format support via minimp3
+//! - `caf` - Core Audio Format support for PCM audio, not enabled by default
 //! - `symphonia` - Enhanced format support via the Symphonia backend
+//! - `tracker` - ProTracker MOD module support, see `ModDecoder`
 //!
 //! When using `symphonia`, additional formats like AAC and MP4 containers become available
 //! if the corresponding features are enabled.
@@ -62,11 +64,26 @@ use crate::{
     Sample,
 };
 
+#[cfg(feature = "async")]
+mod async_stream;
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub use async_stream::{
+    async_stream, AsyncStreamDecoder, AsyncStreamReader, AsyncStreamSender, SendFuture,
+};
 pub mod builder;
-pub use builder::{DecoderBuilder, Settings};
+#[cfg(feature = "symphonia")]
+#[cfg_attr(docsrs, doc(cfg(feature = "symphonia")))]
+pub use builder::DecodeGap;
+pub use builder::{DecoderBuilder, Settings, SNIFF_LEN};
 
+mod cue;
+pub use cue::{split_by_cue, CueError, CueSegment, CueSheet, CueTrack};
 #[cfg(all(feature = "claxon", not(feature = "symphonia-flac")))]
 mod flac;
+mod g711;
+mod growing_wav;
+pub use growing_wav::GrowingWavDecoder;
 #[cfg(all(feature = "minimp3", not(feature = "symphonia-mp3")))]
 mod mp3;
 #[cfg(feature = "symphonia")]
@@ -74,6 +91,10 @@ mod read_seek_source;
 #[cfg(feature = "symphonia")]
 /// Symphonia decoders types
 pub mod symphonia;
+#[cfg(feature = "tracker")]
+mod tracker;
+#[cfg(feature = "tracker")]
+pub use tracker::ModDecoder;
 #[cfg(all(feature = "lewton", not(feature = "symphonia-vorbis")))]
 mod vorbis;
 #[cfg(all(feature = "hound", not(feature = "symphonia-wav")))]
@@ -83,6 +104,28 @@ mod wav;
 /// See the [module-level documentation](self) for examples and usage.
 pub struct Decoder<R: Read + Seek>(DecoderImpl<R>);
 
+/// The audio format a [`Decoder`] detected, see [`Decoder::format`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AudioFormat {
+    /// Uncompressed PCM or ADPCM audio, usually from a WAV file.
+    Wav,
+    /// MPEG audio layer I, II or III.
+    Mp3,
+    /// Free Lossless Audio Codec.
+    Flac,
+    /// Ogg Vorbis.
+    Vorbis,
+    /// Advanced Audio Coding, usually in an MP4 container.
+    Aac,
+    /// Apple Lossless Audio Codec.
+    Alac,
+    /// Decoded by a decoder registered with [`DecoderBuilder::with_custom_decoder`].
+    Custom,
+    /// A format Symphonia decodes that has no variant of its own.
+    Other,
+}
+
 /// Source of audio samples from decoding a file that never ends.
 /// When the end of the file is reached, the decoder starts again from the beginning.
 ///
@@ -101,7 +144,8 @@ pub struct Decoder<R: Read + Seek>(DecoderImpl<R>);
 pub struct LoopedDecoder<R: Read + Seek> {
     /// The underlying decoder implementation.
     inner: Option<DecoderImpl<R>>,
-    /// Configuration settings for the decoder.
+    /// Configuration settings for the decoder, used to restart Symphonia decoders.
+    #[cfg_attr(not(feature = "symphonia"), allow(dead_code))]
     settings: Settings,
 }
 
@@ -119,6 +163,7 @@ enum DecoderImpl<R: Read + Seek> {
     Mp3(mp3::Mp3Decoder<R>),
     #[cfg(feature = "symphonia")]
     Symphonia(symphonia::SymphoniaDecoder, PhantomData<R>),
+    Custom(Box<dyn Source + Send>, PhantomData<R>),
     // This variant is here just to satisfy the compiler when there are no decoders enabled.
     // It is unreachable and should never be constructed.
     #[allow(dead_code)]
@@ -128,6 +173,23 @@ enum DecoderImpl<R: Read + Seek> {
 enum Unreachable {}
 
 impl<R: Read + Seek> DecoderImpl<R> {
+    fn format(&self) -> AudioFormat {
+        match self {
+            #[cfg(all(feature = "hound", not(feature = "symphonia-wav")))]
+            DecoderImpl::Wav(_) => AudioFormat::Wav,
+            #[cfg(all(feature = "lewton", not(feature = "symphonia-vorbis")))]
+            DecoderImpl::Vorbis(_) => AudioFormat::Vorbis,
+            #[cfg(all(feature = "claxon", not(feature = "symphonia-flac")))]
+            DecoderImpl::Flac(_) => AudioFormat::Flac,
+            #[cfg(all(feature = "minimp3", not(feature = "symphonia-mp3")))]
+            DecoderImpl::Mp3(_) => AudioFormat::Mp3,
+            #[cfg(feature = "symphonia")]
+            DecoderImpl::Symphonia(source, PhantomData) => source.format(),
+            DecoderImpl::Custom(_, PhantomData) => AudioFormat::Custom,
+            DecoderImpl::None(_, _) => unreachable!(),
+        }
+    }
+
     #[inline]
     fn next(&mut self) -> Option<Sample> {
         match self {
@@ -141,6 +203,7 @@ impl<R: Read + Seek> DecoderImpl<R> {
             DecoderImpl::Mp3(source) => source.next(),
             #[cfg(feature = "symphonia")]
             DecoderImpl::Symphonia(source, PhantomData) => source.next(),
+            DecoderImpl::Custom(source, PhantomData) => source.next(),
             DecoderImpl::None(_, _) => unreachable!(),
         }
     }
@@ -158,6 +221,7 @@ impl<R: Read + Seek> DecoderImpl<R> {
             DecoderImpl::Mp3(source) => source.size_hint(),
             #[cfg(feature = "symphonia")]
             DecoderImpl::Symphonia(source, PhantomData) => source.size_hint(),
+            DecoderImpl::Custom(source, PhantomData) => source.size_hint(),
             DecoderImpl::None(_, _) => unreachable!(),
         }
     }
@@ -175,6 +239,7 @@ impl<R: Read + Seek> DecoderImpl<R> {
             DecoderImpl::Mp3(source) => source.current_span_len(),
             #[cfg(feature = "symphonia")]
             DecoderImpl::Symphonia(source, PhantomData) => source.current_span_len(),
+            DecoderImpl::Custom(source, PhantomData) => source.current_span_len(),
             DecoderImpl::None(_, _) => unreachable!(),
         }
     }
@@ -192,6 +257,7 @@ impl<R: Read + Seek> DecoderImpl<R> {
             DecoderImpl::Mp3(source) => source.channels(),
             #[cfg(feature = "symphonia")]
             DecoderImpl::Symphonia(source, PhantomData) => source.channels(),
+            DecoderImpl::Custom(source, PhantomData) => source.channels(),
             DecoderImpl::None(_, _) => unreachable!(),
         }
     }
@@ -209,6 +275,7 @@ impl<R: Read + Seek> DecoderImpl<R> {
             DecoderImpl::Mp3(source) => source.sample_rate(),
             #[cfg(feature = "symphonia")]
             DecoderImpl::Symphonia(source, PhantomData) => source.sample_rate(),
+            DecoderImpl::Custom(source, PhantomData) => source.sample_rate(),
             DecoderImpl::None(_, _) => unreachable!(),
         }
     }
@@ -219,6 +286,10 @@ impl<R: Read + Seek> DecoderImpl<R> {
     ///
     /// For formats that lack timing information like MP3 and Vorbis, this requires the decoder to
     /// be initialized with the correct byte length via `Decoder::builder().with_byte_len()`.
+    ///
+    /// When the stream does not report its length, for example a non-seekable stream from a
+    /// server that only sends a content length, the duration is estimated from the byte length
+    /// and the bitrate of the packets decoded so far. The estimate improves while playing.
     #[inline]
     fn total_duration(&self) -> Option<Duration> {
         match self {
@@ -232,6 +303,7 @@ impl<R: Read + Seek> DecoderImpl<R> {
             DecoderImpl::Mp3(source) => source.total_duration(),
             #[cfg(feature = "symphonia")]
             DecoderImpl::Symphonia(source, PhantomData) => source.total_duration(),
+            DecoderImpl::Custom(source, PhantomData) => source.total_duration(),
             DecoderImpl::None(_, _) => unreachable!(),
         }
     }
@@ -249,6 +321,25 @@ impl<R: Read + Seek> DecoderImpl<R> {
             DecoderImpl::Mp3(source) => source.try_seek(pos),
             #[cfg(feature = "symphonia")]
             DecoderImpl::Symphonia(source, PhantomData) => source.try_seek(pos),
+            DecoderImpl::Custom(source, PhantomData) => source.try_seek(pos),
+            DecoderImpl::None(_, _) => unreachable!(),
+        }
+    }
+
+    #[inline]
+    fn try_seek_samples(&mut self, frame: u64) -> Result<(), SeekError> {
+        match self {
+            #[cfg(all(feature = "hound", not(feature = "symphonia-wav")))]
+            DecoderImpl::Wav(source) => source.try_seek_samples(frame),
+            #[cfg(all(feature = "lewton", not(feature = "symphonia-vorbis")))]
+            DecoderImpl::Vorbis(source) => source.try_seek_samples(frame),
+            #[cfg(all(feature = "claxon", not(feature = "symphonia-flac")))]
+            DecoderImpl::Flac(source) => source.try_seek_samples(frame),
+            #[cfg(all(feature = "minimp3", not(feature = "symphonia-mp3")))]
+            DecoderImpl::Mp3(source) => source.try_seek_samples(frame),
+            #[cfg(feature = "symphonia")]
+            DecoderImpl::Symphonia(source, PhantomData) => source.try_seek_samples(frame),
+            DecoderImpl::Custom(source, PhantomData) => source.try_seek_samples(frame),
             DecoderImpl::None(_, _) => unreachable!(),
         }
     }
@@ -325,10 +416,9 @@ where
 }
 
 /// Converts a `Cursor` into a `Decoder`.
-/// When working with files, prefer `TryFrom<File>` as it will automatically set byte_len
-/// for better seeking performance.
 ///
-/// This is useful for decoding audio data that's already in memory.
+/// This is useful for decoding audio data that's already in memory. The byte_len is set to
+/// the length of the data, formats like CAF need it to find the audio.
 ///
 /// # Errors
 ///
@@ -351,7 +441,35 @@ where
     type Error = DecoderError;
 
     fn try_from(data: std::io::Cursor<T>) -> Result<Self, Self::Error> {
-        Self::new(data)
+        let len = data.get_ref().as_ref().len() as u64;
+        Self::builder().with_data(data).with_byte_len(len).build()
+    }
+}
+
+impl Decoder<std::io::Cursor<&'static [u8]>> {
+    /// Builds a new decoder from audio data embedded in the binary.
+    ///
+    /// Because the length of the data is known, seeking and duration calculation are
+    /// enabled automatically.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DecoderError::UnrecognizedFormat` if the audio format could not be determined
+    /// or is not supported.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use rodio::Decoder;
+    ///
+    /// static BEEP: &[u8] = include_bytes!("../../assets/beep.wav");
+    /// let decoder = Decoder::new_from_bytes(BEEP).unwrap();
+    /// ```
+    pub fn new_from_bytes(data: &'static [u8]) -> Result<Self, DecoderError> {
+        Self::builder()
+            .with_byte_len(data.len() as u64)
+            .with_data(std::io::Cursor::new(data))
+            .with_seekable(true)
+            .build()
     }
 }
 
@@ -557,6 +675,15 @@ impl<R: Read + Seek + Send + Sync + 'static> Decoder<R> {
     }
 }
 
+impl<R: Read + Seek> Decoder<R> {
+    /// Returns the format that was detected when the decoder was built.
+    ///
+    /// Meant for diagnostics, the samples are decoded the same way whatever the format.
+    pub fn format(&self) -> AudioFormat {
+        self.0.format()
+    }
+}
+
 impl<R> Iterator for Decoder<R>
 where
     R: Read + Seek,
@@ -601,6 +728,11 @@ where
     fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
         self.0.try_seek(pos)
     }
+
+    #[inline]
+    fn try_seek_samples(&mut self, frame: u64) -> Result<(), SeekError> {
+        self.0.try_seek_samples(frame)
+    }
 }
 
 impl<R> Iterator for LoopedDecoder<R>
@@ -667,6 +799,11 @@ where
                     let sample = source.next();
                     (DecoderImpl::Symphonia(source, PhantomData), sample)
                 }
+                DecoderImpl::Custom(mut source, PhantomData) => {
+                    source.try_seek(Duration::ZERO).ok()?;
+                    let sample = source.next();
+                    (DecoderImpl::Custom(source, PhantomData), sample)
+                }
             };
             self.inner = Some(new_decoder);
             sample
@@ -3,18 +3,22 @@ use std::sync::Arc;
 use symphonia::{
     core::{
         audio::{AudioBufferRef, SampleBuffer, SignalSpec},
-        codecs::{Decoder, DecoderOptions, CODEC_TYPE_NULL},
+        codecs::{
+            Decoder, DecoderOptions, CODEC_TYPE_AAC, CODEC_TYPE_ALAC, CODEC_TYPE_FLAC,
+            CODEC_TYPE_MP1, CODEC_TYPE_MP2, CODEC_TYPE_MP3, CODEC_TYPE_NULL, CODEC_TYPE_VORBIS,
+        },
         errors::Error,
-        formats::{FormatOptions, FormatReader, SeekMode, SeekTo, SeekedTo},
+        formats::{FormatOptions, FormatReader, Packet, SeekMode, SeekTo, SeekedTo},
         io::MediaSourceStream,
         meta::MetadataOptions,
         probe::Hint,
-        units,
+        units::{self, TimeBase},
     },
-    default::get_probe,
+    default::{get_codecs, get_probe},
 };
 
-use super::{DecoderError, Settings};
+use super::builder::GapCallback;
+use super::{AudioFormat, DecodeGap, DecoderError, Settings};
 use crate::{
     common::{assert_error_traits, ChannelCount, Sample, SampleRate},
     source, Source,
@@ -22,12 +26,20 @@ use crate::{
 
 pub(crate) struct SymphoniaDecoder {
     decoder: Box<dyn Decoder>,
+    track_id: u32,
     current_span_offset: usize,
     format: Box<dyn FormatReader>,
     total_duration: Option<Duration>,
+    byte_len: Option<u64>,
+    // Size and length of the packets decoded so far, to estimate the duration from `byte_len`.
+    packet_bytes: u64,
+    packet_frames: u64,
     buffer: SampleBuffer<Sample>,
     spec: SignalSpec,
     seek_mode: SeekMode,
+    gap_callback: Option<GapCallback>,
+    // Timestamp at which the previous packet ended, `None` after seeking.
+    next_ts: Option<u64>,
 }
 
 impl SymphoniaDecoder {
@@ -53,6 +65,25 @@ impl SymphoniaDecoder {
         self.format.into_inner()
     }
 
+    pub(crate) fn format(&self) -> AudioFormat {
+        match self.decoder.codec_params().codec {
+            CODEC_TYPE_MP1 | CODEC_TYPE_MP2 | CODEC_TYPE_MP3 => AudioFormat::Mp3,
+            CODEC_TYPE_FLAC => AudioFormat::Flac,
+            CODEC_TYPE_VORBIS => AudioFormat::Vorbis,
+            CODEC_TYPE_AAC => AudioFormat::Aac,
+            CODEC_TYPE_ALAC => AudioFormat::Alac,
+            codec => match get_codecs().get_codec(codec) {
+                Some(descriptor)
+                    if descriptor.short_name.starts_with("pcm")
+                        || descriptor.short_name.starts_with("adpcm") =>
+                {
+                    AudioFormat::Wav
+                }
+                _ => AudioFormat::Other,
+            },
+        }
+    }
+
     fn init(
         mss: MediaSourceStream,
         settings: &Settings,
@@ -110,10 +141,10 @@ impl SymphoniaDecoder {
             .zip(stream.codec_params.n_frames)
             .map(|(base, spans)| base.calc_time(spans).into());
 
-        let decoded = loop {
+        let (decoded, packet_bytes, next_ts) = loop {
             let current_span = match probed.format.next_packet() {
                 Ok(packet) => packet,
-                Err(Error::IoError(_)) => break decoder.last_decoded(),
+                Err(Error::IoError(_)) => break (decoder.last_decoded(), 0, 0),
                 Err(e) => return Err(e),
             };
 
@@ -123,7 +154,13 @@ impl SymphoniaDecoder {
             }
 
             match decoder.decode(&current_span) {
-                Ok(decoded) => break decoded,
+                Ok(decoded) => {
+                    break (
+                        decoded,
+                        current_span.buf().len(),
+                        current_span.ts() + current_span.dur(),
+                    )
+                }
                 Err(e) => match e {
                     Error::DecodeError(_) => {
                         // Decode errors are intentionally ignored with no retry limit.
@@ -136,15 +173,22 @@ impl SymphoniaDecoder {
             }
         };
         let spec = decoded.spec().to_owned();
+        let packet_frames = decoded.frames() as u64;
         let buffer = SymphoniaDecoder::get_buffer(decoded, &spec);
         Ok(Some(SymphoniaDecoder {
             decoder,
+            track_id,
             current_span_offset: 0,
             format: probed.format,
             total_duration,
+            byte_len: settings.byte_len,
+            packet_bytes: packet_bytes as u64,
+            packet_frames,
             buffer,
             spec,
             seek_mode,
+            gap_callback: settings.gap_callback.clone(),
+            next_ts: Some(next_ts),
         }))
     }
 
@@ -155,6 +199,25 @@ impl SymphoniaDecoder {
         buffer.copy_interleaved_ref(decoded);
         buffer
     }
+
+    /// Switches to the first supported track of a new stream, for example the next link of a
+    /// chained Ogg file.
+    fn reset_track(&mut self) -> symphonia::core::errors::Result<()> {
+        let track = self
+            .format
+            .tracks()
+            .iter()
+            .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+            .ok_or(Error::Unsupported("No track with supported codec"))?;
+        self.decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions::default())?;
+        self.track_id = track.id;
+        // The duration that was known only covered the previous stream.
+        self.total_duration = None;
+        // Timestamps start again in the new stream.
+        self.next_ts = None;
+        Ok(())
+    }
 }
 
 impl Source for SymphoniaDecoder {
@@ -182,7 +245,14 @@ impl Source for SymphoniaDecoder {
 
     #[inline]
     fn total_duration(&self) -> Option<Duration> {
-        self.total_duration
+        self.total_duration.or_else(|| {
+            let byte_len = self.byte_len?;
+            if self.packet_bytes == 0 || self.packet_frames == 0 {
+                return None;
+            }
+            let frames = byte_len as f64 * self.packet_frames as f64 / self.packet_bytes as f64;
+            Some(Duration::from_secs_f64(frames / self.spec.rate as f64))
+        })
     }
 
     fn try_seek(&mut self, pos: Duration) -> Result<(), source::SeekError> {
@@ -225,6 +295,7 @@ impl Source for SymphoniaDecoder {
         // so we need to reset the decoder to make sure it's in sync and prevent
         // audio glitches.
         self.decoder.reset();
+        self.next_ts = None;
 
         // Force the iterator to decode the next packet.
         self.current_span_offset = usize::MAX;
@@ -243,6 +314,42 @@ impl Source for SymphoniaDecoder {
 
         Ok(())
     }
+
+    fn try_seek_samples(&mut self, frame: u64) -> Result<(), source::SeekError> {
+        // Timestamps only count frames when the time base is one over the sample rate.
+        let params = self.decoder.codec_params();
+        let counts_frames = params
+            .time_base
+            .is_some_and(|base| base.numer == 1 && base.denom == self.sample_rate().get());
+        if !counts_frames || matches!(self.seek_mode, SeekMode::Coarse) {
+            return self.try_seek(crate::math::frame_to_duration(frame, self.sample_rate()));
+        }
+        let frame = params.n_frames.map_or(frame, |frames| frame.min(frames));
+
+        let seek_res = match self.format.seek(
+            SeekMode::Accurate,
+            SeekTo::TimeStamp {
+                ts: frame,
+                track_id: self.track_id,
+            },
+        ) {
+            Err(Error::SeekError(symphonia::core::errors::SeekErrorKind::ForwardOnly)) => {
+                return Err(source::SeekError::SymphoniaDecoder(
+                    SeekError::RandomAccessNotSupported,
+                ));
+            }
+            other => other.map_err(Arc::new).map_err(SeekError::Demuxer),
+        }?;
+        self.decoder.reset();
+        self.next_ts = None;
+        self.current_span_offset = usize::MAX;
+
+        let frames_to_skip = seek_res.required_ts.saturating_sub(seek_res.actual_ts);
+        for _ in 0..frames_to_skip * self.channels().get() as u64 {
+            self.next();
+        }
+        Ok(())
+    }
 }
 
 /// Error returned when the try_seek implementation of the symphonia decoder fails.
@@ -265,6 +372,26 @@ pub enum SeekError {
 }
 assert_error_traits!(SeekError);
 
+/// Reports the audio missing before `packet`, which was decoded, to `callback`. `next_ts` is
+/// where the previous packet ended and is moved to the end of `packet`.
+fn check_gap(
+    next_ts: &mut Option<u64>,
+    callback: Option<&GapCallback>,
+    time_base: Option<TimeBase>,
+    packet: &Packet,
+) {
+    let expected = next_ts.replace(packet.ts() + packet.dur());
+    let (Some(callback), Some(expected), Some(time_base)) = (callback, expected, time_base) else {
+        return;
+    };
+    if packet.ts() > expected {
+        callback.0(DecodeGap {
+            position: time_base.calc_time(expected).into(),
+            missing: time_base.calc_time(packet.ts() - expected).into(),
+        });
+    }
+}
+
 impl SymphoniaDecoder {
     /// Note span offset must be set after
     fn refine_position(&mut self, seek_res: SeekedTo) -> Result<(), source::SeekError> {
@@ -299,7 +426,20 @@ impl Iterator for SymphoniaDecoder {
     fn next(&mut self) -> Option<Self::Item> {
         if self.current_span_offset >= self.buffer.len() {
             let decoded = loop {
-                let packet = self.format.next_packet().ok()?;
+                let packet = match self.format.next_packet() {
+                    Ok(packet) => packet,
+                    // A new stream starts, its format may differ from the previous one. The
+                    // change is picked up below once its first packet has been decoded.
+                    Err(Error::ResetRequired) => {
+                        self.reset_track().ok()?;
+                        continue;
+                    }
+                    Err(_) => return None,
+                };
+                if packet.track_id() != self.track_id {
+                    continue;
+                }
+                let time_base = self.decoder.codec_params().time_base;
                 let decoded = match self.decoder.decode(&packet) {
                     Ok(decoded) => decoded,
                     Err(Error::DecodeError(_)) => {
@@ -310,6 +450,12 @@ impl Iterator for SymphoniaDecoder {
                     }
                     Err(_) => return None,
                 };
+                check_gap(
+                    &mut self.next_ts,
+                    self.gap_callback.as_ref(),
+                    time_base,
+                    &packet,
+                );
 
                 // Loop until we get a packet with audio frames. This is necessary because some
                 // formats can have packets with only metadata, particularly when rewinding, in
@@ -317,6 +463,8 @@ impl Iterator for SymphoniaDecoder {
                 // Note: checking `decoded.frames()` is more reliable than `packet.dur()`, which
                 // can resturn non-zero durations for packets without audio frames.
                 if decoded.frames() > 0 {
+                    self.packet_bytes += packet.buf().len() as u64;
+                    self.packet_frames += decoded.frames() as u64;
                     break decoded;
                 }
             };
@@ -0,0 +1,544 @@
+use std::io::Read;
+use std::time::Duration;
+
+use super::DecoderError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::math::nz;
+use crate::source::SeekError;
+use crate::{Sample, Source};
+
+/// Rate the module is rendered at.
+const SAMPLE_RATE: SampleRate = nz!(48000);
+/// Clock of a PAL Amiga, notes are given as periods of this clock.
+const PAL_CLOCK: f64 = 7_093_789.2;
+const ROWS: usize = 64;
+const VIBRATO: [u8; 32] = [
+    0, 24, 49, 74, 97, 120, 141, 161, 180, 197, 212, 224, 235, 244, 250, 253, 255, 253, 250, 244,
+    235, 224, 212, 197, 180, 161, 141, 120, 97, 74, 49, 24,
+];
+
+/// Plays ProTracker MOD modules, the music format of many Amiga and retro games.
+///
+/// The module is rendered to stereo at 48 kHz with the Amiga panning: the first and last of
+/// every four channels play on the left. Modules with 4 to 32 channels are supported, as are
+/// the common effects except for pattern loops and delays, which are played as if absent.
+///
+/// The song ends after the last pattern, or when it jumps back to a pattern that was already
+/// played. With [`ModDecoder::with_looping`] it starts again from the restart position of
+/// the module instead. Seeking is not supported.
+///
+/// XM and IT modules are not supported.
+///
+/// # Examples
+/// ```no_run
+/// use std::fs::File;
+/// use rodio::decoder::ModDecoder;
+///
+/// let file = File::open("music.mod").unwrap();
+/// let module = ModDecoder::new(file).unwrap().with_looping(true);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ModDecoder {
+    samples: Vec<Instrument>,
+    orders: Vec<u8>,
+    restart: usize,
+    patterns: Vec<Vec<Cell>>,
+    num_channels: usize,
+    channels: Vec<Channel>,
+    looping: bool,
+    ended: bool,
+    // Rows that were played, to detect the song jumping back.
+    visited: Vec<bool>,
+    order: usize,
+    row: usize,
+    tick: u32,
+    speed: u32,
+    tempo: u32,
+    frames_left_in_tick: u32,
+    // Where the song continues after the current row, if an effect changed it.
+    jump: Option<(usize, usize)>,
+    // The right sample of the current frame.
+    right: Option<Sample>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Instrument {
+    data: Vec<i8>,
+    finetune: i8,
+    volume: u8,
+    loop_start: usize,
+    loop_len: usize,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Cell {
+    sample: u8,
+    period: u16,
+    effect: u8,
+    param: u8,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Channel {
+    sample: Option<usize>,
+    position: f64,
+    playing: bool,
+    period: u16,
+    // Period played this tick, after arpeggio and vibrato.
+    tick_period: f64,
+    volume: u8,
+    cell: Cell,
+    porta_target: u16,
+    porta_speed: u8,
+    vibrato_speed: u8,
+    vibrato_depth: u8,
+    vibrato_pos: u8,
+}
+
+impl ModDecoder {
+    /// Reads a whole module.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DecoderError::UnrecognizedFormat` if the data is not a ProTracker module and
+    /// `DecoderError::IoError` if reading failed.
+    pub fn new<R: Read>(mut data: R) -> Result<Self, DecoderError> {
+        let mut bytes = Vec::new();
+        data.read_to_end(&mut bytes)
+            .map_err(|e| DecoderError::IoError(e.to_string()))?;
+        let header = bytes.get(..1084).ok_or(DecoderError::UnrecognizedFormat)?;
+        let num_channels =
+            channel_count(&header[1080..1084]).ok_or(DecoderError::UnrecognizedFormat)?;
+        let song_len = (header[950] as usize).clamp(1, 128);
+        let orders = header[952..952 + song_len].to_vec();
+        let restart = header[951] as usize;
+        let num_patterns = header[952..1080].iter().max().copied().unwrap_or(0) as usize + 1;
+
+        let pattern_len = ROWS * num_channels * 4;
+        let mut offset = 1084;
+        let mut patterns = Vec::with_capacity(num_patterns);
+        for _ in 0..num_patterns {
+            let data = bytes
+                .get(offset..offset + pattern_len)
+                .ok_or(DecoderError::UnrecognizedFormat)?;
+            patterns.push(
+                data.chunks_exact(4)
+                    .map(|cell| Cell {
+                        sample: (cell[0] & 0xF0) | (cell[2] >> 4),
+                        period: u16::from(cell[0] & 0x0F) << 8 | u16::from(cell[1]),
+                        effect: cell[2] & 0x0F,
+                        param: cell[3],
+                    })
+                    .collect(),
+            );
+            offset += pattern_len;
+        }
+
+        let mut samples = Vec::with_capacity(31);
+        for header in header[20..950].chunks_exact(30) {
+            let word = |at: usize| u16::from_be_bytes([header[at], header[at + 1]]) as usize * 2;
+            let len = word(22);
+            // Modules are often truncated, play what is there.
+            let data: Vec<i8> = bytes
+                .get(offset.min(bytes.len())..(offset + len).min(bytes.len()))
+                .unwrap_or_default()
+                .iter()
+                .map(|byte| *byte as i8)
+                .collect();
+            offset += len;
+            let (loop_start, loop_len) = (word(26), word(28));
+            let looped = loop_len > 2 && loop_start + loop_len <= data.len();
+            samples.push(Instrument {
+                finetune: ((header[24] & 0x0F) << 4) as i8 >> 4,
+                volume: header[25].min(64),
+                loop_start: if looped { loop_start } else { 0 },
+                loop_len: if looped { loop_len } else { 0 },
+                data,
+            });
+        }
+
+        let mut decoder = ModDecoder {
+            samples,
+            visited: vec![false; orders.len() * ROWS],
+            orders,
+            restart,
+            patterns,
+            num_channels,
+            channels: vec![Channel::default(); num_channels],
+            looping: false,
+            ended: false,
+            order: 0,
+            row: 0,
+            tick: 0,
+            speed: 6,
+            tempo: 125,
+            frames_left_in_tick: 0,
+            jump: None,
+            right: None,
+        };
+        decoder.start_tick();
+        Ok(decoder)
+    }
+
+    /// Starts the song again when it ends, from the restart position of the module.
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    fn start_tick(&mut self) {
+        if self.tick == 0 {
+            self.start_row();
+        } else {
+            for index in 0..self.channels.len() {
+                self.update_effect(index);
+            }
+        }
+        // A tick lasts 2.5 seconds divided by the tempo.
+        self.frames_left_in_tick = SAMPLE_RATE.get() * 5 / (2 * self.tempo);
+    }
+
+    fn start_row(&mut self) {
+        if self.visited[self.order * ROWS + self.row] {
+            self.end_song();
+            if self.ended {
+                return;
+            }
+        }
+        self.visited[self.order * ROWS + self.row] = true;
+
+        let pattern = self.orders[self.order] as usize;
+        for index in 0..self.num_channels {
+            let cell = self.patterns[pattern][self.row * self.num_channels + index];
+            self.start_cell(index, cell);
+        }
+    }
+
+    fn start_cell(&mut self, index: usize, cell: Cell) {
+        let channel = &mut self.channels[index];
+        channel.cell = cell;
+        if let Some(instrument) = (cell.sample as usize).checked_sub(1) {
+            if instrument < self.samples.len() {
+                channel.sample = Some(instrument);
+                channel.volume = self.samples[instrument].volume;
+            }
+        }
+        let param = cell.param;
+        let (x, y) = (param >> 4, param & 0x0F);
+        if cell.period != 0 {
+            let period = match channel.sample {
+                Some(sample) => finetuned(cell.period, self.samples[sample].finetune),
+                None => cell.period,
+            };
+            if matches!(cell.effect, 0x3 | 0x5) {
+                channel.porta_target = period;
+            } else {
+                channel.period = period;
+                channel.position = if cell.effect == 0x9 {
+                    param as f64 * 256.0
+                } else {
+                    0.0
+                };
+                channel.playing = true;
+                channel.vibrato_pos = 0;
+            }
+        }
+        channel.tick_period = channel.period as f64;
+
+        match cell.effect {
+            0x3 if param != 0 => channel.porta_speed = param,
+            0x4 => {
+                if x != 0 {
+                    channel.vibrato_speed = x;
+                }
+                if y != 0 {
+                    channel.vibrato_depth = y;
+                }
+            }
+            0xB => self.jump = Some((param as usize, 0)),
+            0xC => channel.volume = param.min(64),
+            0xD => {
+                let order = self.jump.map_or(self.order + 1, |(order, _)| order);
+                // Rows past the end of a pattern break to its start.
+                let row = (x * 10 + y) as usize;
+                self.jump = Some((order, if row < ROWS { row } else { 0 }));
+            }
+            0xE => match x {
+                0x1 => channel.period = channel.period.saturating_sub(y as u16).max(113),
+                0x2 => channel.period = (channel.period + y as u16).min(856),
+                0xA => channel.volume = (channel.volume + y).min(64),
+                0xB => channel.volume = channel.volume.saturating_sub(y),
+                0xC if y == 0 => channel.volume = 0,
+                _ => {}
+            },
+            0xF if param > 0 && param < 32 => self.speed = param as u32,
+            0xF if param >= 32 => self.tempo = param as u32,
+            _ => {}
+        }
+    }
+
+    fn update_effect(&mut self, index: usize) {
+        let tick = self.tick;
+        let channel = &mut self.channels[index];
+        let Cell { effect, param, .. } = channel.cell;
+        let (x, y) = (param >> 4, param & 0x0F);
+        channel.tick_period = channel.period as f64;
+        match effect {
+            0x0 if param != 0 => {
+                let semitones = [0, x, y][tick as usize % 3];
+                channel.tick_period = channel.period as f64 / 2f64.powf(semitones as f64 / 12.0);
+            }
+            0x1 => channel.period = channel.period.saturating_sub(param as u16).max(113),
+            0x2 => channel.period = (channel.period + param as u16).min(856),
+            0x3 => channel.tone_portamento(),
+            0x4 => channel.vibrato(),
+            0x5 => {
+                channel.tone_portamento();
+                channel.volume_slide(x, y);
+            }
+            0x6 => {
+                channel.vibrato();
+                channel.volume_slide(x, y);
+            }
+            0xA => channel.volume_slide(x, y),
+            0xE if x == 0xC && tick == y as u32 => channel.volume = 0,
+            _ => {}
+        }
+        if !matches!(effect, 0x0 | 0x4 | 0x6) {
+            channel.tick_period = channel.period as f64;
+        }
+    }
+
+    fn next_tick(&mut self) {
+        self.tick += 1;
+        if self.tick >= self.speed {
+            self.tick = 0;
+            let (order, row) = self.jump.take().unwrap_or((self.order, self.row + 1));
+            (self.order, self.row) = if row >= ROWS {
+                (order + 1, 0)
+            } else {
+                (order, row)
+            };
+            if self.order >= self.orders.len() {
+                self.end_song();
+            }
+        }
+        if !self.ended {
+            self.start_tick();
+        }
+    }
+
+    fn end_song(&mut self) {
+        if !self.looping {
+            self.ended = true;
+            return;
+        }
+        self.visited.fill(false);
+        self.order = if self.restart < self.orders.len() {
+            self.restart
+        } else {
+            0
+        };
+        self.row = 0;
+    }
+
+    fn render_frame(&mut self) -> (Sample, Sample) {
+        let mut left = 0.0;
+        let mut right = 0.0;
+        for (index, channel) in self.channels.iter_mut().enumerate() {
+            let Some(sample) = channel.sample.filter(|_| channel.playing) else {
+                continue;
+            };
+            if channel.tick_period <= 0.0 {
+                continue;
+            }
+            let instrument = &self.samples[sample];
+            let mut position = channel.position as usize;
+            if instrument.loop_len > 0 {
+                let loop_end = instrument.loop_start + instrument.loop_len;
+                while position >= loop_end {
+                    channel.position -= instrument.loop_len as f64;
+                    position -= instrument.loop_len;
+                }
+            } else if position >= instrument.data.len() {
+                channel.playing = false;
+                continue;
+            }
+            let value = instrument.data[position] as f32 / 128.0 * channel.volume as f32 / 64.0;
+            // Amiga panning: channels 0 and 3 of every four are on the left.
+            if matches!(index % 4, 0 | 3) {
+                left += value;
+            } else {
+                right += value;
+            }
+            channel.position += PAL_CLOCK / (2.0 * channel.tick_period) / SAMPLE_RATE.get() as f64;
+        }
+        let gain = 2.0 / self.num_channels as f32;
+        (left * gain, right * gain)
+    }
+}
+
+impl Channel {
+    fn tone_portamento(&mut self) {
+        let speed = self.porta_speed as u16;
+        if self.porta_target == 0 {
+            return;
+        }
+        self.period = if self.period < self.porta_target {
+            (self.period + speed).min(self.porta_target)
+        } else {
+            self.period.saturating_sub(speed).max(self.porta_target)
+        };
+    }
+
+    fn vibrato(&mut self) {
+        let delta = VIBRATO[(self.vibrato_pos & 31) as usize] as f64 * self.vibrato_depth as f64;
+        let delta = delta / 128.0;
+        self.tick_period = if self.vibrato_pos & 32 == 0 {
+            self.period as f64 + delta
+        } else {
+            self.period as f64 - delta
+        };
+        self.vibrato_pos = (self.vibrato_pos + self.vibrato_speed) & 63;
+    }
+
+    fn volume_slide(&mut self, up: u8, down: u8) {
+        self.volume = if up > 0 {
+            (self.volume + up).min(64)
+        } else {
+            self.volume.saturating_sub(down)
+        };
+    }
+}
+
+/// Returns the number of channels given by the signature of a module.
+fn channel_count(signature: &[u8]) -> Option<usize> {
+    match signature {
+        b"M.K." | b"M!K!" | b"FLT4" | b"4CHN" => Some(4),
+        b"6CHN" => Some(6),
+        b"8CHN" | b"FLT8" | b"OCTA" | b"CD81" => Some(8),
+        [tens @ b'1'..=b'3', ones @ b'0'..=b'9', b'C', b'H'] => {
+            let channels = ((tens - b'0') * 10 + ones - b'0') as usize;
+            (channels <= 32).then_some(channels)
+        }
+        _ => None,
+    }
+}
+
+/// Applies the finetune of a sample, in eighths of a semitone.
+fn finetuned(period: u16, finetune: i8) -> u16 {
+    (period as f64 / 2f64.powf(finetune as f64 / 96.0)).round() as u16
+}
+
+impl Iterator for ModDecoder {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        if let Some(right) = self.right.take() {
+            return Some(right);
+        }
+        if self.frames_left_in_tick == 0 {
+            self.next_tick();
+        }
+        if self.ended {
+            return None;
+        }
+        self.frames_left_in_tick -= 1;
+        let (left, right) = self.render_frame();
+        self.right = Some(right);
+        Some(left)
+    }
+}
+
+impl Source for ModDecoder {
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        nz!(2)
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        SAMPLE_RATE
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+
+    #[inline]
+    fn try_seek(&mut self, _: Duration) -> Result<(), SeekError> {
+        Err(SeekError::NotSupported {
+            underlying_source: std::any::type_name::<Self>(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ModDecoder;
+    use crate::decoder::DecoderError;
+
+    /// A four channel module with a looped square wave played on the first channel.
+    fn module(effects: &[(usize, u8, u8)]) -> Vec<u8> {
+        let mut module = vec![0; 1084];
+        // 32 bytes of sample data, looped.
+        module[20 + 22..20 + 30].copy_from_slice(&[0, 16, 0, 64, 0, 0, 0, 16]);
+        module[950] = 1;
+        module[1080..1084].copy_from_slice(b"M.K.");
+        let mut pattern = vec![0; 64 * 4 * 4];
+        // C-3 played with the first sample.
+        pattern[..4].copy_from_slice(&[0x01, 0xAC, 0x10, 0x00]);
+        for (row, effect, param) in effects {
+            let cell = row * 16 + 4;
+            pattern[cell + 2] = *effect;
+            pattern[cell + 3] = *param;
+        }
+        module.extend(pattern);
+        module.extend((0..32).map(|i| if i < 16 { 100u8 } else { 156u8 }));
+        module
+    }
+
+    #[test]
+    fn renders_module() {
+        let decoder = ModDecoder::new(module(&[]).as_slice()).unwrap();
+        let output: Vec<f32> = decoder.collect();
+        // 64 rows of 6 ticks of 20 ms at 125 BPM.
+        assert_eq!(output.len(), 64 * 6 * 960 * 2);
+        let left: Vec<f32> = output.iter().step_by(2).copied().collect();
+        assert!(left.iter().any(|s| *s > 0.3) && left.iter().any(|s| *s < -0.3));
+        assert!(output.iter().skip(1).step_by(2).all(|s| *s == 0.0));
+    }
+
+    #[test]
+    fn follows_speed_and_pattern_break() {
+        // Three ticks per row, then break to the next (missing) order after row 8.
+        let module = module(&[(0, 0xF, 3), (7, 0xD, 0)]);
+        let decoder = ModDecoder::new(module.as_slice()).unwrap();
+        assert_eq!(decoder.count(), 8 * 3 * 960 * 2);
+    }
+
+    #[test]
+    fn loops_when_asked() {
+        let decoder = ModDecoder::new(module(&[]).as_slice())
+            .unwrap()
+            .with_looping(true);
+        assert_eq!(
+            decoder.take(64 * 6 * 960 * 2 * 3).count(),
+            64 * 6 * 960 * 2 * 3
+        );
+    }
+
+    #[test]
+    fn rejects_other_data() {
+        assert!(matches!(
+            ModDecoder::new(&[0u8; 2000][..]),
+            Err(DecoderError::UnrecognizedFormat)
+        ));
+    }
+}
@@ -1,4 +1,4 @@
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{BufReader, Read, Seek, SeekFrom};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -7,16 +7,20 @@ use crate::{Sample, Source};
 
 use crate::common::{ChannelCount, SampleRate};
 
+use super::g711::{self, FORMAT_ALAW, FORMAT_MULAW};
 use dasp_sample::Sample as _;
 use dasp_sample::I24;
 use hound::{SampleFormat, WavReader};
 
 /// Decoder for the WAV format.
+///
+/// Integer and float samples are read with hound. A-law and µ-law samples, which hound does
+/// not support, are read directly.
 pub struct WavDecoder<R>
 where
     R: Read + Seek,
 {
-    reader: SamplesIterator<R>,
+    reader: Samples<R>,
     total_duration: Duration,
     sample_rate: SampleRate,
     channels: ChannelCount,
@@ -29,7 +33,7 @@ where
     /// Attempts to decode the data as WAV.
     pub fn new(mut data: R) -> Result<WavDecoder<R>, R> {
         if !is_wave(data.by_ref()) {
-            return Err(data);
+            return WavDecoder::new_companded(data);
         }
 
         let reader = WavReader::new(data).expect("should still be wav");
@@ -44,25 +48,126 @@ where
         let channels = spec.channels;
         assert!(channels > 0);
 
-        let total_duration = {
-            let data_rate = sample_rate as u64 * channels as u64;
-            let secs = len / data_rate;
-            let nanos = ((len % data_rate) * 1_000_000_000) / data_rate;
-            Duration::new(secs, nanos as u32)
-        };
+        let sample_rate =
+            SampleRate::new(sample_rate).expect("wav should have a sample rate higher then zero");
+        let channels = ChannelCount::new(channels).expect("wav should have a least one channel");
+        Ok(WavDecoder {
+            reader: Samples::Hound(reader),
+            total_duration: duration(len, sample_rate, channels),
+            sample_rate,
+            channels,
+        })
+    }
 
+    fn new_companded(mut data: R) -> Result<WavDecoder<R>, R> {
+        let stream_pos = data.stream_position().unwrap_or_default();
+        let Some(header) = read_companded_header(&mut data) else {
+            let _ = data.seek(SeekFrom::Start(stream_pos));
+            return Err(data);
+        };
+        let reader = CompandedSamples {
+            data: BufReader::new(data),
+            format: header.format,
+            start: header.start,
+            len: header.len,
+            samples_read: 0,
+        };
         Ok(WavDecoder {
-            reader,
-            total_duration,
-            sample_rate: SampleRate::new(sample_rate)
-                .expect("wav should have a sample rate higher then zero"),
-            channels: ChannelCount::new(channels).expect("wav should have a least one channel"),
+            reader: Samples::Companded(reader),
+            total_duration: duration(header.len as u64, header.sample_rate, header.channels),
+            sample_rate: header.sample_rate,
+            channels: header.channels,
         })
     }
 
     #[inline]
     pub fn into_inner(self) -> R {
-        self.reader.reader.into_inner()
+        match self.reader {
+            Samples::Hound(reader) => reader.reader.into_inner(),
+            Samples::Companded(reader) => reader.data.into_inner(),
+        }
+    }
+}
+
+fn duration(len: u64, sample_rate: SampleRate, channels: ChannelCount) -> Duration {
+    let data_rate = sample_rate.get() as u64 * channels.get() as u64;
+    let secs = len / data_rate;
+    let nanos = ((len % data_rate) * 1_000_000_000) / data_rate;
+    Duration::new(secs, nanos as u32)
+}
+
+enum Samples<R>
+where
+    R: Read + Seek,
+{
+    Hound(SamplesIterator<R>),
+    Companded(CompandedSamples<R>),
+}
+
+impl<R> Samples<R>
+where
+    R: Read + Seek,
+{
+    fn samples_read(&self) -> u32 {
+        match self {
+            Samples::Hound(reader) => reader.samples_read,
+            Samples::Companded(reader) => reader.samples_read,
+        }
+    }
+
+    /// Number of frames in the file.
+    fn frames(&self, channels: ChannelCount) -> u32 {
+        match self {
+            Samples::Hound(reader) => reader.reader.duration(),
+            Samples::Companded(reader) => reader.len / channels.get() as u32,
+        }
+    }
+
+    fn seek(&mut self, frame: u32, channels: ChannelCount) -> Result<(), SeekError> {
+        let samples_read = frame * channels.get() as u32;
+        match self {
+            Samples::Hound(reader) => {
+                reader
+                    .reader
+                    .seek(frame)
+                    .map_err(Arc::new)
+                    .map_err(SeekError::HoundDecoder)?;
+                reader.samples_read = samples_read;
+            }
+            Samples::Companded(reader) => {
+                // One byte per sample.
+                reader
+                    .data
+                    .seek(SeekFrom::Start(reader.start + samples_read as u64))
+                    .map_err(Arc::new)
+                    .map_err(SeekError::HoundDecoder)?;
+                reader.samples_read = samples_read;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R> Iterator for Samples<R>
+where
+    R: Read + Seek,
+{
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        match self {
+            Samples::Hound(reader) => reader.next(),
+            Samples::Companded(reader) => reader.next(),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Samples::Hound(reader) => reader.size_hint(),
+            Samples::Companded(reader) => reader.size_hint(),
+        }
     }
 }
 
@@ -145,6 +250,111 @@ where
 
 impl<R> ExactSizeIterator for SamplesIterator<R> where R: Read + Seek {}
 
+/// Reads A-law or µ-law samples, one byte each.
+struct CompandedSamples<R>
+where
+    R: Read + Seek,
+{
+    data: BufReader<R>,
+    format: u16,
+    // Position and length in bytes of the samples.
+    start: u64,
+    len: u32,
+    samples_read: u32,
+}
+
+impl<R> Iterator for CompandedSamples<R>
+where
+    R: Read + Seek,
+{
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        if self.samples_read >= self.len {
+            return None;
+        }
+        let mut code = [0];
+        self.data.read_exact(&mut code).ok()?;
+        self.samples_read += 1;
+        Some(g711::expand(self.format, code[0]))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.len - self.samples_read) as usize;
+        (len, Some(len))
+    }
+}
+
+struct CompandedHeader {
+    format: u16,
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+    start: u64,
+    len: u32,
+}
+
+/// Reads the header of a WAV file holding A-law or µ-law samples, leaving `data` at the start
+/// of the samples.
+fn read_companded_header<R>(data: &mut R) -> Option<CompandedHeader>
+where
+    R: Read + Seek,
+{
+    let mut riff = [0; 12];
+    data.read_exact(&mut riff).ok()?;
+    if &riff[..4] != b"RIFF" || &riff[8..] != b"WAVE" {
+        return None;
+    }
+
+    let mut format = None;
+    loop {
+        let mut chunk = [0; 8];
+        data.read_exact(&mut chunk).ok()?;
+        let len = u32::from_le_bytes(chunk[4..].try_into().unwrap());
+        match &chunk[..4] {
+            b"fmt " if (16..=1024).contains(&len) => {
+                let mut fmt = vec![0; len as usize];
+                data.read_exact(&mut fmt).ok()?;
+                let field = |at: usize| u16::from_le_bytes([fmt[at], fmt[at + 1]]);
+                let mut tag = field(0);
+                if tag == 0xFFFE && len >= 26 {
+                    // The format tag is the start of the sub format GUID.
+                    tag = field(24);
+                }
+                let sample_rate = u32::from_le_bytes(fmt[4..8].try_into().unwrap());
+                format = Some((tag, field(2), sample_rate, field(14)));
+                // Chunks are padded to an even length.
+                data.seek(SeekFrom::Current(i64::from(len % 2))).ok()?;
+            }
+            b"data" => {
+                let (format, channels, sample_rate, bits) = format?;
+                if !matches!(format, FORMAT_ALAW | FORMAT_MULAW) || bits != 8 {
+                    return None;
+                }
+                let start = data.stream_position().ok()?;
+                // Writers that did not finish leave the length unset.
+                let end = data.seek(SeekFrom::End(0)).ok()?;
+                data.seek(SeekFrom::Start(start)).ok()?;
+                let len = len.min((end - start).try_into().unwrap_or(u32::MAX));
+                let channels = ChannelCount::new(channels)?;
+                return Some(CompandedHeader {
+                    format,
+                    channels,
+                    sample_rate: SampleRate::new(sample_rate)?,
+                    start,
+                    // Whole frames only.
+                    len: len - len % channels.get() as u32,
+                });
+            }
+            _ => {
+                data.seek(SeekFrom::Current(i64::from(len + len % 2)))
+                    .ok()?;
+            }
+        }
+    }
+}
+
 impl<R> Source for WavDecoder<R>
 where
     R: Read + Seek,
@@ -171,21 +381,16 @@ where
 
     #[inline]
     fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
-        let file_len = self.reader.reader.duration();
+        let file_len = self.reader.frames(self.channels);
 
         let new_pos = pos.as_secs_f32() * self.sample_rate().get() as f32;
         let new_pos = new_pos as u32;
         let new_pos = new_pos.min(file_len); // saturate pos at the end of the source
 
         // make sure the next sample is for the right channel
-        let to_skip = self.reader.samples_read % self.channels().get() as u32;
+        let to_skip = self.reader.samples_read() % self.channels().get() as u32;
 
-        self.reader
-            .reader
-            .seek(new_pos)
-            .map_err(Arc::new)
-            .map_err(SeekError::HoundDecoder)?;
-        self.reader.samples_read = new_pos * self.channels().get() as u32;
+        self.reader.seek(new_pos, self.channels)?;
 
         for _ in 0..to_skip {
             self.next();
@@ -193,6 +398,13 @@ where
 
         Ok(())
     }
+
+    #[inline]
+    fn try_seek_samples(&mut self, frame: u64) -> Result<(), SeekError> {
+        let frames = self.reader.frames(self.channels);
+        self.reader
+            .seek(frame.min(frames as u64) as u32, self.channels)
+    }
 }
 
 impl<R> Iterator for WavDecoder<R>
@@ -164,13 +164,16 @@
 
 #[cfg(feature = "playback")]
 pub use cpal::{
-    self, traits::DeviceTrait, Device, Devices, DevicesError, InputDevices, OutputDevices,
+    self, traits::DeviceTrait, Device, Devices, DevicesError, HostId, InputDevices, OutputDevices,
     SupportedStreamConfig,
 };
 
 mod common;
+mod mock_stream;
 mod sink;
 mod spatial_sink;
+#[cfg(feature = "async")]
+mod spawn_blocking;
 #[cfg(feature = "playback")]
 pub mod stream;
 #[cfg(feature = "wav_output")]
@@ -186,16 +189,25 @@ pub mod math;
 pub mod microphone;
 pub mod mixer;
 pub mod queue;
+#[cfg(feature = "ring_buffer")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ring_buffer")))]
+pub mod ring_buffer;
 pub mod source;
 pub mod static_buffer;
 
-pub use crate::common::{BitDepth, ChannelCount, Sample, SampleRate};
+pub use crate::common::{
+    BitDepth, ChannelCount, ChannelLayout, ChannelPosition, Sample, SampleRate,
+};
 pub use crate::decoder::Decoder;
-pub use crate::sink::Sink;
+pub use crate::mock_stream::MockOutputStream;
+pub use crate::sink::{QueueFull, Sink, SinkParams};
 pub use crate::source::Source;
 pub use crate::spatial_sink::SpatialSink;
 #[cfg(feature = "playback")]
-pub use crate::stream::{play, OutputStream, OutputStreamBuilder, PlayError, StreamError};
+pub use crate::stream::{
+    available_hosts, output_stream_configs, play, play_source, OutputStream, OutputStreamBuilder,
+    PlayError, PlaybackClock, StreamError,
+};
 #[cfg(feature = "wav_output")]
 #[cfg_attr(docsrs, doc(cfg(feature = "wav_output")))]
 pub use crate::wav_output::wav_to_file;
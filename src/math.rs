@@ -101,6 +101,77 @@ pub(crate) fn duration_to_coefficient(duration: Duration, sample_rate: SampleRat
     f32::exp(-1.0 / (duration.as_secs_f32() * sample_rate.get() as f32))
 }
 
+/// Converts a frame index to a position a quarter frame past the start of that frame.
+///
+/// Seek implementations that round the position to a frame, either down or to the nearest,
+/// land on `frame`.
+pub(crate) fn frame_to_duration(frame: u64, sample_rate: SampleRate) -> Duration {
+    let rate = sample_rate.get() as u128;
+    let nanos = (4 * frame as u128 + 1) * 1_000_000_000 / (4 * rate);
+    Duration::new(
+        (nanos / 1_000_000_000) as u64,
+        (nanos % 1_000_000_000) as u32,
+    )
+}
+
+/// In place radix-2 FFT of a fixed length, which must be a power of two.
+///
+/// The twiddle factors are computed once when it is created.
+#[derive(Clone, Debug)]
+pub(crate) struct Fft {
+    len: usize,
+    // `(sin, cos)` of `-2 * PI * k / len` for each `k` below `len / 2`.
+    twiddles: Vec<(f32, f32)>,
+}
+
+impl Fft {
+    pub(crate) fn new(len: usize) -> Fft {
+        debug_assert!(len.is_power_of_two());
+        let angle = -2.0 * std::f32::consts::PI / len as f32;
+        Fft {
+            len,
+            twiddles: (0..len / 2).map(|k| (angle * k as f32).sin_cos()).collect(),
+        }
+    }
+
+    /// Transforms `re` and `im`, both as long as this FFT.
+    pub(crate) fn process(&self, re: &mut [f32], im: &mut [f32]) {
+        let len = self.len;
+        assert!(re.len() == len && im.len() == len);
+        let mut j = 0;
+        for i in 1..len {
+            let mut bit = len >> 1;
+            while j & bit != 0 {
+                j ^= bit;
+                bit >>= 1;
+            }
+            j |= bit;
+            if i < j {
+                re.swap(i, j);
+                im.swap(i, j);
+            }
+        }
+
+        let mut size = 2;
+        while size <= len {
+            let stride = len / size;
+            for start in (0..len).step_by(size) {
+                for k in 0..size / 2 {
+                    let (sin, cos) = self.twiddles[k * stride];
+                    let (a, b) = (start + k, start + k + size / 2);
+                    let t_re = re[b] * cos - im[b] * sin;
+                    let t_im = re[b] * sin + im[b] * cos;
+                    re[b] = re[a] - t_re;
+                    im[b] = im[a] - t_im;
+                    re[a] += t_re;
+                    im[a] += t_im;
+                }
+            }
+            size *= 2;
+        }
+    }
+}
+
 /// Utility macro for getting a `NonZero` from a literal. Especially
 /// useful for passing in `ChannelCount` and `Samplerate`.
 /// Equivalent to: `const { core::num::NonZero::new($n).unwrap() }`
@@ -306,4 +377,32 @@ mod test {
             );
         }
     }
+
+    #[test]
+    fn fft_matches_dft() {
+        let len = 16;
+        let input: Vec<f32> = (0..len).map(|i| ((i * 7) % 5) as f32 - 2.0).collect();
+        let mut re = input.clone();
+        let mut im = vec![0.0; len];
+        Fft::new(len).process(&mut re, &mut im);
+
+        for bin in 0..len {
+            let (mut dft_re, mut dft_im) = (0.0f32, 0.0f32);
+            for (i, sample) in input.iter().enumerate() {
+                let angle = -2.0 * std::f32::consts::PI * (bin * i) as f32 / len as f32;
+                dft_re += sample * angle.cos();
+                dft_im += sample * angle.sin();
+            }
+            assert!(
+                (re[bin] - dft_re).abs() < 1e-4,
+                "{bin}: {} {dft_re}",
+                re[bin]
+            );
+            assert!(
+                (im[bin] - dft_im).abs() < 1e-4,
+                "{bin}: {} {dft_im}",
+                im[bin]
+            );
+        }
+    }
 }
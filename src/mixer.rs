@@ -1,6 +1,6 @@
 //! Mixer that plays multiple sounds at the same time.
 
-use crate::common::{ChannelCount, SampleRate};
+use crate::common::{ChannelCount, ChannelLayout, SampleRate};
 use crate::source::{SeekError, Source, UniformSourceIterator};
 use crate::Sample;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -10,19 +10,43 @@ use std::time::Duration;
 /// Builds a new mixer.
 ///
 /// You can choose the characteristics of the output thanks to this constructor. All the sounds
-/// added to the mixer will be converted to these values.
+/// added to the mixer will be converted to these values: `sample_rate` is the rate the mixer
+/// runs at internally, sources with a different sample rate are resampled to it. This rate
+/// does not have to match the output device, the mixer can itself be resampled when played.
 ///
 /// After creating a mixer, you can add new sounds with the controller.
 ///
+/// The mixer does not process audio in blocks, every output sample is summed from the next
+/// sample of each source. Gain changes and ramps applied to the sources are therefore heard
+/// with sample accuracy, regardless of the span length of the sources. Controls of a
+/// [`Sink`](crate::Sink) are the exception, they are applied every 5 milliseconds.
+///
 /// Note that mixer without any input source behaves like an `Empty` (not: `Zero`) source,
 /// and thus, just after appending to a sink, the mixer is removed from the sink.
 /// As a result, input sources added to the mixer later might not be forwarded to the sink.
 /// Add `Zero` source to prevent detaching the mixer from sink.
 pub fn mixer(channels: ChannelCount, sample_rate: SampleRate) -> (Mixer, MixerSource) {
+    build(channels, None, sample_rate)
+}
+
+/// Builds a new mixer whose output has the speakers of `layout`.
+///
+/// Sounds added to the mixer are mixed to the speakers of the layout, see
+/// [`ChannelLayout`]. Otherwise the mixer behaves as one built with [`mixer`].
+pub fn mixer_with_layout(layout: ChannelLayout, sample_rate: SampleRate) -> (Mixer, MixerSource) {
+    build(layout.channel_count(), Some(layout), sample_rate)
+}
+
+fn build(
+    channels: ChannelCount,
+    layout: Option<ChannelLayout>,
+    sample_rate: SampleRate,
+) -> (Mixer, MixerSource) {
     let input = Mixer(Arc::new(Inner {
         has_pending: AtomicBool::new(false),
         pending_sources: Mutex::new(Vec::new()),
         channels,
+        layout,
         sample_rate,
     }));
 
@@ -45,25 +69,54 @@ struct Inner {
     has_pending: AtomicBool,
     pending_sources: Mutex<Vec<Box<dyn Source + Send>>>,
     channels: ChannelCount,
+    layout: Option<ChannelLayout>,
     sample_rate: SampleRate,
 }
 
 impl Mixer {
     /// Adds a new source to mix to the existing ones.
+    ///
+    /// The source is converted to the channel count and sample rate of the mixer if it does
+    /// not already match them. A mono source added to a stereo mixer plays on both channels.
+    /// If the mixer has a [`ChannelLayout`] the source is mixed to its speakers.
     #[inline]
     pub fn add<T>(&self, source: T)
     where
         T: Source + Send + 'static,
     {
-        let uniform_source =
-            UniformSourceIterator::new(source, self.0.channels, self.0.sample_rate);
-        self.0
-            .pending_sources
-            .lock()
-            .unwrap()
-            .push(Box::new(uniform_source) as Box<_>);
+        let uniform_source: Box<dyn Source + Send> = match self.0.layout {
+            Some(layout) => Box::new(UniformSourceIterator::with_layout(
+                source,
+                layout,
+                self.0.sample_rate,
+            )),
+            None => Box::new(UniformSourceIterator::new(
+                source,
+                self.0.channels,
+                self.0.sample_rate,
+            )),
+        };
+        self.0.pending_sources.lock().unwrap().push(uniform_source);
         self.0.has_pending.store(true, Ordering::SeqCst); // TODO: can we relax this ordering?
     }
+
+    /// The channel count all sources are converted to.
+    #[inline]
+    pub fn channels(&self) -> ChannelCount {
+        self.0.channels
+    }
+
+    /// The speaker layout sources are mixed to, if the mixer was built with one.
+    #[inline]
+    pub fn channel_layout(&self) -> Option<ChannelLayout> {
+        self.0.layout
+    }
+
+    /// The sample rate the mixer runs at and all sources are resampled to.
+    #[inline]
+    pub fn sample_rate(&self) -> SampleRate {
+        self.0.sample_rate
+    }
 }
 
 /// The output of the mixer. Implements `Source`.
@@ -183,10 +236,25 @@ impl MixerSource {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use crate::buffer::SamplesBuffer;
     use crate::math::nz;
     use crate::mixer;
     use crate::source::Source;
+    use crate::ChannelLayout;
+
+    #[test]
+    fn mixes_to_layout() {
+        let (tx, rx) = mixer::mixer_with_layout(ChannelLayout::Surround5_1, nz!(48000));
+        tx.add(SamplesBuffer::new(nz!(1), nz!(48000), vec![0.5]));
+        tx.add(SamplesBuffer::new(nz!(2), nz!(48000), vec![0.25, -0.25]));
+
+        assert_eq!(rx.channels(), nz!(6));
+        let frame: Vec<_> = rx.take(6).collect();
+        // Front left, front right, center, LFE, back left, back right.
+        assert_eq!(frame, [0.25, -0.25, 0.5, 0.0, 0.0, 0.0]);
+    }
 
     #[test]
     fn basic() {
@@ -267,6 +335,63 @@ mod tests {
         assert_eq!(rx.next(), None);
     }
 
+    #[test]
+    fn rate_conv_keeps_pitch() {
+        let (tx, rx) = mixer::mixer(nz!(1), nz!(44100));
+
+        // One second of a 441 Hz sine at 22.05 kHz.
+        let samples: Vec<f32> = (0..22050)
+            .map(|i| (2.0 * std::f32::consts::PI * 441.0 * i as f32 / 22050.0).sin())
+            .collect();
+        tx.add(SamplesBuffer::new(nz!(1), nz!(22050), samples));
+
+        let output: Vec<f32> = rx.collect();
+        assert!(output.len().abs_diff(44100) <= 2);
+        let crossings = output
+            .windows(2)
+            .filter(|w| w[0] < 0.0 && w[1] >= 0.0)
+            .count();
+        assert!(crossings.abs_diff(441) <= 1, "{crossings} periods");
+    }
+
+    #[test]
+    fn mono_in_stereo_keeps_pitch() {
+        let (tx, rx) = mixer::mixer(nz!(2), nz!(44100));
+
+        // One second of a 441 Hz sine.
+        let samples: Vec<f32> = (0..44100)
+            .map(|i| (2.0 * std::f32::consts::PI * 441.0 * i as f32 / 44100.0).sin())
+            .collect();
+        tx.add(SamplesBuffer::new(nz!(1), nz!(44100), samples));
+
+        let output: Vec<f32> = rx.collect();
+        assert_eq!(output.len(), 2 * 44100);
+        for channel in 0..2 {
+            let channel: Vec<f32> = output.iter().skip(channel).step_by(2).copied().collect();
+            let crossings = channel
+                .windows(2)
+                .filter(|w| w[0] < 0.0 && w[1] >= 0.0)
+                .count();
+            assert!(crossings.abs_diff(441) <= 1, "{crossings} periods");
+        }
+    }
+
+    #[test]
+    fn gain_ramp_is_sample_accurate() {
+        let (tx, rx) = mixer::mixer(nz!(1), nz!(1000));
+
+        // A source with spans, so it goes through the format conversion.
+        let ones = SamplesBuffer::new(nz!(1), nz!(1000), vec![1.0; 1000]);
+        let ones = ones.buffered().take_duration(Duration::from_millis(500));
+        tx.add(ones.linear_gain_ramp(Duration::from_millis(400), 0.0, 1.0, true));
+
+        let output: Vec<f32> = rx.collect();
+        assert_eq!(output.len(), 500);
+        // Every sample of the ramp differs from the previous one, there are no steps.
+        assert!(output[..400].windows(2).all(|w| w[1] > w[0]));
+        assert!(output[400..].iter().all(|s| *s == 1.0));
+    }
+
     #[test]
     fn start_afterwards() {
         let (tx, mut rx) = mixer::mixer(nz!(1), nz!(48000));
@@ -0,0 +1,93 @@
+//! An output stream without a device, for tests.
+
+use crate::common::{ChannelCount, SampleRate};
+use crate::mixer::{mixer, Mixer, MixerSource};
+use crate::Sample;
+
+/// An output stream that plays to a buffer instead of a device.
+///
+/// Like an [`OutputStream`](crate::OutputStream) it has a mixer to add sources to, but
+/// nothing plays until [`MockOutputStream::render`] pulls samples from it on the calling
+/// thread. This makes the output deterministic, so tests can assert on it without audio
+/// hardware.
+///
+/// # Example
+///
+/// ```
+/// use rodio::buffer::SamplesBuffer;
+/// use rodio::{nz, MockOutputStream};
+///
+/// let mut stream = MockOutputStream::new(nz!(1), nz!(48000));
+/// stream.mixer().add(SamplesBuffer::new(nz!(1), nz!(48000), vec![0.5, -0.5]));
+///
+/// let mut buffer = [1.0; 4];
+/// stream.render(&mut buffer);
+/// assert_eq!(buffer, [0.5, -0.5, 0.0, 0.0]);
+/// ```
+pub struct MockOutputStream {
+    mixer: Mixer,
+    source: MixerSource,
+    frames_played: u64,
+}
+
+impl MockOutputStream {
+    /// Creates a stream that plays `channels` channels at `sample_rate`.
+    pub fn new(channels: ChannelCount, sample_rate: SampleRate) -> MockOutputStream {
+        let (mixer, source) = mixer(channels, sample_rate);
+        MockOutputStream {
+            mixer,
+            source,
+            frames_played: 0,
+        }
+    }
+
+    /// Access the stream's mixer.
+    pub fn mixer(&self) -> &Mixer {
+        &self.mixer
+    }
+
+    /// Fills `buffer` with the next interleaved samples of the mixer, like a device would.
+    ///
+    /// Silence is written while nothing is playing. Start the buffer at a frame boundary,
+    /// its length is best a multiple of the channel count.
+    pub fn render(&mut self, buffer: &mut [Sample]) {
+        for sample in buffer.iter_mut() {
+            *sample = self.source.next().unwrap_or(0.0);
+        }
+        self.frames_played += (buffer.len() / self.mixer.channels().get() as usize) as u64;
+    }
+
+    /// Total number of frames rendered since the stream was created, see
+    /// [`OutputStream::frames_played`](crate::OutputStream::frames_played).
+    pub fn frames_played(&self) -> u64 {
+        self.frames_played
+    }
+
+    /// Total number of samples rendered since the stream was created, see
+    /// [`OutputStream::samples_requested`](crate::OutputStream::samples_requested).
+    pub fn samples_requested(&self) -> u64 {
+        self.frames_played * self.mixer.channels().get() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MockOutputStream;
+    use crate::math::nz;
+    use crate::source::{SineWave, Source};
+
+    #[test]
+    fn renders_tone() {
+        let mut stream = MockOutputStream::new(nz!(2), nz!(48000));
+        stream.mixer().add(SineWave::new(1000.0).amplify(0.5));
+
+        let mut buffer = vec![0.0; 96];
+        stream.render(&mut buffer);
+        assert_eq!(stream.frames_played(), 48);
+        assert_eq!(stream.samples_requested(), 96);
+        let expected: Vec<f32> = SineWave::new(1000.0).amplify(0.5).take(48).collect();
+        for (frame, expected) in buffer.chunks(2).zip(expected) {
+            assert_eq!(frame, [expected, expected]);
+        }
+    }
+}
@@ -1,5 +1,7 @@
 //! Queue that plays sounds one after the other.
 
+use std::any::Any;
+use std::fmt;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
@@ -29,11 +31,14 @@ pub fn queue(keep_alive_if_empty: bool) -> (Arc<SourcesQueueInput>, SourcesQueue
     let input = Arc::new(SourcesQueueInput {
         next_sounds: Mutex::new(Vec::new()),
         keep_alive_if_empty: AtomicBool::new(keep_alive_if_empty),
+        on_end: Mutex::new(None),
     });
 
     let output = SourcesQueueOutput {
         current: Box::new(Empty::new()) as Box<_>,
         signal_after_end: None,
+        current_appended: false,
+        current_label: None,
         input: input.clone(),
     };
 
@@ -44,13 +49,71 @@ pub fn queue(keep_alive_if_empty: bool) -> (Arc<SourcesQueueInput>, SourcesQueue
 
 type Sound = Box<dyn Source + Send>;
 type SignalDone = Option<Sender<()>>;
+type OnEnd = Box<dyn FnMut(Option<&Label>) + Send>;
+
+/// User data attached to a sound in a queue, for example the id of the track it plays.
+///
+/// See [`SourcesQueueInput::append_labeled_with_signal`] and
+/// [`Sink::append_labeled`](crate::Sink::append_labeled). Clones share the same value and
+/// compare equal.
+///
+/// The queue drops its clone on the thread playing it, usually the audio thread. If that is
+/// the last clone the value is freed there, so prefer values that are cheap to drop such as
+/// ids, or keep a clone around.
+#[derive(Clone)]
+pub struct Label(Arc<dyn Any + Send + Sync>);
+
+impl Label {
+    /// Wraps `value` in a label.
+    pub fn new<T>(value: T) -> Label
+    where
+        T: Any + Send + Sync,
+    {
+        Label(Arc::new(value))
+    }
+
+    /// Returns the value of the label if it is a `T`.
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.0.downcast_ref()
+    }
+}
+
+impl PartialEq for Label {
+    fn eq(&self, other: &Label) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for Label {}
+
+impl fmt::Debug for Label {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Label").finish_non_exhaustive()
+    }
+}
+
+/// Format and length of a sound waiting in a queue, see [`SourcesQueueInput::queued`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueuedSound {
+    /// The number of channels of the sound.
+    pub channels: ChannelCount,
+    /// The sample rate of the sound.
+    pub sample_rate: SampleRate,
+    /// The length of the sound, if known.
+    pub total_duration: Option<Duration>,
+    /// The label the sound was appended with, if any.
+    pub label: Option<Label>,
+}
 
 /// The input of the queue.
 pub struct SourcesQueueInput {
-    next_sounds: Mutex<Vec<(Sound, SignalDone)>>,
+    next_sounds: Mutex<Vec<(Sound, SignalDone, Option<Label>)>>,
 
     // See constructor.
     keep_alive_if_empty: AtomicBool,
+
+    // Called when a sound appended to the queue ends.
+    on_end: Mutex<Option<OnEnd>>,
 }
 
 impl SourcesQueueInput {
@@ -63,7 +126,7 @@ impl SourcesQueueInput {
         self.next_sounds
             .lock()
             .unwrap()
-            .push((Box::new(source) as Box<_>, None));
+            .push((Box::new(source) as Box<_>, None, None));
     }
 
     /// Adds a new source to the end of the queue.
@@ -73,6 +136,19 @@ impl SourcesQueueInput {
     /// Enable the feature flag `crossbeam-channel` in rodio to use a `crossbeam_channel::Receiver` instead.
     #[inline]
     pub fn append_with_signal<T>(&self, source: T) -> Receiver<()>
+    where
+        T: Source + Send + 'static,
+    {
+        self.append_labeled_with_signal(source, None)
+    }
+
+    /// Adds a new source with a label to the end of the queue.
+    ///
+    /// The label is returned by [`SourcesQueueInput::queued`] and passed to the callback of
+    /// [`SourcesQueueInput::set_on_end`]. The `Receiver` will be signalled when the sound has
+    /// finished playing.
+    #[inline]
+    pub fn append_labeled_with_signal<T>(&self, source: T, label: Option<Label>) -> Receiver<()>
     where
         T: Source + Send + 'static,
     {
@@ -80,10 +156,23 @@ impl SourcesQueueInput {
         self.next_sounds
             .lock()
             .unwrap()
-            .push((Box::new(source) as Box<_>, Some(tx)));
+            .push((Box::new(source) as Box<_>, Some(tx), label));
         rx
     }
 
+    /// Calls `callback` with the label of each sound appended to the queue when it ends,
+    /// `None` for sounds without a label.
+    ///
+    /// The callback runs on the thread playing the queue, usually the audio thread, so it
+    /// should return quickly. Sounds removed by [`SourcesQueueInput::clear`] never start and
+    /// are not reported, neither is a sound that ends while the callback is being replaced.
+    pub fn set_on_end<F>(&self, callback: F)
+    where
+        F: FnMut(Option<&Label>) + Send + 'static,
+    {
+        *self.on_end.lock().unwrap() = Some(Box::new(callback));
+    }
+
     /// Sets whether the queue stays alive if there's no more sound to play.
     ///
     /// See also the constructor.
@@ -92,6 +181,24 @@ impl SourcesQueueInput {
             .store(keep_alive_if_empty, Ordering::Release);
     }
 
+    /// Returns the format and length of the sounds waiting to be played, in order.
+    ///
+    /// The sound that is playing is not included. Only the state of the sounds is read, they
+    /// are not consumed.
+    pub fn queued(&self) -> Vec<QueuedSound> {
+        self.next_sounds
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(sound, _, label)| QueuedSound {
+                channels: sound.channels(),
+                sample_rate: sound.sample_rate(),
+                total_duration: sound.total_duration(),
+                label: label.clone(),
+            })
+            .collect()
+    }
+
     /// Removes all the sounds from the queue. Returns the number of sounds cleared.
     pub fn clear(&self) -> usize {
         let mut sounds = self.next_sounds.lock().unwrap();
@@ -108,6 +215,10 @@ pub struct SourcesQueueOutput {
     // Signal this sender before picking from `next`.
     signal_after_end: Option<Sender<()>>,
 
+    // Whether `current` was appended to the queue, rather than silence, and its label.
+    current_appended: bool,
+    current_label: Option<Label>,
+
     // The next sounds.
     input: Arc<SourcesQueueInput>,
 }
@@ -216,25 +327,37 @@ impl SourcesQueueOutput {
         if let Some(signal_after_end) = self.signal_after_end.take() {
             let _ = signal_after_end.send(());
         }
+        if std::mem::take(&mut self.current_appended) {
+            let label = self.current_label.take();
+            // Never wait for `set_on_end` on the audio thread, the end of this sound is not
+            // reported if the callback is being replaced.
+            if let Ok(mut on_end) = self.input.on_end.try_lock() {
+                if let Some(on_end) = on_end.as_mut() {
+                    on_end(label.as_ref());
+                }
+            }
+        }
 
-        let (next, signal_after_end) = {
+        let (next, signal_after_end, label) = {
             let mut next = self.input.next_sounds.lock().unwrap();
 
             if next.is_empty() {
                 let silence = Box::new(Zero::new_samples(nz!(1), nz!(44100), THRESHOLD)) as Box<_>;
                 if self.input.keep_alive_if_empty.load(Ordering::Acquire) {
                     // Play a short silence in order to avoid spinlocking.
-                    (silence, None)
+                    (silence, None, None)
                 } else {
                     return Err(());
                 }
             } else {
+                self.current_appended = true;
                 next.remove(0)
             }
         };
 
         self.current = next;
         self.signal_after_end = signal_after_end;
+        self.current_label = label;
         Ok(())
     }
 }
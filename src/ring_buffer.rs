@@ -0,0 +1,246 @@
+//! A source fed with samples from another thread through a lock-free ring buffer.
+//!
+//! This lets audio generated outside of rodio, for example by a DSP engine running on its own
+//! thread, be played through a [`Mixer`](crate::mixer::Mixer) or [`Sink`](crate::Sink).
+//!
+//! # Example
+//!
+//! ```
+//! use rodio::ring_buffer;
+//! use rodio::nz;
+//!
+//! let (mut producer, source) = ring_buffer::ring_buffer(nz!(2), nz!(48000), 4800);
+//! std::thread::spawn(move || {
+//!     while producer.push_frame(&[0.1, -0.1]).is_ok() {}
+//! });
+//! // `source` can now be played, it outputs silence whenever the producer falls behind.
+//! ```
+
+use std::time::Duration;
+
+use crate::common::{assert_error_traits, ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Builds a ring buffer that holds up to `capacity` frames.
+///
+/// The [`Producer`] is meant to be moved to the thread generating the audio, the
+/// [`RingBufferSource`] can be played like any other source.
+pub fn ring_buffer(
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+    capacity: usize,
+) -> (Producer, RingBufferSource) {
+    let (producer, consumer) = rtrb::RingBuffer::new(capacity * channels.get() as usize);
+    let producer = Producer {
+        inner: producer,
+        channels,
+    };
+    let source = RingBufferSource {
+        inner: consumer,
+        channels,
+        sample_rate,
+        pos_in_frame: 0,
+        silence_left: 0,
+    };
+    (producer, source)
+}
+
+/// Returned by [`Producer::push_frame`] when there is no room for another frame.
+#[derive(Debug, thiserror::Error, Clone, Copy, PartialEq, Eq)]
+#[error("The ring buffer is full")]
+pub struct BufferFull;
+assert_error_traits! {BufferFull}
+
+/// The writing end of a ring buffer, see [`ring_buffer`].
+#[derive(Debug)]
+pub struct Producer {
+    inner: rtrb::Producer<Sample>,
+    channels: ChannelCount,
+}
+
+impl Producer {
+    /// Appends a single frame, one sample for each channel.
+    ///
+    /// The frame is made available to the source as a whole, the source never plays part of a
+    /// frame.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `frame` does not match the channel count.
+    pub fn push_frame(&mut self, frame: &[Sample]) -> Result<(), BufferFull> {
+        assert_eq!(
+            frame.len(),
+            self.channels.get() as usize,
+            "a frame needs one sample per channel"
+        );
+        let chunk = self
+            .inner
+            .write_chunk_uninit(frame.len())
+            .map_err(|_| BufferFull)?;
+        chunk.fill_from_iter(frame.iter().copied());
+        Ok(())
+    }
+
+    /// Appends as many whole frames from interleaved `samples` as fit. Returns the number of
+    /// frames written.
+    pub fn push_frames(&mut self, samples: &[Sample]) -> usize {
+        let channels = self.channels.get() as usize;
+        let frames = (samples.len() / channels).min(self.free_frames());
+        if frames > 0 {
+            let chunk = self
+                .inner
+                .write_chunk_uninit(frames * channels)
+                .expect("checked there are enough free slots");
+            chunk.fill_from_iter(samples.iter().copied());
+        }
+        frames
+    }
+
+    /// The number of frames that can be pushed before the buffer is full.
+    pub fn free_frames(&self) -> usize {
+        self.inner.slots() / self.channels.get() as usize
+    }
+
+    /// Returns `true` if no more frames can be pushed right now.
+    pub fn is_full(&self) -> bool {
+        self.free_frames() == 0
+    }
+
+    /// Returns `true` if the source has been dropped.
+    pub fn is_abandoned(&self) -> bool {
+        self.inner.is_abandoned()
+    }
+}
+
+/// The reading end of a ring buffer, see [`ring_buffer`].
+///
+/// Plays silence while the buffer is empty and ends once the [`Producer`] is dropped and
+/// all frames have been played.
+#[derive(Debug)]
+pub struct RingBufferSource {
+    inner: rtrb::Consumer<Sample>,
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+    // Zero at frame boundaries, frames are pushed whole so only there can the buffer run
+    // empty.
+    pos_in_frame: usize,
+    // Samples left of a silent frame started on an underrun. No samples are popped until
+    // it is done so the channels stay aligned.
+    silence_left: usize,
+}
+
+impl Iterator for RingBufferSource {
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        if self.silence_left > 0 {
+            self.silence_left -= 1;
+            return Some(0.0);
+        }
+
+        let sample = match self.inner.pop() {
+            Ok(sample) => sample,
+            // The producer may have pushed its last frames just before it was dropped, so
+            // check again once it is known that nothing more will be pushed.
+            Err(_) if self.pos_in_frame == 0 && self.inner.is_abandoned() => {
+                self.inner.pop().ok()?
+            }
+            Err(_) if self.pos_in_frame == 0 => {
+                self.silence_left = self.channels.get() as usize - 1;
+                return Some(0.0);
+            }
+            Err(_) => 0.0,
+        };
+        self.pos_in_frame = (self.pos_in_frame + 1) % self.channels.get() as usize;
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.inner.slots(), None)
+    }
+}
+
+impl Source for RingBufferSource {
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.channels
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::{ring_buffer, BufferFull};
+    use crate::math::nz;
+
+    #[test]
+    fn reports_full() {
+        let (mut producer, _source) = ring_buffer(nz!(2), nz!(48000), 2);
+        assert_eq!(producer.push_frame(&[1.0, 2.0]), Ok(()));
+        assert_eq!(producer.push_frames(&[3.0, 4.0, 5.0, 6.0]), 1);
+        assert!(producer.is_full());
+        assert_eq!(producer.push_frame(&[7.0, 8.0]), Err(BufferFull));
+    }
+
+    #[test]
+    fn silence_on_underrun() {
+        let (mut producer, mut source) = ring_buffer(nz!(2), nz!(48000), 4);
+        producer.push_frame(&[1.0, 2.0]).unwrap();
+        assert_eq!(source.next(), Some(1.0));
+        assert_eq!(source.next(), Some(2.0));
+        assert_eq!(source.next(), Some(0.0));
+        // The silent frame is finished even if a frame arrives halfway through it.
+        producer.push_frame(&[3.0, 4.0]).unwrap();
+        assert_eq!(source.next(), Some(0.0));
+        assert_eq!(source.next(), Some(3.0));
+        assert_eq!(source.next(), Some(4.0));
+        drop(producer);
+        assert_eq!(source.next(), None);
+    }
+
+    #[test]
+    fn frames_from_another_thread() {
+        let (mut producer, source) = ring_buffer(nz!(2), nz!(48000), 16);
+        let writer = thread::spawn(move || {
+            // Start at one, a frame of zeros would look like silence.
+            for i in 1..=1000 {
+                let frame = [i as f32, -(i as f32)];
+                while producer.push_frame(&frame).is_err() {
+                    thread::yield_now();
+                }
+            }
+        });
+
+        let samples: Vec<f32> = source.collect();
+        writer.join().unwrap();
+
+        assert_eq!(samples.len() % 2, 0);
+        let mut next = 1;
+        for frame in samples.chunks_exact(2) {
+            if frame == [0.0, 0.0] {
+                continue;
+            }
+            assert_eq!(frame, [next as f32, -(next as f32)]);
+            next += 1;
+        }
+        assert_eq!(next, 1001);
+    }
+}
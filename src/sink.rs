@@ -3,14 +3,16 @@ use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 #[cfg(feature = "crossbeam-channel")]
-use crossbeam_channel::{Receiver, Sender};
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender};
 use dasp_sample::FromSample;
 #[cfg(not(feature = "crossbeam-channel"))]
-use std::sync::mpsc::{Receiver, Sender};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender};
 
+use crate::common::assert_error_traits;
 use crate::mixer::Mixer;
-use crate::source::SeekError;
-use crate::{queue, source::Done, Source};
+use crate::queue::{Label, QueuedSound};
+use crate::source::{RateAdjustment, SeekError};
+use crate::{math, queue, source::Done, Source};
 
 /// Handle to a device that outputs sounds.
 ///
@@ -22,17 +24,43 @@ pub struct Sink {
 
     controls: Arc<Controls>,
     sound_count: Arc<AtomicUsize>,
+    max_queue_len: AtomicUsize,
 
     detached: bool,
 }
 
+/// Returned by [`Sink::try_append`] when the queue already holds the maximum number of sounds.
+#[derive(Debug, thiserror::Error, Clone, Copy, PartialEq, Eq)]
+#[error("The queue of the sink is full")]
+pub struct QueueFull;
+assert_error_traits! {QueueFull}
+
+/// Playback parameters of a [`Sink`] that are changed together with [`Sink::apply`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SinkParams {
+    /// The volume, see [`Sink::set_volume`].
+    pub volume: f32,
+    /// Whether the sink is muted, see [`Sink::set_muted`].
+    pub muted: bool,
+    /// The play speed, see [`Sink::set_speed`].
+    pub speed: f32,
+    /// The rate adjustment in parts per million, see [`Sink::set_rate_adjustment`].
+    pub rate_adjustment: f32,
+}
+
 struct SeekOrder {
-    pos: Duration,
+    target: SeekTarget,
     feedback: Sender<Result<(), SeekError>>,
 }
 
+#[derive(Clone, Copy)]
+enum SeekTarget {
+    Time(Duration),
+    Frame(u64),
+}
+
 impl SeekOrder {
-    fn new(pos: Duration) -> (Self, Receiver<Result<(), SeekError>>) {
+    fn new(target: SeekTarget) -> (Self, Receiver<Result<(), SeekError>>) {
         #[cfg(not(feature = "crossbeam-channel"))]
         let (tx, rx) = {
             use std::sync::mpsc;
@@ -44,14 +72,27 @@ impl SeekOrder {
             use crossbeam_channel::bounded;
             bounded(1)
         };
-        (Self { pos, feedback: tx }, rx)
+        (
+            Self {
+                target,
+                feedback: tx,
+            },
+            rx,
+        )
     }
 
-    fn attempt<S>(self, maybe_seekable: &mut S)
+    /// Seeks `maybe_seekable`, calling `seeked` before reporting the result.
+    fn attempt<S>(self, maybe_seekable: &mut S, seeked: impl FnOnce(&mut S))
     where
         S: Source,
     {
-        let res = maybe_seekable.try_seek(self.pos);
+        let res = match self.target {
+            SeekTarget::Time(pos) => maybe_seekable.try_seek(pos),
+            SeekTarget::Frame(frame) => maybe_seekable.try_seek_samples(frame),
+        };
+        if res.is_ok() {
+            seeked(maybe_seekable);
+        }
         let _ignore_receiver_dropped = self.feedback.send(res);
     }
 }
@@ -59,11 +100,28 @@ impl SeekOrder {
 struct Controls {
     pause: AtomicBool,
     volume: Mutex<f32>,
+    // Whether volume changes are ramped, set by `Sink::set_volume_db`.
+    volume_smoothed: AtomicBool,
+    muted: AtomicBool,
     stopped: AtomicBool,
+    // Set when the sink is dropped with a fade out configured.
+    fading_out: AtomicBool,
+    fade_on_drop: Mutex<Duration>,
     speed: Mutex<f32>,
+    rate_adjustment: Mutex<f32>,
+    // Target and length of the ramp set by `Sink::set_speed_smooth`.
+    speed_ramp: Mutex<(f32, Duration)>,
     to_clear: Mutex<u32>,
     seek: Mutex<Option<SeekOrder>>,
+    loop_region: Mutex<Option<(Duration, Duration)>>,
     position: Mutex<Duration>,
+    // Total duration of the sound that is playing.
+    duration: Mutex<Option<Duration>>,
+    // Sample rate of the sound that is playing times its speed.
+    effective_sample_rate: Mutex<Option<f32>>,
+    // Held while `Sink::apply` writes its changes and while the audio thread reads them, so a
+    // batch is picked up as a whole.
+    batch: Mutex<()>,
 }
 
 impl Sink {
@@ -86,13 +144,24 @@ impl Sink {
             controls: Arc::new(Controls {
                 pause: AtomicBool::new(false),
                 volume: Mutex::new(1.0),
+                volume_smoothed: AtomicBool::new(false),
+                muted: AtomicBool::new(false),
                 stopped: AtomicBool::new(false),
+                fading_out: AtomicBool::new(false),
+                fade_on_drop: Mutex::new(Duration::ZERO),
                 speed: Mutex::new(1.0),
+                rate_adjustment: Mutex::new(0.0),
+                speed_ramp: Mutex::new((1.0, Duration::ZERO)),
                 to_clear: Mutex::new(0),
                 seek: Mutex::new(None),
+                loop_region: Mutex::new(None),
                 position: Mutex::new(Duration::ZERO),
+                duration: Mutex::new(None),
+                effective_sample_rate: Mutex::new(None),
+                batch: Mutex::new(()),
             }),
             sound_count: Arc::new(AtomicUsize::new(0)),
+            max_queue_len: AtomicUsize::new(usize::MAX),
             detached: false,
         };
         (sink, queue_rx)
@@ -101,13 +170,50 @@ impl Sink {
     /// Appends a sound to the queue of sounds to play.
     #[inline]
     pub fn append<S>(&self, source: S)
+    where
+        S: Source + Send + 'static,
+        f32: FromSample<S::Item>,
+    {
+        self.append_with_label(source, None, false);
+    }
+
+    /// Appends a sound to the queue with a label, for example the id of the track it plays.
+    ///
+    /// The label is returned by [`Sink::queue_info`] while the sound waits and passed to the
+    /// callback of [`Sink::set_on_source_end`] once it ends. Read it back with
+    /// [`Label::downcast_ref`]. The label may be dropped on the audio thread, see [`Label`].
+    pub fn append_labeled<S, T>(&self, source: S, label: T)
+    where
+        S: Source + Send + 'static,
+        f32: FromSample<S::Item>,
+        T: std::any::Any + Send + Sync,
+    {
+        self.append_with_label(source, Some(Label::new(label)), false);
+    }
+
+    /// Calls `callback` each time a sound of the sink ends, with the label it was appended
+    /// with or `None` if it has none.
+    ///
+    /// Sounds that are skipped or stopped end as well, but sounds removed by [`Sink::clear`]
+    /// are never reported. Neither are sounds that end while the callback is being replaced.
+    /// The callback runs on the audio thread, it should return quickly, for example by sending
+    /// the label over a channel.
+    pub fn set_on_source_end<F>(&self, callback: F)
+    where
+        F: FnMut(Option<&Label>) + Send + 'static,
+    {
+        self.queue_tx.set_on_end(callback);
+    }
+
+    // `reserved` is set if the sound was already counted, see `Sink::try_append`.
+    fn append_with_label<S>(&self, source: S, label: Option<Label>, reserved: bool)
     where
         S: Source + Send + 'static,
         f32: FromSample<S::Item>,
     {
         // Wait for the queue to flush then resume stopped playback
         if self.controls.stopped.load(Ordering::SeqCst) {
-            if self.sound_count.load(Ordering::SeqCst) > 0 {
+            if self.sound_count.load(Ordering::SeqCst) > reserved as usize {
                 self.sleep_until_end();
             }
             self.controls.stopped.store(false, Ordering::SeqCst);
@@ -116,7 +222,14 @@ impl Sink {
         let controls = self.controls.clone();
 
         let start_played = AtomicBool::new(false);
-
+        let mut was_muted = false;
+        // Time left of the fade out started when the sink was dropped.
+        let mut fade_left: Option<Duration> = None;
+
+        let rate_adjustment = *self.controls.rate_adjustment.lock().unwrap();
+        let mut source = RateAdjustment::new(source, rate_adjustment);
+        // A new sound starts at the speed a ramp in progress is heading to.
+        source.ramp_speed(self.controls.speed_ramp.lock().unwrap().0, Duration::ZERO);
         let source = source
             .speed(1.0)
             // Must be placed before pausable but after speed & delay
@@ -127,9 +240,24 @@ impl Sink {
             .stoppable()
             // If you change the duration update the docs for try_seek!
             .periodic_access(Duration::from_millis(5), move |src| {
+                if controls.fading_out.load(Ordering::SeqCst) {
+                    match &mut fade_left {
+                        None => {
+                            let fade = *controls.fade_on_drop.lock().unwrap();
+                            src.inner_mut().inner_mut().ramp_factor(0.0, fade);
+                            fade_left = Some(fade);
+                        }
+                        Some(left) => *left = left.saturating_sub(Duration::from_millis(5)),
+                    }
+                    if fade_left.is_some_and(|left| left.is_zero()) {
+                        controls.stopped.store(true, Ordering::SeqCst);
+                    }
+                }
                 if controls.stopped.load(Ordering::SeqCst) {
                     src.stop();
                     *controls.position.lock().unwrap() = Duration::ZERO;
+                    *controls.duration.lock().unwrap() = None;
+                    *controls.effective_sample_rate.lock().unwrap() = None;
                 }
                 {
                     let mut to_clear = controls.to_clear.lock().unwrap();
@@ -137,26 +265,123 @@ impl Sink {
                         src.inner_mut().skip();
                         *to_clear -= 1;
                         *controls.position.lock().unwrap() = Duration::ZERO;
+                        *controls.duration.lock().unwrap() = None;
                     } else {
-                        *controls.position.lock().unwrap() = src.inner().inner().inner().inner().get_pos();
+                        let track = src.inner().inner().inner().inner();
+                        *controls.position.lock().unwrap() = track.get_pos();
+                        *controls.duration.lock().unwrap() = track.total_duration();
                     }
                 }
+                let batch = controls.batch.lock().unwrap();
                 let amp = src.inner_mut().inner_mut();
-                amp.set_factor(*controls.volume.lock().unwrap());
+                let muted = controls.muted.load(Ordering::SeqCst);
+                let volume = if muted {
+                    0.0
+                } else {
+                    *controls.volume.lock().unwrap()
+                };
+                let smoothed = controls.volume_smoothed.load(Ordering::SeqCst)
+                    || muted != std::mem::replace(&mut was_muted, muted);
+                if fade_left.is_some() {
+                    // Keep fading out.
+                } else if smoothed && start_played.load(Ordering::SeqCst) {
+                    amp.ramp_factor(volume, Duration::from_millis(5));
+                } else {
+                    amp.set_factor(volume);
+                }
                 amp.inner_mut()
                     .set_paused(controls.pause.load(Ordering::SeqCst));
-                amp.inner_mut()
-                    .inner_mut()
-                    .inner_mut()
-                    .set_factor(*controls.speed.lock().unwrap());
+                let speed = amp.inner_mut().inner_mut().inner_mut();
+                let speed_factor = *controls.speed.lock().unwrap();
+                speed.set_factor(speed_factor);
+                let rate = speed.inner_mut();
+                rate.set_ppm(*controls.rate_adjustment.lock().unwrap());
+                let (target, over) = *controls.speed_ramp.lock().unwrap();
+                rate.ramp_speed(target, over);
+                *controls.effective_sample_rate.lock().unwrap() = Some(
+                    rate.sample_rate().get() as f32 * speed_factor * rate.current_speed(),
+                );
+                drop(batch);
+                if let Some((start, end)) = *controls.loop_region.lock().unwrap() {
+                    if amp.inner().inner().get_pos() >= end {
+                        let _ = amp.try_seek(start);
+                    }
+                }
                 if let Some(seek) = controls.seek.lock().unwrap().take() {
-                    seek.attempt(amp)
+                    seek.attempt(amp, |amp| {
+                        *controls.position.lock().unwrap() = amp.inner().inner().get_pos();
+                    })
                 }
                 start_played.store(true, Ordering::SeqCst);
             });
-        self.sound_count.fetch_add(1, Ordering::Relaxed);
+        if !reserved {
+            self.sound_count.fetch_add(1, Ordering::Relaxed);
+        }
         let source = Done::new(source, self.sound_count.clone());
-        *self.sleep_until_end.lock().unwrap() = Some(self.queue_tx.append_with_signal(source));
+        *self.sleep_until_end.lock().unwrap() =
+            Some(self.queue_tx.append_labeled_with_signal(source, label));
+    }
+
+    /// Fades out what is playing over `duration` when the sink is dropped, instead of cutting
+    /// it off with a click.
+    ///
+    /// The fade runs on the audio thread after the drop, the sounds are removed once it ends.
+    /// It does not apply to [`Sink::stop`] or to a detached sink.
+    pub fn with_fade_on_drop(self, duration: Duration) -> Sink {
+        *self.controls.fade_on_drop.lock().unwrap() = duration;
+        self
+    }
+
+    /// Appends a sound to the queue and resumes playback if the sink is paused.
+    ///
+    /// [`Sink::append`] on a paused sink only queues the sound, this is what a "play this
+    /// file" action usually expects instead.
+    #[inline]
+    pub fn append_and_play<S>(&self, source: S)
+    where
+        S: Source + Send + 'static,
+        f32: FromSample<S::Item>,
+    {
+        self.append(source);
+        self.play();
+    }
+
+    /// Appends a sound to the queue unless it already holds the maximum number of sounds.
+    ///
+    /// The sound that is playing counts towards the limit set with
+    /// [`Sink::set_max_queue_len`]. If the queue is full the source is dropped and
+    /// [`QueueFull`] is returned, this never blocks.
+    pub fn try_append<S>(&self, source: S) -> Result<(), QueueFull>
+    where
+        S: Source + Send + 'static,
+        f32: FromSample<S::Item>,
+    {
+        // Reserve the slot before appending so that concurrent calls can not exceed the
+        // limit together.
+        let max = self.max_queue_len.load(Ordering::Relaxed);
+        self.sound_count
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |len| {
+                (len < max).then_some(len + 1)
+            })
+            .map_err(|_| QueueFull)?;
+        self.append_with_label(source, None, true);
+        Ok(())
+    }
+
+    /// Limits the number of sounds [`Sink::try_append`] accepts, `None` removes the limit.
+    ///
+    /// Sounds added with [`Sink::append`] are not limited but do count towards the limit.
+    pub fn set_max_queue_len(&self, len: Option<usize>) {
+        self.max_queue_len
+            .store(len.unwrap_or(usize::MAX), Ordering::Relaxed);
+    }
+
+    /// Returns the limit set with [`Sink::set_max_queue_len`].
+    pub fn max_queue_len(&self) -> Option<usize> {
+        match self.max_queue_len.load(Ordering::Relaxed) {
+            usize::MAX => None,
+            len => Some(len),
+        }
     }
 
     /// Gets the volume of the sound.
@@ -175,6 +400,43 @@ impl Sink {
     #[inline]
     pub fn set_volume(&self, value: f32) {
         *self.controls.volume.lock().unwrap() = value;
+        self.controls.volume_smoothed.store(false, Ordering::SeqCst);
+    }
+
+    /// Gets the volume of the sound in decibels.
+    ///
+    /// Returns `f32::NEG_INFINITY` for a volume of `0.0`. Like [`Sink::volume`] this ignores
+    /// [`Sink::set_muted`].
+    #[inline]
+    pub fn volume_db(&self) -> f32 {
+        math::linear_to_db(self.volume())
+    }
+
+    /// Changes the volume of the sound in decibels.
+    ///
+    /// `0.0` dB is the "normal" volume, `-6.0` dB roughly halves the amplitude and
+    /// `f32::NEG_INFINITY` mutes the sink. Unlike [`Sink::set_volume`] the change is not applied
+    /// at once, the volume moves linearly to the new value over 5 ms to avoid audible clicks
+    /// (zipper noise) when the volume changes often.
+    #[inline]
+    pub fn set_volume_db(&self, db: f32) {
+        *self.controls.volume.lock().unwrap() = math::db_to_linear(db);
+        self.controls.volume_smoothed.store(true, Ordering::SeqCst);
+    }
+
+    /// Mutes or unmutes the sound without changing its volume.
+    ///
+    /// The volume fades out or back in over 5 ms to avoid clicks. [`Sink::volume`] keeps
+    /// returning the volume the sink plays at once unmuted.
+    #[inline]
+    pub fn set_muted(&self, muted: bool) {
+        self.controls.muted.store(muted, Ordering::SeqCst);
+    }
+
+    /// Returns `true` if the sink is muted, see [`Sink::set_muted`].
+    #[inline]
+    pub fn is_muted(&self) -> bool {
+        self.controls.muted.load(Ordering::SeqCst)
     }
 
     /// Gets the speed of the sound.
@@ -203,6 +465,113 @@ impl Sink {
         *self.controls.speed.lock().unwrap() = value;
     }
 
+    /// Moves the play speed of the sound linearly to `target` over `over`, like a tape
+    /// machine starting or stopping.
+    ///
+    /// Unlike [`Sink::set_speed`] the change has no clicks, the sound is resampled at a rate
+    /// that follows the ramp. A `target` of `0.0` slows the sound down to a halt, ramp back
+    /// to `1.0` to continue. The speed multiplies the one set with [`Sink::set_speed`], which
+    /// [`Sink::speed`] returns. [`Sink::get_pos`] does not follow the ramp, it counts the
+    /// played samples as if the speed were `1.0`.
+    #[inline]
+    pub fn set_speed_smooth(&self, target: f32, over: Duration) {
+        *self.controls.speed_ramp.lock().unwrap() = (target, over);
+    }
+
+    /// Returns the rate at which the samples of the sound that is playing are consumed: its
+    /// sample rate times the speed of [`Sink::set_speed`] and [`Sink::set_speed_smooth`].
+    ///
+    /// A 44.1 kHz sound played at a speed of `2.0` returns `88200.0`. The value is updated
+    /// every 5 ms by the audio thread. Returns `None` if nothing is playing.
+    pub fn effective_sample_rate(&self) -> Option<f32> {
+        if self.empty() {
+            return None;
+        }
+        *self.controls.effective_sample_rate.lock().unwrap()
+    }
+
+    /// Gets the speed of the sound in semitones, see [`Sink::set_pitch_semitones`].
+    #[inline]
+    pub fn pitch_semitones(&self) -> f32 {
+        12.0 * self.speed().log2()
+    }
+
+    /// Changes the play speed of the sound in semitones, `12.0` being an octave higher.
+    ///
+    /// This is [`Sink::set_speed`] in musical units: the speed is set to `2^(semitones / 12)`,
+    /// so both the pitch and the tempo change.
+    #[inline]
+    pub fn set_pitch_semitones(&self, semitones: f32) {
+        self.set_speed(2f32.powf(semitones / 12.0));
+    }
+
+    /// Changes several playback parameters at once.
+    ///
+    /// `change` gets a copy of the current parameters and edits it, only the fields it changed
+    /// are written back. The edits are committed together, the audio thread picks them up on
+    /// the same buffer, so there is no moment where for example the new volume plays at the
+    /// old speed. The volume is applied at once like with [`Sink::set_volume`] if it changed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let (sink, _queue) = rodio::Sink::new();
+    /// sink.apply(|params| {
+    ///     params.volume = 0.5;
+    ///     params.speed = 1.25;
+    /// });
+    /// assert_eq!(sink.volume(), 0.5);
+    /// assert_eq!(sink.speed(), 1.25);
+    /// ```
+    pub fn apply<F>(&self, change: F)
+    where
+        F: FnOnce(&mut SinkParams),
+    {
+        let before = SinkParams {
+            volume: self.volume(),
+            muted: self.is_muted(),
+            speed: self.speed(),
+            rate_adjustment: self.rate_adjustment(),
+        };
+        let mut params = before;
+        // Runs without the lock so a slow or panicking closure cannot stall the audio thread.
+        change(&mut params);
+
+        let _batch = self.controls.batch.lock().unwrap();
+        if params.volume != before.volume {
+            self.set_volume(params.volume);
+        }
+        if params.muted != before.muted {
+            self.set_muted(params.muted);
+        }
+        if params.speed != before.speed {
+            self.set_speed(params.speed);
+        }
+        if params.rate_adjustment != before.rate_adjustment {
+            self.set_rate_adjustment(params.rate_adjustment);
+        }
+    }
+
+    /// Gets the rate adjustment in parts per million.
+    ///
+    /// See [`Sink::set_rate_adjustment`] for details.
+    #[inline]
+    pub fn rate_adjustment(&self) -> f32 {
+        *self.controls.rate_adjustment.lock().unwrap()
+    }
+
+    /// Nudges the playback rate by a few parts per million (ppm) to keep in sync with an
+    /// external clock, for example the frames of a video.
+    ///
+    /// Positive values slow playback down, negative values speed it up. The adjustment is
+    /// applied gradually and clamped to [`MAX_RATE_ADJUSTMENT_PPM`](crate::source::MAX_RATE_ADJUSTMENT_PPM).
+    /// Unlike [`Sink::set_speed`] this resamples the audio so it also works for such tiny
+    /// changes. See [`RateAdjustment`] for details.
+    #[inline]
+    pub fn set_rate_adjustment(&self, ppm: f32) {
+        *self.controls.rate_adjustment.lock().unwrap() = ppm;
+    }
+
     /// Resumes playback of a paused sink.
     ///
     /// No effect if not paused.
@@ -234,7 +603,22 @@ impl Sink {
     /// When seeking beyond the end of a source this
     /// function might return an error if the duration of the source is not known.
     pub fn try_seek(&self, pos: Duration) -> Result<(), SeekError> {
-        let (order, feedback) = SeekOrder::new(pos);
+        self.seek(SeekTarget::Time(pos))
+    }
+
+    /// Attempts to seek to the start of frame `frame` in the current source, see
+    /// [`Source::try_seek_samples`].
+    ///
+    /// This blocks between 0 and ~5 milliseconds.
+    ///
+    /// # Errors
+    /// The same as [`Sink::try_seek`].
+    pub fn try_seek_samples(&self, frame: u64) -> Result<(), SeekError> {
+        self.seek(SeekTarget::Frame(frame))
+    }
+
+    fn seek(&self, target: SeekTarget) -> Result<(), SeekError> {
+        let (order, feedback) = SeekOrder::new(target);
         *self.controls.seek.lock().unwrap() = Some(order);
 
         if self.sound_count.load(Ordering::Acquire) == 0 {
@@ -244,7 +628,9 @@ impl Sink {
 
         match feedback.recv() {
             Ok(seek_res) => {
-                *self.controls.position.lock().unwrap() = pos;
+                if let SeekTarget::Time(pos) = target {
+                    *self.controls.position.lock().unwrap() = pos;
+                }
                 seek_res
             }
             // The feedback channel closed. Probably another SeekOrder was set
@@ -254,6 +640,29 @@ impl Sink {
         }
     }
 
+    /// Repeats the part between `start` and `end` of the source that is playing.
+    ///
+    /// Each time playback reaches `end` the sink seeks back to `start`, as with
+    /// [`Sink::try_seek`] the end may be overshot by up to ~5 milliseconds. The region applies
+    /// to whichever source is playing, including sources appended later. Pass `None` to
+    /// continue playback normally. Sources that do not support seeking are played without
+    /// looping.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` is not before `end`.
+    pub fn set_loop_region(&self, region: Option<(Duration, Duration)>) {
+        if let Some((start, end)) = region {
+            assert!(start < end, "the loop region must start before it ends");
+        }
+        *self.controls.loop_region.lock().unwrap() = region;
+    }
+
+    /// Returns the region set with [`Sink::set_loop_region`].
+    pub fn loop_region(&self) -> Option<(Duration, Duration)> {
+        *self.controls.loop_region.lock().unwrap()
+    }
+
     /// Pauses playback of this sink.
     ///
     /// No effect if already paused.
@@ -300,6 +709,32 @@ impl Sink {
         self.controls.stopped.store(true, Ordering::SeqCst);
     }
 
+    /// Stops the sink and restores all settings to their defaults so it can be reused.
+    ///
+    /// All queued sources are dropped as with [`Sink::stop`]. The sink is unmuted, volume and
+    /// speed return to `1.0`, the rate adjustment to zero, the loop region and the limit set
+    /// with [`Sink::set_max_queue_len`] are removed and the position reset. A paused sink is
+    /// resumed. The sink stays connected, appending a new
+    /// source starts playback again.
+    ///
+    /// This does not block, it is safe to call while the sink is playing.
+    pub fn reset(&self) {
+        self.stop();
+        self.controls.pause.store(false, Ordering::SeqCst);
+        *self.controls.volume.lock().unwrap() = 1.0;
+        self.controls.volume_smoothed.store(false, Ordering::SeqCst);
+        self.controls.muted.store(false, Ordering::SeqCst);
+        *self.controls.speed.lock().unwrap() = 1.0;
+        *self.controls.rate_adjustment.lock().unwrap() = 0.0;
+        *self.controls.speed_ramp.lock().unwrap() = (1.0, Duration::ZERO);
+        *self.controls.seek.lock().unwrap() = None;
+        *self.controls.loop_region.lock().unwrap() = None;
+        *self.controls.position.lock().unwrap() = Duration::ZERO;
+        *self.controls.duration.lock().unwrap() = None;
+        *self.controls.effective_sample_rate.lock().unwrap() = None;
+        self.max_queue_len.store(usize::MAX, Ordering::Relaxed);
+    }
+
     /// Destroys the sink without stopping the sounds that are still playing.
     #[inline]
     pub fn detach(mut self) {
@@ -314,12 +749,43 @@ impl Sink {
         }
     }
 
+    /// Sleeps the current thread until the sound ends or `timeout` elapses.
+    ///
+    /// Returns `true` if playback finished within the timeout and `false` otherwise. Unlike
+    /// [`Sink::sleep_until_end`] this can be called again after it timed out.
+    pub fn wait_until_end_timeout(&self, timeout: Duration) -> bool {
+        let Some(sleep_until_end) = self.sleep_until_end.lock().unwrap().take() else {
+            return true;
+        };
+
+        match sleep_until_end.recv_timeout(timeout) {
+            Ok(()) | Err(RecvTimeoutError::Disconnected) => true,
+            Err(RecvTimeoutError::Timeout) => {
+                // Put the signal back unless a new sound got appended in the meantime.
+                let mut slot = self.sleep_until_end.lock().unwrap();
+                if slot.is_none() {
+                    *slot = Some(sleep_until_end);
+                }
+                false
+            }
+        }
+    }
+
     /// Returns true if this sink has no more sounds to play.
     #[inline]
     pub fn empty(&self) -> bool {
         self.len() == 0
     }
 
+    /// Returns the format and length of the sounds waiting to play after the current one, in
+    /// order, for example to show what plays next.
+    ///
+    /// The sounds are not consumed. Sounds that have not started yet report their duration at
+    /// a speed of `1.0`.
+    pub fn queue_info(&self) -> Vec<QueuedSound> {
+        self.queue_tx.queued()
+    }
+
     /// Returns the number of sounds currently in the queue.
     #[allow(clippy::len_without_is_empty)]
     #[inline]
@@ -338,6 +804,18 @@ impl Sink {
     pub fn get_pos(&self) -> Duration {
         *self.controls.position.lock().unwrap()
     }
+
+    /// Returns the total duration of the sound that's being played, not of the whole queue.
+    ///
+    /// Like [`get_pos()`](Sink::get_pos) this takes the speed into account. Returns `None`
+    /// if the duration of the sound is not known or nothing is playing.
+    #[inline]
+    pub fn current_duration(&self) -> Option<Duration> {
+        if self.empty() {
+            return None;
+        }
+        *self.controls.duration.lock().unwrap()
+    }
 }
 
 impl Drop for Sink {
@@ -346,7 +824,11 @@ impl Drop for Sink {
         self.queue_tx.set_keep_alive_if_empty(false);
 
         if !self.detached {
-            self.controls.stopped.store(true, Ordering::Relaxed);
+            if self.controls.fade_on_drop.lock().unwrap().is_zero() {
+                self.controls.stopped.store(true, Ordering::Relaxed);
+            } else {
+                self.controls.fading_out.store(true, Ordering::Relaxed);
+            }
         }
     }
 }
@@ -354,10 +836,13 @@ impl Drop for Sink {
 #[cfg(test)]
 mod tests {
     use std::sync::atomic::Ordering;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
 
     use crate::buffer::SamplesBuffer;
     use crate::math::nz;
-    use crate::{Sink, Source};
+    use crate::source::SineWave;
+    use crate::{QueueFull, Sink, Source};
 
     #[test]
     fn test_pause_and_stop() {
@@ -395,6 +880,39 @@ mod tests {
         assert!(sink.empty());
     }
 
+    #[test]
+    fn test_fade_on_drop() {
+        let (sink, mut queue_rx) = Sink::new();
+        let sink = sink.with_fade_on_drop(Duration::from_millis(20));
+        sink.append(SamplesBuffer::new(nz!(1), nz!(1000), vec![1.0; 1000]));
+        let played: Vec<f32> = queue_rx
+            .by_ref()
+            .skip_while(|s| *s == 0.0)
+            .take(100)
+            .collect();
+        assert_eq!(played, vec![1.0; 100]);
+
+        drop(sink);
+        let tail: Vec<f32> = queue_rx.take(100).collect();
+        // Ramps down over the fade instead of cutting off, then ends.
+        assert!(tail.len() >= 20 && tail.len() < 40, "{tail:?}");
+        assert!(tail[0] > 0.9, "{tail:?}");
+        assert!(tail.windows(2).all(|w| w[1] <= w[0]), "{tail:?}");
+        assert!(tail[tail.len() - 1] < 0.1, "{tail:?}");
+    }
+
+    #[test]
+    fn test_append_and_play_resumes() {
+        let (sink, queue_rx) = Sink::new();
+        sink.pause();
+        sink.append_and_play(SamplesBuffer::new(nz!(1), nz!(1), vec![10.0, -10.0]));
+        assert!(!sink.is_paused());
+
+        let mut played = queue_rx.skip_while(|x| *x == 0.0);
+        assert_eq!(played.next(), Some(10.0));
+        assert_eq!(played.next(), Some(-10.0));
+    }
+
     #[test]
     fn test_stop_and_start() {
         let (sink, mut queue_rx) = Sink::new();
@@ -423,6 +941,152 @@ mod tests {
         assert_eq!(queue_rx.next(), src.next());
     }
 
+    #[test]
+    fn test_wait_until_end_timeout() {
+        let (sink, mut queue_rx) = Sink::new();
+
+        sink.append(SamplesBuffer::new(nz!(1), nz!(1), vec![1.0, 2.0, 3.0]));
+        for _ in 0..5 {
+            queue_rx.next();
+        }
+        assert!(sink.wait_until_end_timeout(Duration::from_millis(100)));
+
+        sink.append(SineWave::new(440.0));
+        assert!(!sink.wait_until_end_timeout(Duration::from_millis(20)));
+        // The signal is kept so waiting again is possible.
+        assert!(!sink.wait_until_end_timeout(Duration::from_millis(20)));
+    }
+
+    #[test]
+    fn test_loop_region() {
+        let (sink, queue_rx) = Sink::new();
+
+        // Ten seconds where each sample holds its own position in seconds.
+        let samples: Vec<f32> = (0..10_000).map(|i| i as f32 / 1000.0).collect();
+        sink.append(SamplesBuffer::new(nz!(1), nz!(1000), samples));
+        sink.set_loop_region(Some((Duration::from_secs(2), Duration::from_secs(3))));
+
+        let played: Vec<f32> = queue_rx.skip_while(|s| *s == 0.0).take(6000).collect();
+        assert!(played.iter().all(|s| *s < 3.01), "played past the loop end");
+        let loops = played.windows(2).filter(|w| w[1] < w[0]).count();
+        assert!(loops >= 3, "looped {loops} times");
+        assert!(played.windows(2).all(|w| w[1] > w[0] || w[1] == 2.0));
+
+        sink.set_loop_region(None);
+    }
+
+    #[test]
+    #[should_panic(expected = "the loop region must start before it ends")]
+    fn test_loop_region_rejects_empty_region() {
+        let (sink, _queue_rx) = Sink::new();
+        sink.set_loop_region(Some((Duration::from_secs(3), Duration::from_secs(3))));
+    }
+
+    #[test]
+    fn test_try_seek_samples() {
+        let (sink, mut queue_rx) = Sink::new();
+        sink.pause();
+
+        // Each sample holds its own frame index, offset so none of them is silent.
+        let samples: Vec<f32> = (0..20_000).flat_map(|i| [i as f32 + 1.0; 2]).collect();
+        sink.append(SamplesBuffer::new(nz!(2), nz!(1000), samples));
+        // Seeking blocks until the output is read, play it from another thread.
+        let player = std::thread::spawn(move || queue_rx.find(|s| *s != 0.0));
+
+        sink.try_seek_samples(12_345).unwrap();
+        assert_eq!(sink.get_pos(), Duration::from_secs_f64(12.345));
+        sink.play();
+        assert_eq!(player.join().unwrap(), Some(12_346.0));
+    }
+
+    #[test]
+    fn test_current_duration() {
+        let (sink, mut queue_rx) = Sink::new();
+        assert_eq!(sink.current_duration(), None);
+
+        sink.append(SamplesBuffer::new(nz!(1), nz!(1000), vec![1.0; 3000]));
+        sink.append(SamplesBuffer::new(nz!(1), nz!(1000), vec![2.0; 1000]));
+        assert_eq!(queue_rx.by_ref().find(|s| *s != 0.0), Some(1.0));
+        assert_eq!(sink.current_duration(), Some(Duration::from_secs(3)));
+
+        // The next sound reports its own duration.
+        assert_eq!(queue_rx.by_ref().find(|s| *s == 2.0), Some(2.0));
+        assert_eq!(sink.current_duration(), Some(Duration::from_secs(1)));
+
+        sink.stop();
+        let _ = queue_rx.by_ref().take(100).count();
+        assert_eq!(sink.current_duration(), None);
+    }
+
+    #[test]
+    fn test_try_append() {
+        let (sink, mut queue_rx) = Sink::new();
+        sink.set_max_queue_len(Some(2));
+
+        let source = || SamplesBuffer::new(nz!(1), nz!(1), vec![1.0, 2.0]);
+        assert_eq!(sink.try_append(source()), Ok(()));
+        assert_eq!(sink.try_append(source()), Ok(()));
+        assert_eq!(sink.try_append(source()), Err(QueueFull));
+        assert_eq!(sink.len(), 2);
+
+        // Once a sound finished there is room again.
+        let _ = queue_rx.by_ref().skip_while(|s| *s == 0.0).take(2).count();
+        queue_rx.next();
+        assert_eq!(sink.len(), 1);
+        assert_eq!(sink.try_append(source()), Ok(()));
+    }
+
+    #[test]
+    fn test_try_append_from_many_threads() {
+        let (sink, _queue_rx) = Sink::new();
+        sink.set_max_queue_len(Some(3));
+
+        let accepted = std::thread::scope(|scope| {
+            let threads: Vec<_> = (0..8)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let source = SamplesBuffer::new(nz!(1), nz!(1), vec![1.0]);
+                        sink.try_append(source).is_ok()
+                    })
+                })
+                .collect();
+            threads
+                .into_iter()
+                .map(|thread| thread.join().unwrap())
+                .filter(|accepted| *accepted)
+                .count()
+        });
+        assert_eq!(accepted, 3);
+        assert_eq!(sink.len(), 3);
+    }
+
+    #[test]
+    fn test_reset() {
+        let (sink, mut queue_rx) = Sink::new();
+
+        let v = vec![10.0, -10.0, 20.0, -20.0, 30.0, -30.0];
+        sink.append(SamplesBuffer::new(nz!(1), nz!(1), v.clone()));
+        sink.append(SamplesBuffer::new(nz!(1), nz!(1), v.clone()));
+        sink.set_volume(0.5);
+        sink.set_speed(2.0);
+        sink.set_max_queue_len(Some(1));
+        sink.pause();
+
+        sink.reset();
+        assert_eq!(sink.max_queue_len(), None);
+        assert_eq!(sink.volume(), 1.0);
+        assert_eq!(sink.speed(), 1.0);
+        assert!(!sink.is_paused());
+        assert_eq!(sink.get_pos(), Duration::ZERO);
+        // Both queued sources are dropped.
+        assert_eq!(queue_rx.next(), Some(0.0));
+        assert!(sink.empty());
+
+        sink.append(SamplesBuffer::new(nz!(1), nz!(1), v.clone()));
+        let played: Vec<f32> = queue_rx.skip_while(|s| *s == 0.0).take(v.len()).collect();
+        assert_eq!(played, v);
+    }
+
     #[test]
     fn test_volume() {
         let (sink, mut queue_rx) = Sink::new();
@@ -440,4 +1104,228 @@ mod tests {
             assert_eq!(queue_rx.next(), src.next());
         }
     }
+
+    #[test]
+    fn test_volume_db() {
+        let (sink, mut queue_rx) = Sink::new();
+
+        // Controls are updated every 5 samples at this rate.
+        sink.append(SamplesBuffer::new(nz!(1), nz!(1000), vec![1.0; 100]));
+        let before: Vec<f32> = queue_rx
+            .by_ref()
+            .skip_while(|s| *s == 0.0)
+            .take(9)
+            .collect();
+        assert!(before.iter().all(|s| *s == 1.0));
+
+        sink.set_volume_db(-6.0);
+        let after: Vec<f32> = queue_rx.by_ref().take(30).collect();
+        assert!(after.windows(2).all(|w| w[0] - w[1] < 0.15), "{after:?}");
+        let settled = *after.last().unwrap();
+        assert!((settled - 0.5).abs() < 0.01, "{settled}");
+        assert!((sink.volume_db() + 6.0).abs() < 1e-4);
+
+        // Muting silences the sink but keeps the volume.
+        sink.set_muted(true);
+        assert!((sink.volume_db() + 6.0).abs() < 1e-4);
+        sink.set_muted(false);
+
+        sink.set_volume_db(f32::NEG_INFINITY);
+        assert_eq!(sink.volume(), 0.0);
+        assert_eq!(queue_rx.nth(20), Some(0.0));
+    }
+
+    #[test]
+    fn test_set_speed_smooth() {
+        let (sink, mut queue_rx) = Sink::new();
+        sink.append(SineWave::new(440.0));
+        let before: Vec<f32> = queue_rx.by_ref().take(4800).collect();
+        let max_step = |samples: &[f32]| {
+            samples
+                .windows(2)
+                .map(|w| (w[1] - w[0]).abs())
+                .fold(0.0, f32::max)
+        };
+
+        // A tape stop: the pitch falls without jumps until the sound holds still.
+        sink.set_speed_smooth(0.0, Duration::from_secs(1));
+        let stop: Vec<f32> = queue_rx.by_ref().take(48000 + 9600).collect();
+        assert!(max_step(&stop) <= max_step(&before) + 1e-3);
+        let crossings: Vec<usize> = stop
+            .chunks(4800)
+            .map(|window| {
+                window
+                    .windows(2)
+                    .filter(|w| w[0] < 0.0 && w[1] >= 0.0)
+                    .count()
+            })
+            .collect();
+        assert!(crossings[0] >= 40, "{crossings:?}");
+        assert!(crossings.windows(2).all(|w| w[1] <= w[0]), "{crossings:?}");
+        assert_eq!(crossings[10..], [0, 0]);
+        let held = stop[stop.len() - 1];
+        assert!(stop[stop.len() - 4800..].iter().all(|s| *s == held));
+
+        sink.reset();
+        assert_eq!(
+            *sink.controls.speed_ramp.lock().unwrap(),
+            (1.0, Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn test_apply() {
+        let (sink, mut queue_rx) = Sink::new();
+        sink.append(SamplesBuffer::new(nz!(1), nz!(1000), vec![1.0; 1000]));
+        assert_eq!(queue_rx.nth(10), Some(1.0));
+
+        sink.apply(|params| {
+            params.volume = 0.5;
+            params.speed = 2.0;
+        });
+        assert_eq!((sink.volume(), sink.speed()), (0.5, 2.0));
+        let played: Vec<_> = (0..20)
+            .map(|_| (queue_rx.next().unwrap(), queue_rx.sample_rate().get()))
+            .collect();
+        // Both changes land on the same buffer, never one without the other.
+        assert!(played
+            .iter()
+            .all(|played| *played == (1.0, 1000) || *played == (0.5, 2000)));
+        assert_eq!(played[played.len() - 1], (0.5, 2000));
+    }
+
+    #[test]
+    fn test_apply_commits_changed_fields_only() {
+        let (sink, _queue_rx) = Sink::new();
+        sink.apply(|params| {
+            // The closure runs without the lock, the sink stays usable from it.
+            sink.set_speed(1.5);
+            params.volume = 0.25;
+        });
+        assert_eq!((sink.volume(), sink.speed()), (0.25, 1.5));
+    }
+
+    #[test]
+    fn test_queue_info() {
+        let (sink, mut queue_rx) = Sink::new();
+        sink.append(SamplesBuffer::new(nz!(1), nz!(1000), vec![0.0; 1000]));
+        sink.append(SamplesBuffer::new(nz!(2), nz!(1000), vec![0.0; 1000]));
+        sink.append(SamplesBuffer::new(nz!(1), nz!(4000), vec![0.0; 1000]));
+        let info = sink.queue_info();
+        let durations: Vec<_> = info.iter().map(|info| info.total_duration).collect();
+        assert_eq!(
+            durations,
+            [
+                Some(Duration::from_secs(1)),
+                Some(Duration::from_millis(500)),
+                Some(Duration::from_millis(250)),
+            ]
+        );
+        assert_eq!(info[1].channels, nz!(2));
+        assert_eq!(info[2].sample_rate, nz!(4000));
+
+        // The sound that started playing is no longer queued.
+        queue_rx.next();
+        assert_eq!(sink.queue_info().len(), 2);
+    }
+
+    #[test]
+    fn test_effective_sample_rate() {
+        let (sink, mut queue_rx) = Sink::new();
+        assert_eq!(sink.effective_sample_rate(), None);
+        sink.append(SamplesBuffer::new(nz!(1), nz!(44100), vec![0.0; 44100]));
+        queue_rx.nth(10);
+        assert_eq!(sink.effective_sample_rate(), Some(44100.0));
+
+        sink.set_speed(2.0);
+        // The speed is picked up within 5 ms.
+        queue_rx.by_ref().take(441).for_each(drop);
+        assert_eq!(sink.effective_sample_rate(), Some(88200.0));
+    }
+
+    #[test]
+    fn test_labels() {
+        let (sink, mut queue_rx) = Sink::new();
+        let ended = Arc::new(Mutex::new(Vec::new()));
+        let log = ended.clone();
+        sink.set_on_source_end(move |label| {
+            let id = label.and_then(|label| label.downcast_ref::<u32>()).copied();
+            log.lock().unwrap().push(id);
+        });
+        sink.append_labeled(SamplesBuffer::new(nz!(1), nz!(1000), vec![0.5; 100]), 7u32);
+        sink.append(SamplesBuffer::new(nz!(1), nz!(1000), vec![0.5; 100]));
+        sink.append_labeled(SamplesBuffer::new(nz!(1), nz!(1000), vec![0.5; 100]), 9u32);
+
+        let labels: Vec<_> = sink
+            .queue_info()
+            .iter()
+            .map(|info| {
+                info.label
+                    .as_ref()
+                    .and_then(|l| l.downcast_ref::<u32>())
+                    .copied()
+            })
+            .collect();
+        assert_eq!(labels, [Some(7), None, Some(9)]);
+        assert_eq!(sink.queue_info(), sink.queue_info());
+
+        queue_rx.by_ref().take(400).for_each(drop);
+        assert_eq!(*ended.lock().unwrap(), [Some(7), None, Some(9)]);
+    }
+
+    #[test]
+    fn test_pitch_semitones() {
+        let (sink, mut queue_rx) = Sink::new();
+        sink.set_pitch_semitones(12.0);
+        assert_eq!(sink.speed(), 2.0);
+        assert_eq!(sink.pitch_semitones(), 12.0);
+
+        sink.append(SamplesBuffer::new(nz!(1), nz!(1000), vec![1.0; 100]));
+        assert_eq!(queue_rx.nth(10), Some(1.0));
+        assert_eq!(queue_rx.sample_rate(), nz!(2000));
+
+        sink.set_pitch_semitones(-12.0);
+        assert_eq!(sink.speed(), 0.5);
+    }
+
+    #[test]
+    fn test_append_boxed() {
+        let (sink, mut queue_rx) = Sink::new();
+        let chain = SamplesBuffer::new(nz!(1), nz!(1000), vec![1.0; 100])
+            .amplify(0.5)
+            .take_duration(Duration::from_millis(50))
+            .boxed();
+        sink.append(chain);
+        let played: Vec<f32> = queue_rx
+            .by_ref()
+            .skip_while(|s| *s == 0.0)
+            .take(50)
+            .collect();
+        assert_eq!(played, vec![0.5; 50]);
+        sink.stop();
+        assert!(queue_rx.take(10).all(|s| s == 0.0));
+    }
+
+    #[test]
+    fn test_mute() {
+        let (sink, mut queue_rx) = Sink::new();
+
+        // Controls are updated every 5 samples at this rate.
+        sink.append(SamplesBuffer::new(nz!(1), nz!(1000), vec![1.0; 100]));
+        sink.set_volume(0.7);
+        assert_eq!(queue_rx.next(), Some(0.7));
+
+        sink.set_muted(true);
+        assert!(sink.is_muted());
+        assert_eq!(sink.volume(), 0.7);
+        let muted: Vec<f32> = queue_rx.by_ref().take(20).collect();
+        assert!(muted.windows(2).all(|w| w[0] - w[1] < 0.2), "{muted:?}");
+        assert_eq!(muted.last(), Some(&0.0));
+
+        sink.set_muted(false);
+        assert!(!sink.is_muted());
+        let unmuted: Vec<f32> = queue_rx.by_ref().take(20).collect();
+        assert!(unmuted.windows(2).all(|w| w[1] - w[0] < 0.2), "{unmuted:?}");
+        assert!((unmuted.last().unwrap() - 0.7).abs() < 1e-6);
+    }
 }
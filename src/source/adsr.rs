@@ -0,0 +1,250 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::Source;
+
+/// Internal function that builds an `Adsr` object.
+pub fn adsr<I>(
+    input: I,
+    attack: Duration,
+    decay: Duration,
+    sustain_level: f32,
+    release: Duration,
+) -> Adsr<I>
+where
+    I: Source,
+{
+    Adsr {
+        input,
+        attack,
+        decay,
+        sustain_level: sustain_level.clamp(0.0, 1.0),
+        release,
+        released: AdsrHandle(Arc::new(AtomicBool::new(false))),
+        stage: Stage::Attack,
+        stage_frames: 0,
+        level: 0.0,
+        release_level: 0.0,
+        channel: 0,
+    }
+}
+
+/// Shapes the volume of a source with an attack, decay, sustain and release envelope.
+///
+/// The level rises linearly to full volume over the attack time, then falls to the sustain
+/// level over the decay time. It stays there until [`Adsr::release`] or [`AdsrHandle::release`]
+/// is called, then falls to silence over the release time after which the source ends.
+/// Releasing before the sustain stage fades out from the level reached so far.
+#[derive(Clone, Debug)]
+pub struct Adsr<I> {
+    input: I,
+    attack: Duration,
+    decay: Duration,
+    sustain_level: f32,
+    release: Duration,
+    released: AdsrHandle,
+    stage: Stage,
+    // Frames played in the current stage.
+    stage_frames: u64,
+    level: f32,
+    // Level when the release started.
+    release_level: f32,
+    // The channel of the next sample.
+    channel: u16,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Stage {
+    Attack,
+    Decay,
+    Sustain,
+    Release,
+    Done,
+}
+
+/// Starts the release of an [`Adsr`] from any thread.
+#[derive(Clone, Debug)]
+pub struct AdsrHandle(Arc<AtomicBool>);
+
+impl AdsrHandle {
+    /// Starts the release stage, like lifting a key.
+    #[inline]
+    pub fn release(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+impl<I> Adsr<I>
+where
+    I: Source,
+{
+    /// Starts the release stage, like lifting a key.
+    #[inline]
+    pub fn release(&mut self) {
+        self.released.release();
+    }
+
+    /// Returns a handle to start the release from another thread, for example while the
+    /// source plays in a [`Sink`](crate::Sink).
+    #[inline]
+    pub fn handle(&self) -> AdsrHandle {
+        self.released.clone()
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    /// Computes the level of the next frame and moves through the stages.
+    fn advance(&mut self) {
+        if self.stage < Stage::Release && self.released.0.load(Ordering::Relaxed) {
+            self.stage = Stage::Release;
+            self.stage_frames = 0;
+            self.release_level = self.level;
+        }
+
+        let rate = self.input.sample_rate().get() as f64;
+        let frames = |duration: Duration| (duration.as_secs_f64() * rate).round() as u64;
+        loop {
+            let (len, start, end) = match self.stage {
+                Stage::Attack => (frames(self.attack), 0.0, 1.0),
+                Stage::Decay => (frames(self.decay), 1.0, self.sustain_level),
+                Stage::Sustain => {
+                    self.level = self.sustain_level;
+                    return;
+                }
+                Stage::Release => (frames(self.release), self.release_level, 0.0),
+                Stage::Done => return,
+            };
+            if self.stage_frames < len {
+                let progress = self.stage_frames as f32 / len as f32;
+                self.level = start + (end - start) * progress;
+                self.stage_frames += 1;
+                return;
+            }
+            self.stage = match self.stage {
+                Stage::Attack => Stage::Decay,
+                Stage::Decay => Stage::Sustain,
+                _ => Stage::Done,
+            };
+            self.stage_frames = 0;
+        }
+    }
+}
+
+impl<I> Iterator for Adsr<I>
+where
+    I: Source,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<I::Item> {
+        if self.channel == 0 {
+            self.advance();
+        }
+        if self.stage == Stage::Done {
+            return None;
+        }
+        let sample = self.input.next()?;
+        self.channel = (self.channel + 1) % self.input.channels().get();
+        Some(sample * self.level)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.input.size_hint().1)
+    }
+}
+
+impl<I> Source for Adsr<I>
+where
+    I: Source,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Seeks the inner source, the envelope carries on where it was.
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::Source;
+
+    #[test]
+    fn follows_envelope() {
+        let tone = SamplesBuffer::new(nz!(2), nz!(1000), vec![1.0; 2000]);
+        let ms = Duration::from_millis;
+        let mut envelope = tone.adsr(ms(10), ms(10), 0.5, ms(20));
+
+        let attack: Vec<f32> = envelope.by_ref().take(20).collect();
+        let expected: Vec<f32> = (0..10).flat_map(|i| [i as f32 / 10.0; 2]).collect();
+        assert_eq!(attack, expected);
+
+        let decay: Vec<f32> = envelope.by_ref().take(20).step_by(2).collect();
+        assert_eq!(decay[0], 1.0);
+        assert!(decay.windows(2).all(|w| w[1] < w[0]), "{decay:?}");
+
+        assert!(envelope.by_ref().take(200).all(|s| s == 0.5));
+
+        envelope.handle().release();
+        let release: Vec<f32> = envelope.by_ref().step_by(2).collect();
+        assert_eq!(release.len(), 20);
+        assert_eq!(release[0], 0.5);
+        assert!(release.windows(2).all(|w| w[1] < w[0]), "{release:?}");
+    }
+
+    #[test]
+    fn early_release_fades_from_current_level() {
+        let tone = SamplesBuffer::new(nz!(1), nz!(1000), vec![1.0; 1000]);
+        let ms = Duration::from_millis;
+        let mut envelope = tone.adsr(ms(10), ms(10), 0.5, ms(10));
+        assert_eq!(envelope.by_ref().nth(5), Some(0.5));
+        envelope.release();
+        let release: Vec<f32> = envelope.collect();
+        assert_eq!(release.len(), 10);
+        assert_eq!(release[0], 0.5);
+    }
+}
@@ -11,7 +11,13 @@ pub fn amplify<I>(input: I, factor: f32) -> Amplify<I>
 where
     I: Source,
 {
-    Amplify { input, factor }
+    Amplify {
+        input,
+        factor,
+        target: factor,
+        step: 0.0,
+        remaining: 0,
+    }
 }
 
 /// Filter that modifies each sample by a given value.
@@ -19,6 +25,10 @@ where
 pub struct Amplify<I> {
     input: I,
     factor: f32,
+    // The factor a ramp ends at, equal to `factor` when not ramping.
+    target: f32,
+    step: f32,
+    remaining: u64,
 }
 
 impl<I> Amplify<I> {
@@ -26,12 +36,14 @@ impl<I> Amplify<I> {
     #[inline]
     pub fn set_factor(&mut self, factor: f32) {
         self.factor = factor;
+        self.target = factor;
+        self.remaining = 0;
     }
 
     /// Modifies the amplification factor logarithmically.
     #[inline]
     pub fn set_log_factor(&mut self, factor: f32) {
-        self.factor = math::db_to_linear(factor);
+        self.set_factor(math::db_to_linear(factor));
     }
 
     /// Returns a reference to the inner source.
@@ -53,6 +65,32 @@ impl<I> Amplify<I> {
     }
 }
 
+impl<I> Amplify<I>
+where
+    I: Source,
+{
+    /// Moves the amplification factor linearly to `factor` over `duration`.
+    ///
+    /// Changing the gain gradually avoids the clicks ("zipper noise") of abrupt changes. Does
+    /// nothing if the factor is already moving towards `factor`.
+    pub fn ramp_factor(&mut self, factor: f32, duration: Duration) {
+        if factor == self.target {
+            return;
+        }
+        let samples = duration.as_secs_f64()
+            * self.input.sample_rate().get() as f64
+            * self.input.channels().get() as f64;
+        let samples = samples.round() as u64;
+        if samples == 0 {
+            self.set_factor(factor);
+            return;
+        }
+        self.target = factor;
+        self.step = (factor - self.factor) / samples as f32;
+        self.remaining = samples;
+    }
+}
+
 impl<I> Iterator for Amplify<I>
 where
     I: Source,
@@ -61,7 +99,16 @@ where
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        self.input.next().map(|value| value * self.factor)
+        let value = self.input.next()?;
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            self.factor = if self.remaining == 0 {
+                self.target
+            } else {
+                self.factor + self.step
+            };
+        }
+        Some(value * self.factor)
     }
 
     #[inline]
@@ -100,4 +147,41 @@ where
     fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
         self.input.try_seek(pos)
     }
+
+    #[inline]
+    fn try_seek_samples(&mut self, frame: u64) -> Result<(), SeekError> {
+        self.input.try_seek_samples(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::Source;
+
+    #[test]
+    fn ramps_without_jumps() {
+        let source = SamplesBuffer::new(nz!(1), nz!(1000), vec![1.0; 20]);
+        let mut source = source.amplify(1.0);
+        source.ramp_factor(0.0, Duration::from_millis(10));
+        // Asking for the same target again does not restart the ramp.
+        source.next();
+        source.ramp_factor(0.0, Duration::from_millis(10));
+
+        let output: Vec<f32> = source.collect();
+        assert!(output.windows(2).all(|w| w[0] - w[1] <= 0.1 + 1e-6));
+        assert!(output[9..].iter().all(|s| *s == 0.0), "{output:?}");
+    }
+
+    #[test]
+    fn minus_six_decibel_halves_amplitude() {
+        let source = SamplesBuffer::new(nz!(1), nz!(48000), vec![1.0, -0.5, 0.25]);
+        let output: Vec<f32> = source.amplify_decibel(-6.0).collect();
+        for (out, expected) in output.iter().zip([0.5, -0.25, 0.125]) {
+            assert!((out - expected).abs() < 0.005, "{out} != {expected}");
+        }
+    }
 }
@@ -0,0 +1,29 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+/// A parameter of an effect that can be changed from another thread while playing.
+///
+/// Clones share the same value: keep one to change the value from a UI thread and pass the
+/// other to an effect such as [`Source::low_pass_shared`](crate::Source::low_pass_shared).
+/// Reading and writing never locks, so the audio thread is never blocked.
+#[derive(Clone, Debug)]
+pub struct AtomicParam(Arc<AtomicU32>);
+
+impl AtomicParam {
+    /// Creates a parameter with an initial value.
+    pub fn new(value: f32) -> AtomicParam {
+        AtomicParam(Arc::new(AtomicU32::new(value.to_bits())))
+    }
+
+    /// Returns the current value.
+    #[inline]
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    /// Changes the value, effects pick it up from their next sample.
+    #[inline]
+    pub fn set(&self, value: f32) {
+        self.0.store(value.to_bits(), Ordering::Relaxed);
+    }
+}
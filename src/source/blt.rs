@@ -3,7 +3,7 @@ use crate::Source;
 use std::f32::consts::PI;
 use std::time::Duration;
 
-use super::SeekError;
+use super::{AtomicParam, SeekError};
 
 // Implemented following http://www.musicdsp.org/files/Audio-EQ-Cookbook.txt
 
@@ -30,6 +30,7 @@ where
     BltFilter {
         input,
         formula: BltFormula::LowPass { freq, q },
+        cutoff: None,
         applier: None,
         x_n1: 0.0,
         x_n2: 0.0,
@@ -46,6 +47,7 @@ where
     BltFilter {
         input,
         formula: BltFormula::HighPass { freq, q },
+        cutoff: None,
         applier: None,
         x_n1: 0.0,
         x_n2: 0.0,
@@ -54,11 +56,37 @@ where
     }
 }
 
+/// Internal function that builds a low-pass `BltFilter` with a shared cutoff.
+pub fn low_pass_shared<I>(input: I, cutoff: AtomicParam) -> BltFilter<I>
+where
+    I: Source<Item = f32>,
+{
+    let mut filter = low_pass(input, cutoff_hz(&cutoff));
+    filter.cutoff = Some(cutoff);
+    filter
+}
+
+/// Internal function that builds a high-pass `BltFilter` with a shared cutoff.
+pub fn high_pass_shared<I>(input: I, cutoff: AtomicParam) -> BltFilter<I>
+where
+    I: Source<Item = f32>,
+{
+    let mut filter = high_pass(input, cutoff_hz(&cutoff));
+    filter.cutoff = Some(cutoff);
+    filter
+}
+
+fn cutoff_hz(cutoff: &AtomicParam) -> u32 {
+    cutoff.get().round().max(1.0) as u32
+}
+
 /// This applies an audio filter, it can be a high or low pass filter.
 #[derive(Clone, Debug)]
 pub struct BltFilter<I> {
     input: I,
     formula: BltFormula,
+    // Cutoff frequency that can be changed while playing.
+    cutoff: Option<AtomicParam>,
     applier: Option<BltApplier>,
     x_n1: f32,
     x_n2: f32,
@@ -68,6 +96,8 @@ pub struct BltFilter<I> {
 
 impl<I> BltFilter<I> {
     /// Modifies this filter so that it becomes a low-pass filter.
+    ///
+    /// This and the other `to_*` methods stop following a shared cutoff.
     pub fn to_low_pass(&mut self, freq: u32) {
         self.to_low_pass_with_q(freq, 0.5);
     }
@@ -80,12 +110,14 @@ impl<I> BltFilter<I> {
     /// Same as to_low_pass but allows the q value (bandwidth) to be changed
     pub fn to_low_pass_with_q(&mut self, freq: u32, q: f32) {
         self.formula = BltFormula::LowPass { freq, q };
+        self.cutoff = None;
         self.applier = None;
     }
 
     /// Same as to_high_pass but allows the q value (bandwidth) to be changed
     pub fn to_high_pass_with_q(&mut self, freq: u32, q: f32) {
         self.formula = BltFormula::HighPass { freq, q };
+        self.cutoff = None;
         self.applier = None;
     }
 
@@ -118,6 +150,16 @@ where
     fn next(&mut self) -> Option<f32> {
         let last_in_span = self.input.current_span_len() == Some(1);
 
+        if let Some(cutoff) = &self.cutoff {
+            let cutoff = cutoff_hz(cutoff);
+            let (BltFormula::LowPass { freq, .. } | BltFormula::HighPass { freq, .. }) =
+                &mut self.formula;
+            if *freq != cutoff {
+                *freq = cutoff;
+                self.applier = None;
+            }
+        }
+
         if self.applier.is_none() {
             self.applier = Some(self.formula.to_applier(self.input.sample_rate().get()));
         }
@@ -246,3 +288,29 @@ impl BltApplier {
         self.b0 * x_n + self.b1 * x_n1 + self.b2 * x_n2 - self.a1 * y_n1 - self.a2 * y_n2
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::source::{AtomicParam, SineWave};
+    use crate::Source;
+
+    fn peak(source: &mut impl Source) -> f32 {
+        source
+            .take(4800)
+            .skip(2400)
+            .fold(0.0, |peak, s| peak.max(s.abs()))
+    }
+
+    #[test]
+    fn shared_cutoff_changes_while_playing() {
+        let cutoff = AtomicParam::new(20000.0);
+        let mut filtered = SineWave::new(5000.0).low_pass_shared(cutoff.clone());
+        assert!(peak(&mut filtered) > 0.9);
+
+        cutoff.set(500.0);
+        assert!(peak(&mut filtered) < 0.05);
+
+        cutoff.set(20000.0);
+        assert!(peak(&mut filtered) > 0.9);
+    }
+}
@@ -3,9 +3,9 @@ use std::mem;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
-use super::SeekError;
+use super::{Amplify, SeekError};
 use crate::common::{ChannelCount, SampleRate};
-use crate::math::nz;
+use crate::math::{db_to_linear, nz};
 use crate::Source;
 
 /// Internal function that builds a `Buffered` object.
@@ -19,12 +19,16 @@ where
 
     Buffered {
         current_span: first_span,
+        seek_start: None,
         position_in_span: 0,
         total_duration,
     }
 }
 
 /// Iterator that at the same time extracts data from the iterator and stores it in a buffer.
+///
+/// Data is only kept as long as a clone still has to play it. Call [`Buffered::seekable`] to
+/// keep everything instead, so it can be played again by seeking back.
 pub struct Buffered<I>
 where
     I: Source,
@@ -32,6 +36,10 @@ where
     /// Immutable reference to the next span of data. Cannot be `Span::Input`.
     current_span: Arc<Span<I>>,
 
+    /// The span seeking starts from, keeps all extracted data alive. `None` unless made
+    /// seekable.
+    seek_start: Option<Arc<Span<I>>>,
+
     /// The position in number of samples of this iterator inside `current_span`.
     position_in_span: usize,
 
@@ -128,6 +136,64 @@ impl<I> Buffered<I>
 where
     I: Source,
 {
+    /// Keeps all data from the current position on in memory, so the source can seek even if
+    /// its input can not. Seeking replays the kept data and positions are counted from where
+    /// this was called, usually right after [`Source::buffered`].
+    ///
+    /// Memory use grows with the length played, up to the size of the whole input.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{SineWave, Source};
+    /// use std::time::Duration;
+    ///
+    /// let mut tone = SineWave::new(440.0)
+    ///     .take_duration(Duration::from_secs(1))
+    ///     .buffered()
+    ///     .seekable();
+    /// let first = tone.next();
+    /// tone.try_seek(Duration::ZERO).unwrap();
+    /// assert_eq!(tone.next(), first);
+    /// ```
+    pub fn seekable(mut self) -> Buffered<I> {
+        self.seek_start = Some(self.current_span.clone());
+        self
+    }
+
+    /// Scales the source so its loudest sample peaks at `target_db` dBFS, leaving that much
+    /// headroom below full scale.
+    ///
+    /// Unlike a limiter the whole source is scaled by the same factor, so its dynamics are
+    /// kept. The peak is found by reading the source to its end from the current position,
+    /// so the source must be finite. All of it has to stay in memory for this, the result is
+    /// therefore [`seekable`](Buffered::seekable). A silent source is left unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{SineWave, Source};
+    /// use std::time::Duration;
+    ///
+    /// let render = SineWave::new(440.0)
+    ///     .amplify(3.0)
+    ///     .take_duration(Duration::from_secs(1))
+    ///     .buffered()
+    ///     .with_headroom(-1.0);
+    /// ```
+    pub fn with_headroom(self, target_db: f32) -> Amplify<Buffered<I>> {
+        let this = self.seekable();
+        let peak = this
+            .clone()
+            .fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+        let factor = if peak > 0.0 {
+            db_to_linear(target_db) / peak
+        } else {
+            1.0
+        };
+        this.amplify(factor)
+    }
+
     /// Advances to the next span.
     fn next_span(&mut self) {
         let next_span = {
@@ -234,13 +300,37 @@ where
         self.total_duration
     }
 
-    /// Can not support seek, in the end state we lose the underlying source
-    /// which makes seeking back impossible.
-    #[inline]
-    fn try_seek(&mut self, _: Duration) -> Result<(), SeekError> {
-        Err(SeekError::NotSupported {
-            underlying_source: std::any::type_name::<Self>(),
-        })
+    /// Seeks within the buffered data if [`seekable`](Buffered::seekable), extracting more
+    /// from the input when seeking past it. The input itself is never seeked. Seeking past
+    /// the end stops at the end.
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        // Without the data played so far there is no way back, the input is gone.
+        let Some(seek_start) = &self.seek_start else {
+            return Err(SeekError::NotSupported {
+                underlying_source: std::any::type_name::<Self>(),
+            });
+        };
+        self.current_span = seek_start.clone();
+        self.position_in_span = 0;
+        let mut remaining = pos.as_secs_f64();
+        while let Span::Data(SpanData {
+            data,
+            channels,
+            rate,
+            ..
+        }) = &*self.current_span
+        {
+            let channels = channels.get() as usize;
+            let frames = data.len() / channels;
+            let frame = (remaining * rate.get() as f64).round() as usize;
+            if frame < frames {
+                self.position_in_span = frame * channels;
+                break;
+            }
+            remaining -= frames as f64 / rate.get() as f64;
+            self.next_span();
+        }
+        Ok(())
     }
 }
 
@@ -252,8 +342,106 @@ where
     fn clone(&self) -> Buffered<I> {
         Buffered {
             current_span: self.current_span.clone(),
+            seek_start: self.seek_start.clone(),
             position_in_span: self.position_in_span,
             total_duration: self.total_duration,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::math::{linear_to_db, nz};
+    use crate::source::{from_iter, SeekError};
+    use crate::{Sample, Source};
+
+    /// A source that can not seek.
+    struct Unseekable(std::ops::Range<u16>);
+
+    impl Iterator for Unseekable {
+        type Item = Sample;
+
+        fn next(&mut self) -> Option<Sample> {
+            self.0.next().map(Sample::from)
+        }
+    }
+
+    impl Source for Unseekable {
+        fn current_span_len(&self) -> Option<usize> {
+            Some(self.0.len().min(6))
+        }
+
+        fn channels(&self) -> crate::ChannelCount {
+            nz!(2)
+        }
+
+        fn sample_rate(&self) -> crate::SampleRate {
+            nz!(10)
+        }
+
+        fn total_duration(&self) -> Option<Duration> {
+            None
+        }
+
+        fn try_seek(&mut self, _: Duration) -> Result<(), SeekError> {
+            Err(SeekError::NotSupported {
+                underlying_source: "Unseekable",
+            })
+        }
+    }
+
+    #[test]
+    fn seeks_back_into_played_data() {
+        let mut buffered = Unseekable(0..100).buffered().seekable();
+        let first_half: Vec<Sample> = buffered.by_ref().take(50).collect();
+
+        buffered.try_seek(Duration::ZERO).unwrap();
+        let replayed: Vec<Sample> = buffered.by_ref().take(50).collect();
+        assert_eq!(replayed, first_half);
+
+        // One second is 10 frames of 2 samples.
+        buffered.try_seek(Duration::from_secs(1)).unwrap();
+        assert_eq!(buffered.next(), Some(20.0));
+        assert_eq!(buffered.by_ref().count(), 79);
+    }
+
+    #[test]
+    fn seeks_forward_and_past_end() {
+        let mut buffered = from_iter([Unseekable(0..10)]).buffered().seekable();
+        buffered.try_seek(Duration::from_millis(300)).unwrap();
+        assert_eq!(buffered.next(), Some(6.0));
+        buffered.try_seek(Duration::from_secs(10)).unwrap();
+        assert_eq!(buffered.next(), None);
+    }
+
+    #[test]
+    fn only_seekable_keeps_played_data() {
+        let mut buffered = Unseekable(0..100).buffered();
+        assert!(buffered.try_seek(Duration::ZERO).is_err());
+
+        let first = buffered.current_span.clone();
+        buffered.by_ref().take(10).for_each(drop);
+        // The first span was dropped by the buffer, only `first` holds it.
+        assert_eq!(std::sync::Arc::strong_count(&first), 1);
+    }
+
+    #[test]
+    fn headroom_puts_peak_at_target() {
+        let samples = vec![0.1, -0.3, 1.7, -2.5, 0.0, 0.8];
+        let guarded: Vec<f32> = SamplesBuffer::new(nz!(2), nz!(1000), samples)
+            .buffered()
+            .with_headroom(-3.0)
+            .collect();
+        assert_eq!(guarded.len(), 6);
+        let peak = guarded.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!((linear_to_db(peak) + 3.0).abs() < 1e-5, "{peak}");
+        assert_eq!(guarded[3], -peak);
+        assert!((guarded[2] / guarded[3] + 1.7 / 2.5).abs() < 1e-6);
+
+        let silence = SamplesBuffer::new(nz!(1), nz!(1000), vec![0.0; 4]).buffered();
+        assert!(silence.with_headroom(-1.0).all(|s| s == 0.0));
+    }
+}
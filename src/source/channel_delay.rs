@@ -0,0 +1,208 @@
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds a `ChannelDelay` object.
+///
+/// # Panics
+///
+/// Panics if the number of delays differs from the channel count of `input`.
+pub fn channel_delay<I>(input: I, delays: Vec<Duration>) -> ChannelDelay<I>
+where
+    I: Source,
+{
+    assert_eq!(
+        delays.len(),
+        input.channels().get() as usize,
+        "there must be one delay per channel"
+    );
+    let mut source = ChannelDelay {
+        sample_rate: input.sample_rate(),
+        input,
+        delays,
+        offsets: Vec::new(),
+        history: Vec::new(),
+        pos: 0,
+        channel: 0,
+    };
+    source.reset();
+    source
+}
+
+/// Delays each channel of a source by its own amount, with sub-sample precision.
+///
+/// Fractional delays are linearly interpolated between the two nearest samples. The source
+/// keeps its length, so the end of the delayed channels is cut off.
+#[derive(Clone, Debug)]
+pub struct ChannelDelay<I> {
+    input: I,
+    delays: Vec<Duration>,
+    sample_rate: SampleRate,
+    // The delay of each channel in frames.
+    offsets: Vec<f32>,
+    // The most recent samples of each channel, `history[channel][pos]` being the newest.
+    history: Vec<Vec<Sample>>,
+    pos: usize,
+    // The channel of the next sample.
+    channel: usize,
+}
+
+impl<I> ChannelDelay<I>
+where
+    I: Source,
+{
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    /// Converts the delays to frames at the current sample rate and clears the history.
+    fn reset(&mut self) {
+        let rate = self.sample_rate.get() as f64;
+        self.offsets = self
+            .delays
+            .iter()
+            .map(|delay| (delay.as_secs_f64() * rate) as f32)
+            .collect();
+        let longest = self.offsets.iter().fold(0.0f32, |a, b| a.max(*b));
+        let len = longest.ceil() as usize + 2;
+        self.history = vec![vec![0.0; len]; self.delays.len()];
+        self.pos = 0;
+        self.channel = 0;
+    }
+}
+
+impl<I> Iterator for ChannelDelay<I>
+where
+    I: Source,
+{
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        let sample = self.input.next()?;
+        let len = self.history[0].len();
+        if self.channel == 0 {
+            if self.input.sample_rate() != self.sample_rate {
+                self.sample_rate = self.input.sample_rate();
+                self.reset();
+            }
+            self.pos = (self.pos + 1) % len;
+        }
+
+        let channel = self.channel;
+        self.channel = (channel + 1) % self.history.len();
+        let history = &mut self.history[channel];
+        history[self.pos] = sample;
+        let offset = self.offsets[channel];
+        let whole = offset as usize;
+        let fraction = offset - whole as f32;
+        let newer = history[(self.pos + len - whole) % len];
+        let older = history[(self.pos + len - whole - 1) % len];
+        Some(newer + (older - newer) * fraction)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for ChannelDelay<I> where I: Source + ExactSizeIterator {}
+
+impl<I> Source for ChannelDelay<I>
+where
+    I: Source,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    /// Seeks the inner source, the delayed channels start with silence again.
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.reset();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::Source;
+
+    #[test]
+    fn aligns_channels_with_fractional_delay() {
+        let signal = |t: f32| (TAU * 20.0 * t).sin() + 0.5 * (TAU * 47.0 * t).sin();
+        let samples: Vec<f32> = (0..2000)
+            .flat_map(|i| [signal(i as f32 / 1000.0); 2])
+            .collect();
+        let delays = vec![Duration::ZERO, Duration::from_micros(2500)];
+        let output: Vec<f32> = SamplesBuffer::new(nz!(2), nz!(1000), samples)
+            .channel_delay(delays)
+            .collect();
+        assert_eq!(output.len(), 4000);
+
+        let left: Vec<f32> = output.iter().step_by(2).copied().collect();
+        let right: Vec<f32> = output.iter().skip(1).step_by(2).copied().collect();
+        assert_eq!(
+            left[..10],
+            (0..10)
+                .map(|i| signal(i as f32 / 1000.0))
+                .collect::<Vec<_>>()
+        );
+
+        // Find the lag where the right channel best matches the left one.
+        let correlation =
+            |lag: usize| -> f32 { (100..1900).map(|i| left[i] * right[i + lag]).sum() };
+        let scores: Vec<f32> = (0..6).map(correlation).collect();
+        let peak = (1..5)
+            .max_by(|a, b| scores[*a].total_cmp(&scores[*b]))
+            .unwrap();
+        let (before, at, after) = (scores[peak - 1], scores[peak], scores[peak + 1]);
+        let lag = peak as f32 + 0.5 * (before - after) / (before - 2.0 * at + after);
+        assert!((lag - 2.5).abs() < 0.05, "{lag}");
+    }
+
+    #[test]
+    #[should_panic(expected = "one delay per channel")]
+    fn rejects_wrong_number_of_delays() {
+        SamplesBuffer::new(nz!(2), nz!(1000), vec![0.0; 4]).channel_delay(vec![Duration::ZERO]);
+    }
+}
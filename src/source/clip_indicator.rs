@@ -0,0 +1,188 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds a `ClipIndicator` object.
+pub fn clip_indicator<I>(input: I, threshold: f32) -> ClipIndicator<I>
+where
+    I: Source,
+{
+    let channels = input.channels().get() as usize;
+    ClipIndicator {
+        input,
+        threshold,
+        flags: Arc::new(ClipFlags {
+            held: (0..channels).map(|_| AtomicBool::new(false)).collect(),
+        }),
+        channel: 0,
+    }
+}
+
+/// Per-channel clip flags of a [`ClipIndicator`], shared with the audio thread.
+///
+/// A flag latches as soon as a sample in its channel exceeds the threshold and stays set until
+/// [`reset_clip`](ClipFlags::reset_clip) is called, like a clip LED on a mixing desk.
+#[derive(Debug)]
+pub struct ClipFlags {
+    held: Box<[AtomicBool]>,
+}
+
+impl ClipFlags {
+    /// Returns for each channel whether it clipped since the last reset.
+    pub fn clip_held(&self) -> Vec<bool> {
+        self.held
+            .iter()
+            .map(|flag| flag.load(Ordering::Relaxed))
+            .collect()
+    }
+
+    /// Clears the flags of all channels.
+    pub fn reset_clip(&self) {
+        for flag in self.held.iter() {
+            flag.store(false, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Latches a flag for each channel in which a sample exceeds a threshold.
+///
+/// The samples pass through unchanged. Use [`get_clip_flags`](ClipIndicator::get_clip_flags)
+/// to read the flags from another thread, for example a UI, after the source has been handed
+/// to a [`Sink`](crate::Sink). The flags do not use locks so they are safe to use on the audio
+/// thread. The number of flags is the channel count of the input when the indicator was
+/// created, additional channels are not tracked should it change.
+#[derive(Clone, Debug)]
+pub struct ClipIndicator<I> {
+    input: I,
+    threshold: f32,
+    flags: Arc<ClipFlags>,
+    channel: usize,
+}
+
+impl<I> ClipIndicator<I>
+where
+    I: Source,
+{
+    /// Returns for each channel whether it clipped since the last reset.
+    #[inline]
+    pub fn clip_held(&self) -> Vec<bool> {
+        self.flags.clip_held()
+    }
+
+    /// Clears the flags of all channels.
+    #[inline]
+    pub fn reset_clip(&self) {
+        self.flags.reset_clip()
+    }
+
+    /// Access the clip flags from another thread.
+    pub fn get_clip_flags(&self) -> Arc<ClipFlags> {
+        Arc::clone(&self.flags)
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> Iterator for ClipIndicator<I>
+where
+    I: Source,
+{
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        let sample = self.input.next()?;
+        if sample.abs() > self.threshold {
+            if let Some(flag) = self.flags.held.get(self.channel) {
+                flag.store(true, Ordering::Relaxed);
+            }
+        }
+        self.channel += 1;
+        if self.channel >= self.input.channels().get() as usize {
+            self.channel = 0;
+        }
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for ClipIndicator<I> where I: Source + ExactSizeIterator {}
+
+impl<I> Source for ClipIndicator<I>
+where
+    I: Source,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.channel = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::Source;
+
+    #[test]
+    fn only_spiking_channel_latches_until_reset() {
+        let mut samples = vec![0.5; 3 * 100];
+        samples[3 * 10 + 1] = 1.5;
+        let source = SamplesBuffer::new(nz!(3), nz!(48000), samples).clip_indicator(1.0);
+        let flags = source.get_clip_flags();
+        assert_eq!(flags.clip_held(), [false, false, false]);
+
+        let played: Vec<f32> = source.collect();
+        assert_eq!(played[3 * 10 + 1], 1.5);
+        assert_eq!(flags.clip_held(), [false, true, false]);
+
+        flags.reset_clip();
+        assert_eq!(flags.clip_held(), [false, false, false]);
+    }
+}
@@ -0,0 +1,229 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds a `ClipLog` object.
+pub fn clip_log<I>(input: I) -> ClipLog<I>
+where
+    I: Source,
+{
+    ClipLog {
+        input,
+        events: Arc::new(Mutex::new(Vec::new())),
+        pending: Vec::new(),
+        window_start: Duration::ZERO,
+        frames_in_window: 0,
+        clipped: 0,
+        channel: 0,
+    }
+}
+
+/// Logs when a source clips, counting the samples outside `-1.0..=1.0` in each second.
+///
+/// The samples pass through unchanged. Read the log with a [`ClipLogHandle`], for example
+/// after rendering a source to find the sections that need attention. Seconds without
+/// clipping are not logged.
+#[derive(Debug)]
+pub struct ClipLog<I> {
+    input: I,
+    events: Arc<Mutex<Vec<(Duration, usize)>>>,
+    // Finished windows that could not be published yet.
+    pending: Vec<(Duration, usize)>,
+    // Position of the current window in the source.
+    window_start: Duration,
+    frames_in_window: u32,
+    // Clipped samples in the current window.
+    clipped: usize,
+    // The channel of the next sample.
+    channel: u16,
+}
+
+/// Reads the log of a [`ClipLog`] from any thread.
+#[derive(Clone, Debug)]
+pub struct ClipLogHandle(Arc<Mutex<Vec<(Duration, usize)>>>);
+
+impl ClipLogHandle {
+    /// Returns the start of each second in which the source clipped, with the number of
+    /// samples that clipped in it, in the order they were played.
+    ///
+    /// A second is logged once it has been played completely, or when the source ends or
+    /// seeks.
+    pub fn events(&self) -> Vec<(Duration, usize)> {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// Clears the log.
+    pub fn clear(&self) {
+        self.0.lock().unwrap().clear();
+    }
+}
+
+impl<I> ClipLog<I> {
+    /// Returns a handle to read the log.
+    #[inline]
+    pub fn handle(&self) -> ClipLogHandle {
+        ClipLogHandle(self.events.clone())
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    /// Ends the current window, and publishes it if samples clipped in it.
+    fn end_window(&mut self, duration: Duration, block: bool) {
+        if self.clipped > 0 {
+            self.pending.push((self.window_start, self.clipped));
+        }
+        self.window_start += duration;
+        self.frames_in_window = 0;
+        self.clipped = 0;
+        if self.pending.is_empty() {
+            return;
+        }
+        // Try again at the end of the next window rather than block the audio thread.
+        let events = if block {
+            self.events.lock().ok()
+        } else {
+            self.events.try_lock().ok()
+        };
+        if let Some(mut events) = events {
+            events.append(&mut self.pending);
+        }
+    }
+}
+
+impl<I> Iterator for ClipLog<I>
+where
+    I: Source,
+{
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        let Some(sample) = self.input.next() else {
+            let sample_rate = self.input.sample_rate().get();
+            let played = Duration::from_secs_f64(self.frames_in_window as f64 / sample_rate as f64);
+            self.end_window(played, true);
+            return None;
+        };
+        if sample.abs() > 1.0 {
+            self.clipped += 1;
+        }
+        self.channel += 1;
+        if self.channel >= self.input.channels().get() {
+            self.channel = 0;
+            self.frames_in_window += 1;
+            if self.frames_in_window >= self.input.sample_rate().get() {
+                self.end_window(Duration::from_secs(1), false);
+            }
+        }
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for ClipLog<I> where I: Source + ExactSizeIterator {}
+
+impl<I> Source for ClipLog<I>
+where
+    I: Source,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    /// Logs the window played so far, windows continue from `pos`.
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.end_window(Duration::ZERO, true);
+        self.window_start = pos;
+        self.channel = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::Source;
+
+    #[test]
+    fn logs_clipping_in_second_half() {
+        // Four seconds of stereo at 1 kHz, the last two clip more and more.
+        let samples: Vec<f32> = (0..2 * 4000)
+            .map(|i| {
+                let frame = i / 2;
+                match frame {
+                    0..2000 => 0.5,
+                    2000..3000 if frame % 100 == 0 => 1.5,
+                    3000.. if frame % 10 == 0 => -1.5,
+                    _ => 0.9,
+                }
+            })
+            .collect();
+        let source = SamplesBuffer::new(nz!(2), nz!(1000), samples).clip_log();
+        let handle = source.handle();
+        assert_eq!(source.count(), 2 * 4000);
+        assert_eq!(
+            handle.events(),
+            [
+                (Duration::from_secs(2), 2 * 10),
+                (Duration::from_secs(3), 2 * 100)
+            ]
+        );
+
+        handle.clear();
+        assert!(handle.events().is_empty());
+    }
+
+    #[test]
+    fn logs_partial_last_second() {
+        let mut samples = vec![0.0; 1500];
+        samples[1200] = 2.0;
+        let source = SamplesBuffer::new(nz!(1), nz!(1000), samples).clip_log();
+        let handle = source.handle();
+        source.for_each(drop);
+        assert_eq!(handle.events(), [(Duration::from_secs(1), 1)]);
+    }
+}
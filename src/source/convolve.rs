@@ -0,0 +1,307 @@
+use std::time::Duration;
+
+use super::{SeekError, UniformSourceIterator};
+use crate::common::{ChannelCount, SampleRate};
+use crate::math::Fft;
+use crate::{Sample, Source};
+
+/// Smallest and largest number of frames processed at once.
+const MIN_BLOCK: usize = 64;
+const MAX_BLOCK: usize = 1024;
+
+/// Internal function that builds a `Convolve` object.
+///
+/// # Panics
+///
+/// Panics if `ir` is empty.
+pub fn convolve<I>(input: I, ir: Vec<f32>) -> Convolve<I>
+where
+    I: Source,
+{
+    assert!(!ir.is_empty(), "the impulse response can not be empty");
+    let channels = input.channels();
+    let sample_rate = input.sample_rate();
+    let block = ir.len().next_power_of_two().clamp(MIN_BLOCK, MAX_BLOCK);
+    let fft = Fft::new(2 * block);
+
+    // The spectrum of each block long part of the impulse response, zero padded so the
+    // convolution with a block of input does not wrap around.
+    let partitions: Vec<Spectrum> = ir
+        .chunks(block)
+        .map(|part| {
+            let mut re = vec![0.0; 2 * block];
+            re[..part.len()].copy_from_slice(part);
+            let mut im = vec![0.0; 2 * block];
+            fft.process(&mut re, &mut im);
+            (re, im)
+        })
+        .collect();
+    let history = (0..channels.get())
+        .map(|_| vec![(vec![0.0; 2 * block], vec![0.0; 2 * block]); partitions.len()])
+        .collect();
+
+    Convolve {
+        input: UniformSourceIterator::new(input, channels, sample_rate),
+        block,
+        tail_frames: ir.len() as u64 - 1,
+        fft,
+        partitions,
+        history,
+        history_pos: 0,
+        sum: (vec![0.0; 2 * block], vec![0.0; 2 * block]),
+        overlap: vec![vec![0.0; block]; channels.get() as usize],
+        output: Vec::with_capacity(block * channels.get() as usize),
+        output_pos: 0,
+        input_frames: 0,
+        output_frames: 0,
+        input_done: false,
+    }
+}
+
+type Spectrum = (Vec<f32>, Vec<f32>);
+
+/// Convolves a source with an impulse response, see [`Source::convolve`].
+///
+/// Uses uniformly partitioned overlap-add FFT convolution: the impulse response is split into
+/// blocks of up to 1024 frames and the input is processed one block at a time, so long
+/// impulse responses stay cheap. The input is read one block ahead, the output is not
+/// delayed.
+#[derive(Clone)]
+pub struct Convolve<I>
+where
+    I: Source,
+{
+    input: UniformSourceIterator<I>,
+    block: usize,
+    tail_frames: u64,
+    fft: Fft,
+    partitions: Vec<Spectrum>,
+    // Spectra of the most recent input blocks of each channel, `history_pos` being the newest.
+    // The newest is read into in place.
+    history: Vec<Vec<Spectrum>>,
+    history_pos: usize,
+    // Scratch space summing the spectrum of the output of a channel.
+    sum: Spectrum,
+    // Second half of the last convolved block of each channel, added to the next one.
+    overlap: Vec<Vec<Sample>>,
+    // Interleaved output of the current block.
+    output: Vec<Sample>,
+    output_pos: usize,
+    input_frames: u64,
+    output_frames: u64,
+    input_done: bool,
+}
+
+impl<I> Convolve<I>
+where
+    I: Source,
+{
+    /// Returns how much longer the output plays than the input: the length of the impulse
+    /// response minus one frame.
+    pub fn tail_duration(&self) -> Duration {
+        Duration::from_secs_f64(self.tail_frames as f64 / self.input.sample_rate().get() as f64)
+    }
+
+    /// Total number of frames the output has, known once the input ended.
+    fn end(&self) -> u64 {
+        if self.input_frames == 0 {
+            0
+        } else {
+            self.input_frames + self.tail_frames
+        }
+    }
+
+    /// Convolves the next block of input into `output`.
+    fn process_block(&mut self) {
+        let channels = self.overlap.len();
+        let parts = self.partitions.len();
+        let pos = (self.history_pos + 1) % parts;
+        self.history_pos = pos;
+        for history in &mut self.history {
+            history[pos].0.fill(0.0);
+            history[pos].1.fill(0.0);
+        }
+
+        let mut frames = 0;
+        'read: while !self.input_done && frames < self.block {
+            for history in &mut self.history {
+                match self.input.next() {
+                    Some(sample) => history[pos].0[frames] = sample,
+                    None => {
+                        self.input_done = true;
+                        break 'read;
+                    }
+                }
+            }
+            frames += 1;
+        }
+        self.input_frames += frames as u64;
+
+        self.output.clear();
+        self.output_pos = 0;
+        let len = if self.input_done {
+            (self.end() - self.output_frames).min(self.block as u64) as usize
+        } else {
+            self.block
+        };
+        self.output.resize(len * channels, 0.0);
+
+        let (sum_re, sum_im) = &mut self.sum;
+        for (channel, history) in self.history.iter_mut().enumerate() {
+            let (re, im) = &mut history[pos];
+            self.fft.process(re, im);
+
+            sum_re.fill(0.0);
+            sum_im.fill(0.0);
+            for (age, (ir_re, ir_im)) in self.partitions.iter().enumerate() {
+                let (x_re, x_im) = &history[(pos + parts - age) % parts];
+                for bin in 0..2 * self.block {
+                    sum_re[bin] += x_re[bin] * ir_re[bin] - x_im[bin] * ir_im[bin];
+                    sum_im[bin] += x_re[bin] * ir_im[bin] + x_im[bin] * ir_re[bin];
+                }
+            }
+            // Inverse transform by swapping the real and imaginary parts.
+            self.fft.process(sum_im, sum_re);
+            let scale = 1.0 / (2 * self.block) as f32;
+
+            let overlap = &mut self.overlap[channel];
+            for (frame, sample) in sum_re[..len].iter().enumerate() {
+                self.output[frame * channels + channel] = sample * scale + overlap[frame];
+            }
+            for (overlap, sample) in overlap.iter_mut().zip(&sum_re[self.block..]) {
+                *overlap = sample * scale;
+            }
+        }
+        self.output_frames += len as u64;
+    }
+}
+
+impl<I> Iterator for Convolve<I>
+where
+    I: Source,
+{
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        if self.output_pos >= self.output.len() {
+            if self.input_done && self.output_frames >= self.end() {
+                return None;
+            }
+            self.process_block();
+        }
+        let sample = self.output.get(self.output_pos).copied()?;
+        self.output_pos += 1;
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.output.len() - self.output_pos, None)
+    }
+}
+
+impl<I> Source for Convolve<I>
+where
+    I: Source,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input
+            .total_duration()
+            .map(|duration| duration + self.tail_duration())
+    }
+
+    /// Seeks the inner source, the reverb tail of what played before the seek is dropped.
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        for spectra in &mut self.history {
+            for (re, im) in spectra {
+                re.fill(0.0);
+                im.fill(0.0);
+            }
+        }
+        for overlap in &mut self.overlap {
+            overlap.fill(0.0);
+        }
+        self.output.clear();
+        self.output_pos = 0;
+        let frames = (pos.as_secs_f64() * self.input.sample_rate().get() as f64).round() as u64;
+        self.input_frames = frames;
+        self.output_frames = frames;
+        self.input_done = false;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::Source;
+
+    fn ir(len: usize) -> Vec<f32> {
+        (0..len).map(|i| (i as f32 * 0.37).sin() * 0.5).collect()
+    }
+
+    #[test]
+    fn impulse_gives_back_ir() {
+        // Long enough to be split into three partitions.
+        let ir = ir(3000);
+        let mut impulse = vec![0.0; 10];
+        impulse[0] = 1.0;
+        let convolved = SamplesBuffer::new(nz!(1), nz!(1000), impulse).convolve(ir.clone());
+        assert_eq!(convolved.tail_duration(), Duration::from_millis(2999));
+        assert_eq!(
+            convolved.total_duration(),
+            Some(Duration::from_millis(3009))
+        );
+
+        let output: Vec<f32> = convolved.collect();
+        assert_eq!(output.len(), 3009);
+        for (i, (out, expected)) in output.iter().zip(&ir).enumerate() {
+            assert!((out - expected).abs() < 1e-4, "{i}: {out} != {expected}");
+        }
+        assert!(output[3000..].iter().all(|s| s.abs() < 1e-4));
+    }
+
+    #[test]
+    fn convolves_channels_separately() {
+        let ir = ir(100);
+        let mut input = vec![0.0; 2 * 2000];
+        input[0] = 1.0;
+        // An impulse on the right channel during the second block.
+        input[2 * 1500 + 1] = 0.5;
+        let output: Vec<f32> = SamplesBuffer::new(nz!(2), nz!(1000), input)
+            .convolve(ir.clone())
+            .collect();
+        assert_eq!(output.len(), 2 * 2099);
+
+        let left: Vec<f32> = output.iter().step_by(2).copied().collect();
+        let right: Vec<f32> = output.iter().skip(1).step_by(2).copied().collect();
+        for (i, expected) in ir.iter().enumerate() {
+            assert!((left[i] - expected).abs() < 1e-4);
+            assert!((right[1500 + i] - expected * 0.5).abs() < 1e-4);
+        }
+        assert!(right[..1500].iter().all(|s| s.abs() < 1e-4));
+        assert!(left[100..].iter().all(|s| s.abs() < 1e-4));
+    }
+}
@@ -0,0 +1,234 @@
+use std::time::Duration;
+
+use super::{AtomicParam, SeekError};
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Length of the window the correlation is measured over.
+const WINDOW: Duration = Duration::from_millis(300);
+
+/// Internal function that builds a `CorrelationMeter` object.
+pub fn correlation_meter<I>(input: I) -> CorrelationMeter<I>
+where
+    I: Source,
+{
+    let frames = (WINDOW.as_secs_f64() * input.sample_rate().get() as f64).ceil() as usize;
+    CorrelationMeter {
+        input,
+        value: AtomicParam::new(0.0),
+        window: vec![[0.0; 3]; frames.max(1)],
+        write_pos: 0,
+        sums: [0.0; 3],
+        left: 0.0,
+        right: 0.0,
+        channel: 0,
+    }
+}
+
+/// Measures the phase correlation of the left and right channel over the last 300 ms.
+///
+/// The correlation is `1.0` when both channels carry the same signal, `0.0` when they are
+/// unrelated and `-1.0` when one is the inverse of the other. Negative values warn that the
+/// channels cancel out when the sound is mixed down to mono. Silence reads `0.0`.
+///
+/// The samples pass through unchanged. Mono sources read `1.0`, of sources with more
+/// channels only the first two are measured. Read the value from another thread through a
+/// [`CorrelationHandle`], it is updated every frame without locks.
+#[derive(Clone, Debug)]
+pub struct CorrelationMeter<I> {
+    input: I,
+    value: AtomicParam,
+    // The products `left * right`, `left * left` and `right * right` of the recent frames,
+    // a ring buffer written at `write_pos`.
+    window: Vec<[f64; 3]>,
+    write_pos: usize,
+    // Sums of the products in `window`.
+    sums: [f64; 3],
+    left: Sample,
+    right: Sample,
+    // The channel of the next sample.
+    channel: u16,
+}
+
+/// Reads the correlation measured by a [`CorrelationMeter`], from any thread.
+#[derive(Clone, Debug)]
+pub struct CorrelationHandle(AtomicParam);
+
+impl CorrelationHandle {
+    /// Returns the correlation of the most recent window, between `-1.0` and `1.0`.
+    #[inline]
+    pub fn correlation(&self) -> f32 {
+        self.0.get()
+    }
+}
+
+impl<I> CorrelationMeter<I> {
+    /// Returns the correlation of the most recent window, between `-1.0` and `1.0`.
+    #[inline]
+    pub fn correlation(&self) -> f32 {
+        self.value.get()
+    }
+
+    /// Returns a handle to read the correlation, for example from a UI thread.
+    #[inline]
+    pub fn handle(&self) -> CorrelationHandle {
+        CorrelationHandle(self.value.clone())
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    fn push_frame(&mut self) {
+        let (left, right) = (self.left as f64, self.right as f64);
+        let products = [left * right, left * left, right * right];
+        let old = std::mem::replace(&mut self.window[self.write_pos], products);
+        for ((sum, new), old) in self.sums.iter_mut().zip(products).zip(old) {
+            *sum += new - old;
+        }
+        self.write_pos += 1;
+        if self.write_pos == self.window.len() {
+            self.write_pos = 0;
+            // Start over from exact sums so rounding errors do not add up.
+            self.sums = self.window.iter().fold([0.0; 3], |sums, products| {
+                [
+                    sums[0] + products[0],
+                    sums[1] + products[1],
+                    sums[2] + products[2],
+                ]
+            });
+        }
+
+        let [cross, left, right] = self.sums;
+        let energy = (left * right).sqrt();
+        let correlation = if energy > 1e-12 { cross / energy } else { 0.0 };
+        self.value.set(correlation.clamp(-1.0, 1.0) as f32);
+    }
+}
+
+impl<I> Iterator for CorrelationMeter<I>
+where
+    I: Source,
+{
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        let sample = self.input.next()?;
+        let channels = self.input.channels().get();
+        match self.channel {
+            0 => {
+                self.left = sample;
+                self.right = sample;
+            }
+            1 => self.right = sample,
+            _ => {}
+        }
+        self.channel += 1;
+        if self.channel >= channels {
+            self.channel = 0;
+            self.push_frame();
+        }
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for CorrelationMeter<I> where I: Source + ExactSizeIterator {}
+
+impl<I> Source for CorrelationMeter<I>
+where
+    I: Source,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.window.fill([0.0; 3]);
+        self.sums = [0.0; 3];
+        self.write_pos = 0;
+        self.channel = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::source::SineWave;
+    use crate::Source;
+
+    fn correlation(left: impl Fn(f32) -> f32, right: impl Fn(f32) -> f32) -> f32 {
+        let samples: Vec<f32> = SineWave::new(440.0)
+            .take(48000)
+            .flat_map(|s| [left(s), right(s)])
+            .collect();
+        let mut meter = SamplesBuffer::new(nz!(2), nz!(48000), samples).correlation_meter();
+        meter.by_ref().for_each(drop);
+        meter.correlation()
+    }
+
+    #[test]
+    fn identical_channels_correlate() {
+        assert!((correlation(|s| s, |s| s) - 1.0).abs() < 1e-4);
+        assert!((correlation(|s| s, |s| 0.5 * s) - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn inverted_channels_anticorrelate() {
+        assert!((correlation(|s| s, |s| -s) + 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn silence_and_mono() {
+        let mut silence =
+            SamplesBuffer::new(nz!(2), nz!(1000), vec![0.0; 2000]).correlation_meter();
+        silence.by_ref().for_each(drop);
+        assert_eq!(silence.correlation(), 0.0);
+
+        let mut mono = SineWave::new(440.0)
+            .take_duration(std::time::Duration::from_millis(100))
+            .correlation_meter();
+        let handle = mono.handle();
+        mono.by_ref().for_each(drop);
+        assert!((handle.correlation() - 1.0).abs() < 1e-4);
+    }
+}
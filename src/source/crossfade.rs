@@ -1,3 +1,4 @@
+use crate::source::mix::mix_to;
 use crate::source::{FadeIn, Mix, TakeDuration};
 use crate::Source;
 use std::time::Duration;
@@ -6,7 +7,8 @@ use std::time::Duration;
 /// duration.
 ///
 /// Only the crossfaded portion (beginning of fadeout, beginning of fadein) is
-/// returned.
+/// returned. Both sounds are converted to the higher sample rate and larger
+/// channel count of the two.
 pub fn crossfade<I1, I2>(
     input_fadeout: I1,
     input_fadein: I2,
@@ -19,7 +21,9 @@ where
     let mut input_fadeout = input_fadeout.take_duration(duration);
     input_fadeout.set_filter_fadeout();
     let input_fadein = input_fadein.take_duration(duration).fade_in(duration);
-    input_fadeout.mix(input_fadein)
+    let channels = input_fadeout.channels().max(input_fadein.channels());
+    let rate = input_fadeout.sample_rate().max(input_fadein.sample_rate());
+    mix_to(input_fadeout, input_fadein, channels, rate)
 }
 
 /// Mixes one sound fading out with another sound fading in for the given
@@ -74,4 +78,18 @@ mod tests {
             .zip(vec![1.0, 2.0 * 0.8, 3.0 * 0.6, 4.0 * 0.4, 5.0 * 0.2])
             .all(|(a, b)| (a - b).abs() < 1e-6));
     }
+
+    #[test]
+    fn crossfade_converts_to_common_format() {
+        let stereo = SamplesBuffer::new(nz!(2), nz!(44100), vec![0.5; 44100 * 2]);
+        let mono = SamplesBuffer::new(nz!(1), nz!(48000), vec![0.5; 48000]);
+        let mixed = crossfade(stereo, mono, Duration::from_millis(500));
+        assert_eq!(mixed.channels(), nz!(2));
+        assert_eq!(mixed.sample_rate(), nz!(48000));
+
+        let result: Vec<f32> = mixed.collect();
+        assert!(result.len().abs_diff(48000) <= 8, "{}", result.len());
+        // The fades add up to the level of both inputs, without clicks.
+        assert!(result[..48000].iter().all(|s| (s - 0.5).abs() < 2e-3));
+    }
 }
@@ -0,0 +1,219 @@
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Cutoff of the low-pass filter applied to the signal fed to the other ear.
+const CUTOFF_HZ: f32 = 700.0;
+/// Extra time the sound needs to reach the other ear.
+const DELAY: Duration = Duration::from_micros(300);
+
+/// Internal function that builds a `Crossfeed` object.
+pub fn crossfeed<I>(input: I, amount: f32) -> Crossfeed<I>
+where
+    I: Source,
+{
+    let mut crossfeed = Crossfeed {
+        input,
+        amount: amount.clamp(0.0, 1.0),
+        sample_rate: 0,
+        coefficient: 0.0,
+        filtered: [0.0; 2],
+        delay_line: Vec::new(),
+        delay_pos: 0,
+        right: None,
+    };
+    crossfeed.update_sample_rate();
+    crossfeed
+}
+
+/// Blends each channel of a stereo source into the other, as happens naturally when listening
+/// to speakers.
+///
+/// This makes music mixed for speakers sound less wide on headphones. Following the design of
+/// Benjamin Bauer, the signal fed to the other ear is low-passed at 700 Hz and delayed by
+/// 0.3 ms to mimic the shadowing of the head. The output is scaled so that low frequencies
+/// present in both channels keep their level.
+///
+/// Only stereo is processed, sources with any other channel count pass through unchanged.
+#[derive(Clone, Debug)]
+pub struct Crossfeed<I> {
+    input: I,
+    amount: f32,
+    sample_rate: u32,
+    coefficient: f32,
+    // Low-passed left and right channel.
+    filtered: [Sample; 2],
+    delay_line: Vec<[Sample; 2]>,
+    delay_pos: usize,
+    // The right sample of the current frame, computed together with the left one.
+    right: Option<Sample>,
+}
+
+impl<I> Crossfeed<I>
+where
+    I: Source,
+{
+    /// Modifies the amount of crossfeed, between `0.0` (none) and `1.0` (both channels fully
+    /// fed to the other ear).
+    #[inline]
+    pub fn set_amount(&mut self, amount: f32) {
+        self.amount = amount.clamp(0.0, 1.0);
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    fn update_sample_rate(&mut self) {
+        let sample_rate = self.input.sample_rate().get();
+        if sample_rate == self.sample_rate {
+            return;
+        }
+        self.sample_rate = sample_rate;
+        self.coefficient = 1.0 - (-TAU * CUTOFF_HZ / sample_rate as f32).exp();
+        let delay = (DELAY.as_secs_f32() * sample_rate as f32).round() as usize;
+        self.delay_line = vec![[0.0; 2]; delay.max(1)];
+        self.delay_pos = 0;
+    }
+}
+
+impl<I> Iterator for Crossfeed<I>
+where
+    I: Source,
+{
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        if let Some(right) = self.right.take() {
+            return Some(right);
+        }
+
+        let stereo = self.input.channels().get() == 2;
+        let left = self.input.next()?;
+        if !stereo {
+            return Some(left);
+        }
+        let Some(right) = self.input.next() else {
+            return Some(left);
+        };
+        self.update_sample_rate();
+
+        for (filtered, sample) in self.filtered.iter_mut().zip([left, right]) {
+            *filtered += self.coefficient * (sample - *filtered);
+        }
+        let [crossed_left, crossed_right] =
+            std::mem::replace(&mut self.delay_line[self.delay_pos], self.filtered);
+        self.delay_pos = (self.delay_pos + 1) % self.delay_line.len();
+
+        let gain = 1.0 / (1.0 + self.amount);
+        self.right = Some((right + self.amount * crossed_left) * gain);
+        Some((left + self.amount * crossed_right) * gain)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let pending = self.right.is_some() as usize;
+        let (lower, upper) = self.input.size_hint();
+        (lower + pending, upper.map(|upper| upper + pending))
+    }
+}
+
+impl<I> ExactSizeIterator for Crossfeed<I> where I: Source + ExactSizeIterator {}
+
+impl<I> Source for Crossfeed<I>
+where
+    I: Source,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        let pending = self.right.is_some() as usize;
+        self.input.current_span_len().map(|len| len + pending)
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.right = None;
+        self.filtered = [0.0; 2];
+        self.delay_line.fill([0.0; 2]);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::Source;
+
+    /// A sine of `freq` Hz on the left channel only, returns the right channel after crossfeed.
+    fn crossfed_right(freq: f32) -> Vec<f32> {
+        let samples: Vec<f32> = (0..4800)
+            .flat_map(|i| [(TAU * freq * i as f32 / 48000.0).sin(), 0.0])
+            .collect();
+        let source = SamplesBuffer::new(nz!(2), nz!(48000), samples);
+        source.crossfeed(1.0).skip(1).step_by(2).collect()
+    }
+
+    fn peak(samples: &[f32]) -> f32 {
+        samples.iter().fold(0.0, |peak, s| s.abs().max(peak))
+    }
+
+    #[test]
+    fn left_reaches_right_delayed() {
+        let right = crossfed_right(200.0);
+        // 0.3 ms at 48 kHz.
+        assert!(right[..14].iter().all(|s| *s == 0.0));
+        assert!(right[15] != 0.0);
+        assert!(peak(&right[2400..]) > 0.1);
+    }
+
+    #[test]
+    fn crossfed_signal_is_low_passed() {
+        let low = peak(&crossfed_right(200.0)[2400..]);
+        let high = peak(&crossfed_right(8000.0)[2400..]);
+        assert!(high < low / 4.0, "{high} vs {low}");
+    }
+
+    #[test]
+    fn zero_amount_is_unchanged() {
+        let samples = vec![0.5, -0.25, 0.125, 1.0];
+        let source = SamplesBuffer::new(nz!(2), nz!(48000), samples.clone());
+        assert_eq!(source.crossfeed(0.0).collect::<Vec<_>>(), samples);
+    }
+}
@@ -136,3 +136,28 @@ where
         self.input.try_seek(compensated_for_delay)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::Source;
+
+    #[test]
+    fn prepends_silence() {
+        let tone: Vec<f32> = (0..48000)
+            .flat_map(|i| [(TAU * 440.0 * i as f32 / 48000.0).sin(); 2])
+            .collect();
+        let source = SamplesBuffer::new(nz!(2), nz!(48000), tone.clone());
+        let delayed = source.delay(Duration::from_millis(500));
+        assert_eq!(delayed.total_duration(), Some(Duration::from_millis(1500)));
+
+        let output: Vec<f32> = delayed.collect();
+        assert_eq!(output.len(), 3 * 48000);
+        assert!(output[..48000].iter().all(|s| *s == 0.0));
+        assert_eq!(output[48000..], tone);
+    }
+}
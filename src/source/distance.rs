@@ -0,0 +1,245 @@
+use std::f32::consts::PI;
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Cutoff of the air absorption filter at the reference distance, in Hz.
+const AIR_ABSORPTION_CUTOFF: f32 = 20_000.0;
+/// Distance beyond the reference distance over which the cutoff halves, in meters.
+const AIR_ABSORPTION_DISTANCE: f32 = 50.0;
+
+/// Internal function that builds a `DistanceAttenuation` object.
+pub fn distance_attenuation<I>(
+    input: I,
+    emitter_position: [f32; 3],
+    listener_position: [f32; 3],
+    reference_distance: f32,
+    rolloff: f32,
+    max_distance: f32,
+) -> DistanceAttenuation<I>
+where
+    I: Source,
+{
+    assert!(
+        reference_distance > 0.0,
+        "reference distance must be positive"
+    );
+    assert!(
+        max_distance >= reference_distance,
+        "max distance can not be below the reference distance"
+    );
+    let channels = input.channels().get() as usize;
+    let mut ret = DistanceAttenuation {
+        input,
+        reference_distance,
+        rolloff,
+        max_distance,
+        distance: reference_distance,
+        gain: 1.0,
+        coefficient: None,
+        state: vec![0.0; channels],
+        channel: 0,
+    };
+    ret.set_positions(emitter_position, listener_position);
+    ret
+}
+
+/// Attenuates a source with distance and mimics air absorption.
+///
+/// The amplitude follows an inverse distance law: within `reference_distance` the source
+/// plays at full volume, beyond it the gain is
+/// `reference_distance / (reference_distance + rolloff * (distance - reference_distance))`.
+/// The distance is clamped to `max_distance`, beyond which the source no longer gets quieter.
+///
+/// A one-pole low-pass filter removes more of the high frequencies the further away the
+/// source is.
+#[derive(Clone, Debug)]
+pub struct DistanceAttenuation<I> {
+    input: I,
+    reference_distance: f32,
+    rolloff: f32,
+    max_distance: f32,
+    distance: f32,
+    gain: f32,
+    // Recomputed at span boundaries as the sample rate may change.
+    coefficient: Option<f32>,
+    state: Vec<Sample>,
+    channel: usize,
+}
+
+impl<I> DistanceAttenuation<I>
+where
+    I: Source,
+{
+    /// Sets the position of the emitter and the listener in the 3D world.
+    pub fn set_positions(&mut self, emitter_position: [f32; 3], listener_position: [f32; 3]) {
+        let distance = emitter_position
+            .iter()
+            .zip(listener_position.iter())
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum::<f32>()
+            .sqrt();
+        self.distance = distance.clamp(self.reference_distance, self.max_distance);
+        self.gain = self.reference_distance
+            / (self.reference_distance + self.rolloff * (self.distance - self.reference_distance));
+        self.coefficient = None;
+    }
+
+    /// Returns the gain currently applied because of the distance.
+    #[inline]
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    fn cutoff(&self) -> f32 {
+        AIR_ABSORPTION_CUTOFF
+            / (1.0 + (self.distance - self.reference_distance) / AIR_ABSORPTION_DISTANCE)
+    }
+}
+
+impl<I> Iterator for DistanceAttenuation<I>
+where
+    I: Source,
+{
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        let last_in_span = self.input.current_span_len() == Some(1);
+
+        let coefficient = match self.coefficient {
+            Some(coefficient) => coefficient,
+            None => {
+                let sample_rate = self.input.sample_rate().get() as f32;
+                let cutoff = self.cutoff().min(sample_rate / 2.0);
+                let coefficient = 1.0 - (-2.0 * PI * cutoff / sample_rate).exp();
+                self.coefficient = Some(coefficient);
+                coefficient
+            }
+        };
+
+        let channels = self.input.channels().get() as usize;
+        if self.state.len() != channels {
+            self.state = vec![0.0; channels];
+            self.channel = 0;
+        }
+
+        let sample = self.input.next()?;
+        let state = &mut self.state[self.channel];
+        *state += coefficient * (sample - *state);
+        let result = *state * self.gain;
+        self.channel = (self.channel + 1) % channels;
+
+        if last_in_span {
+            self.coefficient = None;
+        }
+
+        Some(result)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for DistanceAttenuation<I> where I: Source + ExactSizeIterator {}
+
+impl<I> Source for DistanceAttenuation<I>
+where
+    I: Source,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.state.fill(0.0);
+        self.channel = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::source::{SineWave, Source};
+
+    const LISTENER: [f32; 3] = [0.0, 0.0, 0.0];
+
+    fn peak_at(frequency: f32, distance: f32) -> f32 {
+        SineWave::new(frequency)
+            .distance_attenuation([distance, 0.0, 0.0], LISTENER, 1.0, 1.0, 1000.0)
+            .skip(4800)
+            .take(4800)
+            .fold(0.0, |max, s| s.abs().max(max))
+    }
+
+    #[test]
+    fn full_volume_within_reference_distance() {
+        let near =
+            SineWave::new(100.0).distance_attenuation([0.5, 0.0, 0.0], LISTENER, 1.0, 1.0, 1000.0);
+        assert_eq!(near.gain(), 1.0);
+    }
+
+    #[test]
+    fn amplitude_drops_with_distance() {
+        let near = peak_at(100.0, 1.0);
+        let far = peak_at(100.0, 10.0);
+        assert!((near - 1.0).abs() < 0.01, "near: {near}");
+        assert!((far - 0.1).abs() < 0.01, "far: {far}");
+    }
+
+    #[test]
+    fn high_frequencies_fade_faster() {
+        let low_ratio = peak_at(100.0, 500.0) / peak_at(100.0, 1.0);
+        let high_ratio = peak_at(12_000.0, 500.0) / peak_at(12_000.0, 1.0);
+        assert!(high_ratio < low_ratio * 0.5, "{high_ratio} vs {low_ratio}");
+    }
+
+    #[test]
+    fn distance_is_clamped_to_max() {
+        let mut source =
+            SineWave::new(100.0).distance_attenuation([100.0, 0.0, 0.0], LISTENER, 1.0, 1.0, 10.0);
+        let gain = source.gain();
+        source.set_positions([1000.0, 0.0, 0.0], LISTENER);
+        assert_eq!(source.gain(), gain);
+    }
+}
@@ -0,0 +1,205 @@
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::math::nz;
+use crate::Source;
+
+const ROWS: [f32; 4] = [697.0, 770.0, 852.0, 941.0];
+const COLUMNS: [f32; 4] = [1209.0, 1336.0, 1477.0, 1633.0];
+
+/// A source that plays the dual-tone multi-frequency (DTMF) signals of a phone keypad.
+///
+/// Each key plays the sum of two sines for the tone duration, followed by a silent gap of
+/// 50 ms unless set with [`Dtmf::with_gap`]. There is no gap after the last key. The keys are
+/// the digits, `*`, `#` and the letters `A` to `D`.
+///
+/// Always has one channel.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use rodio::nz;
+/// use rodio::source::Dtmf;
+///
+/// let dial = Dtmf::new("555 0123", Duration::from_millis(100), nz!(8000));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Dtmf {
+    // The low and high frequency of each key.
+    keys: Vec<(f32, f32)>,
+    sample_rate: SampleRate,
+    tone_len: u64,
+    gap_len: u64,
+    position: u64,
+}
+
+impl Dtmf {
+    /// Builds a source that plays `keys`, each for `tone_duration`.
+    ///
+    /// Spaces and dashes in `keys` are skipped, so formatted phone numbers can be used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `keys` contains any other character that is not on a DTMF keypad.
+    pub fn new(keys: &str, tone_duration: Duration, sample_rate: SampleRate) -> Dtmf {
+        let keys = keys
+            .chars()
+            .filter(|key| !matches!(key, ' ' | '-'))
+            .map(|key| {
+                let (row, column) = match key.to_ascii_uppercase() {
+                    '1' => (0, 0),
+                    '2' => (0, 1),
+                    '3' => (0, 2),
+                    'A' => (0, 3),
+                    '4' => (1, 0),
+                    '5' => (1, 1),
+                    '6' => (1, 2),
+                    'B' => (1, 3),
+                    '7' => (2, 0),
+                    '8' => (2, 1),
+                    '9' => (2, 2),
+                    'C' => (2, 3),
+                    '*' => (3, 0),
+                    '0' => (3, 1),
+                    '#' => (3, 2),
+                    'D' => (3, 3),
+                    _ => panic!("'{key}' is not a DTMF key"),
+                };
+                (ROWS[row], COLUMNS[column])
+            })
+            .collect();
+        let mut dtmf = Dtmf {
+            keys,
+            sample_rate,
+            tone_len: 0,
+            gap_len: 0,
+            position: 0,
+        };
+        dtmf.tone_len = dtmf.samples(tone_duration);
+        dtmf.gap_len = dtmf.samples(Duration::from_millis(50));
+        dtmf
+    }
+
+    /// Sets the silence between two keys.
+    pub fn with_gap(mut self, gap: Duration) -> Dtmf {
+        self.gap_len = self.samples(gap);
+        self
+    }
+
+    fn samples(&self, duration: Duration) -> u64 {
+        (duration.as_secs_f64() * self.sample_rate.get() as f64).round() as u64
+    }
+
+    fn total_samples(&self) -> u64 {
+        let keys = self.keys.len() as u64;
+        (keys * (self.tone_len + self.gap_len)).saturating_sub(self.gap_len)
+    }
+}
+
+impl Iterator for Dtmf {
+    type Item = f32;
+
+    #[inline]
+    fn next(&mut self) -> Option<f32> {
+        if self.position >= self.total_samples() {
+            return None;
+        }
+        let key = self.position / (self.tone_len + self.gap_len);
+        let offset = self.position % (self.tone_len + self.gap_len);
+        self.position += 1;
+        if offset >= self.tone_len {
+            return Some(0.0);
+        }
+        let (low, high) = self.keys[key as usize];
+        let t = offset as f32 / self.sample_rate.get() as f32;
+        Some(((TAU * low * t).sin() + (TAU * high * t).sin()) * 0.5)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.total_samples().saturating_sub(self.position) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Dtmf {}
+
+impl Source for Dtmf {
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        nz!(1)
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f64(
+            self.total_samples() as f64 / self.sample_rate.get() as f64,
+        ))
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.position = self.samples(pos).min(self.total_samples());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+    use std::time::Duration;
+
+    use super::Dtmf;
+    use crate::math::nz;
+    use crate::Source;
+
+    /// Level of `freq` in `samples` at 8 kHz.
+    fn level(samples: &[f32], freq: f32) -> f32 {
+        let (sin, cos) = samples
+            .iter()
+            .enumerate()
+            .fold((0.0, 0.0), |(sin, cos), (i, s)| {
+                let phase = TAU * freq * i as f32 / 8000.0;
+                (sin + s * phase.sin(), cos + s * phase.cos())
+            });
+        2.0 * (sin * sin + cos * cos).sqrt() / samples.len() as f32
+    }
+
+    #[test]
+    fn plays_key_frequencies_in_sequence() {
+        let dtmf = Dtmf::new("123", Duration::from_millis(100), nz!(8000));
+        assert_eq!(dtmf.total_duration(), Some(Duration::from_millis(400)));
+        let output: Vec<f32> = dtmf.collect();
+        assert_eq!(output.len(), 3200);
+
+        let columns = [1209.0, 1336.0, 1477.0];
+        for (key, column) in columns.iter().enumerate() {
+            let tone = &output[key * 1200..key * 1200 + 800];
+            assert!(level(tone, 697.0) > 0.45, "key {key}");
+            for other in columns {
+                let expected = if other == *column { 0.45 } else { 0.05 };
+                assert_eq!(level(tone, other) > expected, other == *column, "key {key}");
+            }
+        }
+        assert!(output[800..1200].iter().all(|s| *s == 0.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "'x' is not a DTMF key")]
+    fn rejects_unknown_keys() {
+        Dtmf::new("12x", Duration::from_millis(100), nz!(8000));
+    }
+}
@@ -0,0 +1,329 @@
+use std::f32::consts::PI;
+use std::time::Duration;
+
+use super::{AtomicParam, SeekError};
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+// Peaking filters following http://www.musicdsp.org/files/Audio-EQ-Cookbook.txt
+
+/// One band of an [`Equalizer`]: a peaking filter that boosts or cuts around a frequency.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EqBand {
+    /// Center frequency in Hz.
+    pub frequency: f32,
+    /// Quality factor, higher values affect a narrower range around the center. About `1.4`
+    /// gives the one octave bands of a 10-band graphic equalizer.
+    pub q: f32,
+    /// Gain at the center frequency in decibels, negative values cut.
+    pub gain_db: f32,
+}
+
+impl EqBand {
+    /// Creates a band, see the fields for the meaning of the arguments.
+    pub fn new(frequency: f32, q: f32, gain_db: f32) -> EqBand {
+        EqBand {
+            frequency,
+            q,
+            gain_db,
+        }
+    }
+}
+
+/// A graphic equalizer applying a list of [`EqBand`]s in series.
+///
+/// The gain of each band can be changed while playing through an [`EqualizerHandle`].
+#[derive(Clone, Debug)]
+pub struct Equalizer<I> {
+    input: I,
+    bands: Vec<Band>,
+    handle: EqualizerHandle,
+    sample_rate: SampleRate,
+    // The channel of the next sample.
+    channel: usize,
+}
+
+#[derive(Clone, Debug)]
+struct Band {
+    frequency: f32,
+    q: f32,
+    // The gain the coefficients were computed for.
+    gain_db: f32,
+    coefficients: Coefficients,
+    // Previous inputs and outputs of each channel: x[n-1], x[n-2], y[n-1], y[n-2].
+    history: Vec<[f32; 4]>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Coefficients {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+}
+
+impl Coefficients {
+    fn peaking(frequency: f32, q: f32, gain_db: f32, sample_rate: SampleRate) -> Coefficients {
+        let a = 10f32.powf(gain_db / 40.0);
+        let w0 = 2.0 * PI * frequency / sample_rate.get() as f32;
+        let alpha = w0.sin() / (2.0 * q);
+        let cos_w0 = w0.cos();
+        let a0 = 1.0 + alpha / a;
+        Coefficients {
+            b0: (1.0 + alpha * a) / a0,
+            b1: -2.0 * cos_w0 / a0,
+            b2: (1.0 - alpha * a) / a0,
+            a1: -2.0 * cos_w0 / a0,
+            a2: (1.0 - alpha / a) / a0,
+        }
+    }
+}
+
+/// Changes the gains of an [`Equalizer`] from any thread, see [`Equalizer::handle`].
+#[derive(Clone, Debug)]
+pub struct EqualizerHandle(Vec<AtomicParam>);
+
+impl EqualizerHandle {
+    /// Sets the gain of band `index` in decibels, the equalizer picks it up from its next
+    /// frame.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no band `index`.
+    #[inline]
+    pub fn set_band_gain(&self, index: usize, gain_db: f32) {
+        self.0[index].set(gain_db);
+    }
+
+    /// Returns the gain of band `index` in decibels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no band `index`.
+    #[inline]
+    pub fn band_gain(&self, index: usize) -> f32 {
+        self.0[index].get()
+    }
+
+    /// Returns the number of bands.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the equalizer has no bands.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<I> Equalizer<I>
+where
+    I: Source,
+{
+    /// Builds an equalizer applying `bands` to `input`, see [`Source::equalizer`].
+    pub fn new(input: I, bands: Vec<EqBand>) -> Equalizer<I> {
+        let sample_rate = input.sample_rate();
+        let channels = input.channels().get() as usize;
+        let handle = EqualizerHandle(
+            bands
+                .iter()
+                .map(|band| AtomicParam::new(band.gain_db))
+                .collect(),
+        );
+        let bands = bands
+            .into_iter()
+            .map(|band| Band {
+                frequency: band.frequency,
+                q: band.q,
+                gain_db: band.gain_db,
+                coefficients: Coefficients::peaking(
+                    band.frequency,
+                    band.q,
+                    band.gain_db,
+                    sample_rate,
+                ),
+                history: vec![[0.0; 4]; channels],
+            })
+            .collect();
+        Equalizer {
+            input,
+            bands,
+            handle,
+            sample_rate,
+            channel: 0,
+        }
+    }
+}
+
+impl<I> Equalizer<I> {
+    /// Returns a handle to change the gains, for example from a UI thread.
+    #[inline]
+    pub fn handle(&self) -> EqualizerHandle {
+        self.handle.clone()
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> Equalizer<I>
+where
+    I: Source,
+{
+    /// Picks up gain, sample rate and channel count changes at the start of a frame.
+    fn update(&mut self) {
+        let sample_rate = self.input.sample_rate();
+        let channels = self.input.channels().get() as usize;
+        let rate_changed = sample_rate != self.sample_rate;
+        self.sample_rate = sample_rate;
+        for (band, gain) in self.bands.iter_mut().zip(&self.handle.0) {
+            let gain_db = gain.get();
+            if rate_changed || gain_db != band.gain_db {
+                band.gain_db = gain_db;
+                band.coefficients =
+                    Coefficients::peaking(band.frequency, band.q, gain_db, sample_rate);
+            }
+            if band.history.len() != channels {
+                band.history = vec![[0.0; 4]; channels];
+            }
+        }
+    }
+}
+
+impl<I> Iterator for Equalizer<I>
+where
+    I: Source,
+{
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        if self.channel == 0 {
+            self.update();
+        }
+        let mut sample = self.input.next()?;
+        let channel = self.channel;
+        self.channel = (channel + 1) % self.input.channels().get() as usize;
+
+        for band in &mut self.bands {
+            let Coefficients { b0, b1, b2, a1, a2 } = band.coefficients;
+            let [x1, x2, y1, y2] = band.history[channel];
+            let output = b0 * sample + b1 * x1 + b2 * x2 - a1 * y1 - a2 * y2;
+            band.history[channel] = [sample, x1, output, y1];
+            sample = output;
+        }
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for Equalizer<I> where I: Source + ExactSizeIterator {}
+
+impl<I> Source for Equalizer<I>
+where
+    I: Source,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        for band in &mut self.bands {
+            band.history.fill([0.0; 4]);
+        }
+        self.channel = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EqBand;
+    use crate::math::nz;
+    use crate::source::SineWave;
+    use crate::Source;
+
+    fn gain(frequency: f32, bands: &[EqBand]) -> f32 {
+        let output: Vec<f32> = SineWave::new(frequency)
+            .equalizer(bands.to_vec())
+            .take(9600)
+            .skip(4800)
+            .collect();
+        output.iter().fold(0.0, |peak, s| s.abs().max(peak))
+    }
+
+    #[test]
+    fn boosts_mid_band() {
+        let bands = [
+            EqBand::new(100.0, 1.4, 0.0),
+            EqBand::new(1000.0, 1.4, 12.0),
+            EqBand::new(10_000.0, 1.4, 0.0),
+        ];
+        let boost = 10f32.powf(12.0 / 20.0);
+        assert!((gain(1000.0, &bands) - boost).abs() < 0.05);
+        // The bump falls off away from the center.
+        assert!(gain(500.0, &bands) < 0.6 * boost);
+        assert!(gain(2000.0, &bands) < 0.6 * boost);
+        assert!((gain(100.0, &bands) - 1.0).abs() < 0.05);
+        assert!((gain(10_000.0, &bands) - 1.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn handle_changes_gain_while_playing() {
+        let stereo = SineWave::new(1000.0).reformat(nz!(2), nz!(48000));
+        let mut eq = stereo.equalizer(vec![EqBand::new(1000.0, 1.4, 0.0)]);
+        let handle = eq.handle();
+        assert_eq!(handle.len(), 1);
+
+        let peak = |eq: &mut dyn Iterator<Item = f32>| {
+            eq.take(9600)
+                .skip(4800)
+                .fold(0.0f32, |peak, s| s.abs().max(peak))
+        };
+        assert!((peak(&mut eq) - 1.0).abs() < 0.01);
+        handle.set_band_gain(0, -12.0);
+        assert_eq!(handle.band_gain(0), -12.0);
+        let cut = 10f32.powf(-12.0 / 20.0);
+        assert!((peak(&mut eq) - cut).abs() < 0.01);
+    }
+}
@@ -0,0 +1,137 @@
+use std::time::Duration;
+
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Builds a source that calls `f` to produce each frame.
+///
+/// `f` receives the index of the frame, starting at zero, and returns one sample per channel.
+/// The number of channels is the length `N` of the returned array. If `f` returns `None`, then
+/// the sound ends.
+///
+/// **Note**: `f` runs on the audio thread. It should not block, allocate or do anything else
+/// that may take an unpredictable amount of time, as that can cause audible glitches.
+///
+/// # Panics
+///
+/// Panics if `N` is zero.
+///
+/// # Example
+///
+/// ```
+/// use rodio::nz;
+/// use rodio::source::{from_fn, Source};
+///
+/// // One second of a 440 Hz sine in stereo.
+/// let source = from_fn(nz!(48000), |frame| {
+///     let t = frame as f32 / 48000.0;
+///     let sample = (std::f32::consts::TAU * 440.0 * t).sin();
+///     (frame < 48000).then_some([sample, sample])
+/// });
+/// assert_eq!(source.channels(), nz!(2));
+/// ```
+pub fn from_fn<F, const N: usize>(sample_rate: SampleRate, f: F) -> FromFn<F, N>
+where
+    F: FnMut(u64) -> Option<[Sample; N]>,
+{
+    let channels = u16::try_from(N)
+        .ok()
+        .and_then(ChannelCount::new)
+        .expect("a frame needs at least one and at most u16::MAX channels");
+    FromFn {
+        f,
+        channels,
+        sample_rate,
+        frame_index: 0,
+        frame: [0.0; N],
+        pos_in_frame: N,
+    }
+}
+
+/// A source whose frames are produced by a closure, see [`from_fn`].
+#[derive(Clone)]
+pub struct FromFn<F, const N: usize> {
+    f: F,
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+    frame_index: u64,
+    frame: [Sample; N],
+    pos_in_frame: usize,
+}
+
+impl<F, const N: usize> Iterator for FromFn<F, N>
+where
+    F: FnMut(u64) -> Option<[Sample; N]>,
+{
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        if self.pos_in_frame == N {
+            self.frame = (self.f)(self.frame_index)?;
+            self.frame_index += 1;
+            self.pos_in_frame = 0;
+        }
+        let sample = self.frame[self.pos_in_frame];
+        self.pos_in_frame += 1;
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (N - self.pos_in_frame, None)
+    }
+}
+
+impl<F, const N: usize> Source for FromFn<F, N>
+where
+    F: FnMut(u64) -> Option<[Sample; N]>,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.channels
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+
+    use super::from_fn;
+    use crate::math::nz;
+    use crate::source::{SineWave, Source};
+
+    #[test]
+    fn matches_sine_wave() {
+        let source = from_fn(nz!(48000), |frame| {
+            Some([(TAU * 440.0 * frame as f32 / 48000.0).sin()])
+        });
+        assert_eq!(source.channels(), nz!(1));
+        for (a, b) in source.zip(SineWave::new(440.0)).take(48000) {
+            assert!((a - b).abs() < 1e-2, "{a} != {b}");
+        }
+    }
+
+    #[test]
+    fn interleaves_and_ends() {
+        let source = from_fn(nz!(44100), |frame| {
+            (frame < 2).then_some([frame as f32, -(frame as f32)])
+        });
+        assert_eq!(source.collect::<Vec<_>>(), [0.0, -0.0, 1.0, -1.0]);
+    }
+}
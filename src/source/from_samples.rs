@@ -0,0 +1,132 @@
+use std::time::Duration;
+
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Builds a source that plays the interleaved samples produced by `iter`.
+///
+/// The sound ends when `iter` does. Unlike [`SamplesBuffer`](crate::buffer::SamplesBuffer)
+/// the samples do not need to be known in advance, so `iter` can be lazy or infinite.
+///
+/// **Note**: this is not [`from_iter`](super::from_iter), which chains sources.
+///
+/// # Example
+///
+/// ```
+/// use rodio::nz;
+/// use rodio::source::{from_samples, Source};
+///
+/// // A ramp from silence to full scale over one second.
+/// let ramp = from_samples((0..48000).map(|i| i as f32 / 48000.0), nz!(1), nz!(48000));
+/// assert_eq!(ramp.channels(), nz!(1));
+/// ```
+pub fn from_samples<I>(
+    iter: I,
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+) -> FromSamples<I::IntoIter>
+where
+    I: IntoIterator<Item = Sample>,
+{
+    FromSamples {
+        iter: iter.into_iter(),
+        channels,
+        sample_rate,
+    }
+}
+
+/// A source that plays the samples of an iterator, see [`from_samples`].
+#[derive(Clone, Debug)]
+pub struct FromSamples<I> {
+    iter: I,
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+}
+
+impl<I> FromSamples<I> {
+    /// Returns a reference to the inner iterator.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.iter
+    }
+
+    /// Returns a mutable reference to the inner iterator.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.iter
+    }
+
+    /// Returns the inner iterator.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.iter
+    }
+}
+
+impl<I> Iterator for FromSamples<I>
+where
+    I: Iterator<Item = Sample>,
+{
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        self.iter.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for FromSamples<I> where I: ExactSizeIterator<Item = Sample> {}
+
+impl<I> Source for FromSamples<I>
+where
+    I: Iterator<Item = Sample>,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.channels
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::from_samples;
+    use crate::math::nz;
+    use crate::Source;
+
+    #[test]
+    fn plays_samples_until_iterator_ends() {
+        let source = from_samples((0..100).map(|i| i as f32), nz!(1), nz!(8000));
+        assert_eq!(source.channels(), nz!(1));
+        assert_eq!(source.sample_rate(), nz!(8000));
+        assert_eq!(source.len(), 100);
+        let samples: Vec<f32> = source.collect();
+        assert_eq!(samples, (0..100).map(|i| i as f32).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn keeps_frames_interleaved() {
+        let stereo = from_samples([0.5, -0.5, 0.25, -0.25], nz!(2), nz!(1000));
+        let mono: Vec<f32> = stereo.reformat(nz!(1), nz!(1000)).collect();
+        assert_eq!(mono, [0.5, 0.25]);
+    }
+}
@@ -0,0 +1,368 @@
+//! Binaural rendering using head-related impulse responses.
+
+use std::f32::consts::FRAC_PI_2;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::{SeekError, UniformSourceIterator};
+use crate::common::{ChannelCount, SampleRate};
+use crate::math::nz;
+use crate::{Sample, Source};
+
+/// Head radius used by [`HrirSet::spherical_head`], in meters.
+const HEAD_RADIUS: f32 = 0.0875;
+/// Speed of sound in air, in meters per second.
+const SPEED_OF_SOUND: f32 = 343.0;
+/// Level difference between the ears for a source fully to one side, in dB.
+const MAX_ILD_DB: f32 = 10.0;
+
+/// Head-related impulse responses for a single direction.
+#[derive(Clone, Debug)]
+pub struct Hrir {
+    /// Horizontal angle in degrees. Zero is straight ahead, positive values are to the right.
+    pub azimuth: f32,
+    /// Vertical angle in degrees. Zero is at ear height, positive values are above.
+    pub elevation: f32,
+    /// Impulse response for the left ear.
+    pub left: Vec<f32>,
+    /// Impulse response for the right ear.
+    pub right: Vec<f32>,
+}
+
+/// A set of head-related impulse responses measured (or modelled) at one sample rate.
+///
+/// The response closest to the direction of the emitter is used, there is no interpolation
+/// between responses.
+#[derive(Clone, Debug)]
+pub struct HrirSet {
+    sample_rate: SampleRate,
+    hrirs: Vec<Hrir>,
+    len: usize,
+}
+
+impl HrirSet {
+    /// Builds a set from user supplied impulse responses, for example loaded from a measured
+    /// HRTF database.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hrirs` is empty or all impulse responses are empty.
+    pub fn new(sample_rate: SampleRate, hrirs: Vec<Hrir>) -> HrirSet {
+        let len = hrirs
+            .iter()
+            .map(|hrir| hrir.left.len().max(hrir.right.len()))
+            .max()
+            .expect("an HRIR set needs at least one impulse response");
+        assert!(len > 0, "impulse responses can not be empty");
+        HrirSet {
+            sample_rate,
+            hrirs,
+            len,
+        }
+    }
+
+    /// Builds a small set that models the head as a rigid sphere.
+    ///
+    /// It covers the interaural time difference (using Woodworth's formula) and a broadband
+    /// interaural level difference with a gentle low-pass for the far ear. It lacks the
+    /// spectral cues of a measured set, so front/back and elevation are not rendered.
+    pub fn spherical_head(sample_rate: SampleRate) -> HrirSet {
+        let fs = sample_rate.get() as f32;
+        let max_delay = HEAD_RADIUS / SPEED_OF_SOUND * (FRAC_PI_2 + 1.0) * fs;
+        let len = max_delay.ceil() as usize + 4;
+
+        let hrirs = (-36..=36)
+            .map(|step| {
+                let azimuth = step as f32 * 5.0;
+                // The angle between the emitter and the median plane, positive to the right.
+                let lateral = azimuth.to_radians().sin().asin();
+                let itd = HEAD_RADIUS / SPEED_OF_SOUND * (lateral.abs() + lateral.abs().sin());
+                let far_gain = crate::math::db_to_linear(-MAX_ILD_DB * lateral.abs().sin());
+
+                let near = impulse(len, 0.0, 1.0, false);
+                // Straight ahead both ears are equally near.
+                let far = impulse(len, itd * fs, far_gain, lateral != 0.0);
+                let (left, right) = if lateral >= 0.0 {
+                    (far, near)
+                } else {
+                    (near, far)
+                };
+                Hrir {
+                    azimuth,
+                    elevation: 0.0,
+                    left,
+                    right,
+                }
+            })
+            .collect();
+
+        HrirSet::new(sample_rate, hrirs)
+    }
+
+    /// The sample rate the impulse responses are meant for.
+    pub fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    fn nearest(&self, azimuth: f32, elevation: f32) -> usize {
+        let target = unit_vector(azimuth, elevation);
+        self.hrirs
+            .iter()
+            .enumerate()
+            .map(|(idx, hrir)| {
+                let v = unit_vector(hrir.azimuth, hrir.elevation);
+                let dot = v[0] * target[0] + v[1] * target[1] + v[2] * target[2];
+                (idx, dot)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(idx, _)| idx)
+            .expect("checked to be non-empty in new")
+    }
+}
+
+/// An impulse of `gain` delayed by `delay` samples, using linear interpolation for the
+/// fractional part. With `smooth` a three tap low-pass is applied.
+fn impulse(len: usize, delay: f32, gain: f32, smooth: bool) -> Vec<f32> {
+    let mut ir = vec![0.0; len];
+    let whole = delay.floor() as usize;
+    let frac = delay - delay.floor();
+    let kernel: &[f32] = if smooth { &[0.25, 0.5, 0.25] } else { &[1.0] };
+    for (offset, k) in kernel.iter().enumerate() {
+        if let Some(s) = ir.get_mut(whole + offset) {
+            *s += gain * k * (1.0 - frac);
+        }
+        if let Some(s) = ir.get_mut(whole + offset + 1) {
+            *s += gain * k * frac;
+        }
+    }
+    ir
+}
+
+fn unit_vector(azimuth: f32, elevation: f32) -> [f32; 3] {
+    let (azimuth, elevation) = (azimuth.to_radians(), elevation.to_radians());
+    [
+        azimuth.sin() * elevation.cos(),
+        elevation.sin(),
+        azimuth.cos() * elevation.cos(),
+    ]
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// Azimuth and elevation in degrees of the emitter as seen by the listener. The listener
+/// faces along `up × (right_ear - left_ear)` with `up` being the positive Y axis.
+fn direction(emitter: [f32; 3], left_ear: [f32; 3], right_ear: [f32; 3]) -> (f32, f32) {
+    let center = [
+        (left_ear[0] + right_ear[0]) / 2.0,
+        (left_ear[1] + right_ear[1]) / 2.0,
+        (left_ear[2] + right_ear[2]) / 2.0,
+    ];
+    let right = sub(right_ear, left_ear);
+    let up = [0.0, 1.0, 0.0];
+    let forward = cross(up, right);
+    let to_emitter = sub(emitter, center);
+
+    let x = dot(to_emitter, right) / dot(right, right).sqrt();
+    let y = to_emitter[1];
+    let z = dot(to_emitter, forward) / dot(forward, forward).sqrt().max(f32::EPSILON);
+    let horizontal = (x * x + z * z).sqrt();
+    if horizontal == 0.0 && y == 0.0 {
+        return (0.0, 0.0);
+    }
+    (x.atan2(z).to_degrees(), y.atan2(horizontal).to_degrees())
+}
+
+/// Renders a source binaurally by convolving it with head-related impulse responses.
+///
+/// The input is converted to mono at the sample rate of the [`HrirSet`] and played in
+/// stereo. Compared to [`Spatial`](super::Spatial) this also reproduces the difference in
+/// arrival time between the ears.
+#[derive(Clone)]
+pub struct Hrtf<I>
+where
+    I: Source,
+{
+    input: UniformSourceIterator<I>,
+    hrirs: Arc<HrirSet>,
+    current: usize,
+    // Most recent input samples, `history[pos]` being the newest.
+    history: Vec<Sample>,
+    pos: usize,
+    next_right: Option<Sample>,
+}
+
+impl<I> Hrtf<I>
+where
+    I: Source,
+{
+    /// Builds a new `Hrtf` using the impulse responses in `hrirs`.
+    pub fn new(
+        input: I,
+        hrirs: Arc<HrirSet>,
+        emitter_position: [f32; 3],
+        left_ear: [f32; 3],
+        right_ear: [f32; 3],
+    ) -> Hrtf<I> {
+        let input = UniformSourceIterator::new(input, nz!(1), hrirs.sample_rate());
+        let mut ret = Hrtf {
+            input,
+            history: vec![0.0; hrirs.len],
+            hrirs,
+            current: 0,
+            pos: 0,
+            next_right: None,
+        };
+        ret.set_positions(emitter_position, left_ear, right_ear);
+        ret
+    }
+
+    /// Sets the position of the emitter and ears in the 3D world.
+    pub fn set_positions(
+        &mut self,
+        emitter_pos: [f32; 3],
+        left_ear: [f32; 3],
+        right_ear: [f32; 3],
+    ) {
+        debug_assert!(left_ear != right_ear);
+        let (azimuth, elevation) = direction(emitter_pos, left_ear, right_ear);
+        self.current = self.hrirs.nearest(azimuth, elevation);
+    }
+
+    fn apply_ir(&self, ir: &[f32]) -> Sample {
+        let len = self.history.len();
+        ir.iter()
+            .enumerate()
+            .map(|(age, k)| k * self.history[(self.pos + len - age) % len])
+            .sum()
+    }
+}
+
+impl<I> Iterator for Hrtf<I>
+where
+    I: Source,
+{
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        if let Some(right) = self.next_right.take() {
+            return Some(right);
+        }
+
+        let sample = self.input.next()?;
+        self.pos = (self.pos + 1) % self.history.len();
+        self.history[self.pos] = sample;
+
+        let hrir = &self.hrirs.hrirs[self.current];
+        self.next_right = Some(self.apply_ir(&hrir.right));
+        Some(self.apply_ir(&hrir.left))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.input.size_hint();
+        (lower * 2, upper.map(|upper| upper * 2))
+    }
+}
+
+impl<I> Source for Hrtf<I>
+where
+    I: Source,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        nz!(2)
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.hrirs.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.history.fill(0.0);
+        self.next_right = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{direction, HrirSet, Hrtf};
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::Source;
+
+    const LEFT_EAR: [f32; 3] = [-0.1, 0.0, 0.0];
+    const RIGHT_EAR: [f32; 3] = [0.1, 0.0, 0.0];
+
+    fn render(emitter: [f32; 3]) -> (Vec<f32>, Vec<f32>) {
+        let mut click = vec![0.0; 128];
+        click[0] = 1.0;
+        let source = SamplesBuffer::new(nz!(1), nz!(48000), click);
+        let hrirs = Arc::new(HrirSet::spherical_head(nz!(48000)));
+        let output: Vec<f32> = Hrtf::new(source, hrirs, emitter, LEFT_EAR, RIGHT_EAR).collect();
+        let left = output.iter().step_by(2).copied().collect();
+        let right = output.iter().skip(1).step_by(2).copied().collect();
+        (left, right)
+    }
+
+    fn onset(channel: &[f32]) -> usize {
+        channel.iter().position(|s| s.abs() > 0.01).unwrap()
+    }
+
+    fn peak(channel: &[f32]) -> f32 {
+        channel.iter().fold(0.0, |max, s| s.abs().max(max))
+    }
+
+    #[test]
+    fn emitter_left_arrives_earlier_and_louder_left() {
+        let (left, right) = render([-5.0, 0.0, 0.0]);
+        assert!(onset(&left) + 20 < onset(&right));
+        assert!(peak(&left) > peak(&right) * 2.0);
+    }
+
+    #[test]
+    fn emitter_ahead_is_symmetric() {
+        let (left, right) = render([0.0, 0.0, -5.0]);
+        assert_eq!(left, right);
+    }
+
+    #[test]
+    fn direction_relative_to_listener() {
+        let (azimuth, elevation) = direction([0.0, 0.0, -1.0], LEFT_EAR, RIGHT_EAR);
+        assert!(azimuth.abs() < 1e-3 && elevation.abs() < 1e-3);
+        let (azimuth, _) = direction([1.0, 0.0, 0.0], LEFT_EAR, RIGHT_EAR);
+        assert!((azimuth - 90.0).abs() < 1e-3);
+        let (_, elevation) = direction([0.0, 1.0, 0.0], LEFT_EAR, RIGHT_EAR);
+        assert!((elevation - 90.0).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds a `InvertPolarity` object.
+pub fn invert_polarity<I>(input: I, channels: &[usize]) -> InvertPolarity<I>
+where
+    I: Source,
+{
+    InvertPolarity {
+        input,
+        channels: channels.to_vec(),
+        channel: 0,
+    }
+}
+
+/// Negates the samples of some channels, flipping their polarity.
+///
+/// If no channels are given all of them are inverted. Channels that do not exist in the input
+/// are ignored.
+#[derive(Clone, Debug)]
+pub struct InvertPolarity<I> {
+    input: I,
+    channels: Vec<usize>,
+    // The channel of the next sample.
+    channel: usize,
+}
+
+impl<I> InvertPolarity<I> {
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> Iterator for InvertPolarity<I>
+where
+    I: Source,
+{
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        let sample = self.input.next()?;
+        let inverted = self.channels.is_empty() || self.channels.contains(&self.channel);
+        self.channel += 1;
+        if self.channel >= self.input.channels().get() as usize {
+            self.channel = 0;
+        }
+        Some(if inverted { -sample } else { sample })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for InvertPolarity<I> where I: Source + ExactSizeIterator {}
+
+impl<I> Source for InvertPolarity<I>
+where
+    I: Source,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.channel = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::source::{from_iter, Source};
+
+    #[test]
+    fn inverts_selected_channel_only() {
+        let source = SamplesBuffer::new(nz!(2), nz!(48000), vec![1.0, 2.0, -3.0, 4.0]);
+        let output: Vec<f32> = source.invert_polarity(&[0]).collect();
+        assert_eq!(output, [-1.0, 2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn inverts_all_across_buffers() {
+        let buffers = (0..3).map(|_| SamplesBuffer::new(nz!(3), nz!(48000), vec![1.0, 2.0, 3.0]));
+        let output: Vec<f32> = from_iter(buffers).invert_polarity(&[]).collect();
+        assert_eq!(output, [-1.0, -2.0, -3.0].repeat(3));
+
+        let buffers = (0..3).map(|_| SamplesBuffer::new(nz!(3), nz!(48000), vec![1.0, 2.0, 3.0]));
+        let output: Vec<f32> = from_iter(buffers).invert_polarity(&[2]).collect();
+        assert_eq!(output, [1.0, 2.0, -3.0].repeat(3));
+    }
+}
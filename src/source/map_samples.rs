@@ -0,0 +1,113 @@
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds a `MapSamples` object.
+pub fn map_samples<I, F>(input: I, f: F) -> MapSamples<I, F>
+where
+    I: Source,
+    F: FnMut(Sample) -> Sample,
+{
+    MapSamples { input, f }
+}
+
+/// Applies a closure to each sample of a source.
+#[derive(Clone, Debug)]
+pub struct MapSamples<I, F> {
+    input: I,
+    f: F,
+}
+
+impl<I, F> MapSamples<I, F> {
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I, F> Iterator for MapSamples<I, F>
+where
+    I: Source,
+    F: FnMut(Sample) -> Sample,
+{
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        self.input.next().map(&mut self.f)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I, F> ExactSizeIterator for MapSamples<I, F>
+where
+    I: Source + ExactSizeIterator,
+    F: FnMut(Sample) -> Sample,
+{
+}
+
+impl<I, F> Source for MapSamples<I, F>
+where
+    I: Source,
+    F: FnMut(Sample) -> Sample,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::Source;
+
+    #[test]
+    fn applies_closure_and_keeps_format() {
+        let source = SamplesBuffer::new(nz!(2), nz!(44100), vec![1.0, -0.5, 0.25, 2.0]);
+        let mapped = source.map_samples(|sample| sample * 0.5);
+        assert_eq!(mapped.channels(), nz!(2));
+        assert_eq!(mapped.sample_rate(), nz!(44100));
+        assert_eq!(mapped.collect::<Vec<_>>(), [0.5, -0.25, 0.125, 1.0]);
+    }
+}
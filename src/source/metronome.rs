@@ -0,0 +1,185 @@
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::math::nz;
+use crate::Source;
+
+/// Length of a click.
+const CLICK_DURATION: Duration = Duration::from_millis(30);
+/// Time for a click to decay by about 63%.
+const CLICK_DECAY: f32 = 0.006;
+
+/// An infinite source that produces a click track.
+///
+/// The first beat of each bar is a higher and louder click. The tempo counts quarter notes, so
+/// with a signature of 6/8 the eighth notes are clicked twice as fast as the tempo. The
+/// signature is 4/4 unless set with [`Metronome::with_signature`].
+///
+/// Always has one channel.
+///
+/// # Example
+///
+/// ```
+/// use rodio::nz;
+/// use rodio::source::Metronome;
+///
+/// // A waltz at 90 quarter notes per minute.
+/// let metronome = Metronome::new(90.0, nz!(48000)).with_signature(3, 4);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Metronome {
+    sample_rate: SampleRate,
+    // Samples between two clicks, fractional so the tempo does not drift.
+    beat_len: f64,
+    beats_per_bar: u32,
+    bpm: f32,
+    note_value: u32,
+    position: u64,
+}
+
+impl Metronome {
+    /// Builds a metronome clicking `bpm` quarter notes per minute.
+    #[inline]
+    pub fn new(bpm: f32, sample_rate: SampleRate) -> Metronome {
+        let mut metronome = Metronome {
+            sample_rate,
+            beat_len: 0.0,
+            beats_per_bar: 4,
+            bpm,
+            note_value: 4,
+            position: 0,
+        };
+        metronome.update_beat_len();
+        metronome
+    }
+
+    /// Sets the time signature, for example `(3, 4)` for three quarter notes per bar.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `beats` or `note_value` is zero.
+    #[inline]
+    pub fn with_signature(mut self, beats: u32, note_value: u32) -> Metronome {
+        assert!(beats > 0, "a bar needs at least one beat");
+        assert!(note_value > 0, "the note value can not be zero");
+        self.beats_per_bar = beats;
+        self.note_value = note_value;
+        self.update_beat_len();
+        self
+    }
+
+    fn update_beat_len(&mut self) {
+        let quarter_note = 60.0 / self.bpm as f64 * self.sample_rate.get() as f64;
+        self.beat_len = quarter_note * 4.0 / self.note_value as f64;
+    }
+}
+
+impl Iterator for Metronome {
+    type Item = f32;
+
+    #[inline]
+    fn next(&mut self) -> Option<f32> {
+        let beat = (self.position as f64 / self.beat_len) as u64;
+        let beat_start = (beat as f64 * self.beat_len).ceil() as u64;
+        // Near a beat boundary rounding can put the position in the previous beat.
+        let (beat, beat_start) = if beat_start > self.position {
+            let beat = beat - 1;
+            (beat, (beat as f64 * self.beat_len).ceil() as u64)
+        } else {
+            (beat, beat_start)
+        };
+        let t = (self.position - beat_start) as f32 / self.sample_rate.get() as f32;
+        self.position += 1;
+
+        if t >= CLICK_DURATION.as_secs_f32() {
+            return Some(0.0);
+        }
+        let (freq, level) = if beat % self.beats_per_bar as u64 == 0 {
+            (1500.0, 1.0)
+        } else {
+            (1000.0, 0.6)
+        };
+        Some((TAU * freq * t).sin() * level * (-t / CLICK_DECAY).exp())
+    }
+}
+
+impl Source for Metronome {
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        nz!(1)
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.position = (pos.as_secs_f64() * self.sample_rate.get() as f64).round() as u64;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Metronome;
+    use crate::math::nz;
+
+    /// Returns the sample index and peak of every click.
+    fn clicks(metronome: Metronome, samples: usize) -> Vec<(usize, f32)> {
+        let output: Vec<f32> = metronome.take(samples).collect();
+        let mut clicks: Vec<(usize, f32)> = Vec::new();
+        for (i, sample) in output.iter().enumerate() {
+            let start = clicks.last().is_none_or(|(last, _)| i - last > 2000);
+            if *sample != 0.0 && start {
+                clicks.push((i - 1, 0.0));
+            }
+            if let Some((_, peak)) = clicks.last_mut() {
+                *peak = peak.max(sample.abs());
+            }
+        }
+        clicks
+    }
+
+    #[test]
+    fn clicks_every_half_second_at_120_bpm() {
+        let clicks = clicks(Metronome::new(120.0, nz!(48000)), 48000 * 4);
+        let starts: Vec<usize> = clicks.iter().map(|(start, _)| *start).collect();
+        assert_eq!(starts, (0..8).map(|beat| beat * 24000).collect::<Vec<_>>());
+
+        // The downbeat of each 4/4 bar is accented.
+        let accented: Vec<bool> = clicks.iter().map(|(_, peak)| *peak > 0.7).collect();
+        assert_eq!(
+            accented,
+            [true, false, false, false, true, false, false, false]
+        );
+    }
+
+    #[test]
+    fn signature_sets_bar_and_beat_length() {
+        let metronome = Metronome::new(120.0, nz!(48000)).with_signature(3, 8);
+        let clicks = clicks(metronome, 48000 * 2);
+        let starts: Vec<usize> = clicks.iter().map(|(start, _)| *start).collect();
+        assert_eq!(starts, (0..8).map(|beat| beat * 12000).collect::<Vec<_>>());
+        let accented: Vec<usize> = clicks
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, peak))| *peak > 0.7)
+            .map(|(beat, _)| beat)
+            .collect();
+        assert_eq!(accented, [0, 3, 6]);
+    }
+}
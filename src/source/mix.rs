@@ -14,7 +14,20 @@ where
 {
     let channels = input1.channels();
     let rate = input1.sample_rate();
+    mix_to(input1, input2, channels, rate)
+}
 
+/// Builds a `Mix` that converts both inputs to the given format.
+pub(crate) fn mix_to<I1, I2>(
+    input1: I1,
+    input2: I2,
+    channels: ChannelCount,
+    rate: SampleRate,
+) -> Mix<I1, I2>
+where
+    I1: Source,
+    I2: Source,
+{
     Mix {
         input1: UniformSourceIterator::new(input1, channels, rate),
         input2: UniformSourceIterator::new(input2, channels, rate),
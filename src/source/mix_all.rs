@@ -0,0 +1,184 @@
+use std::time::Duration;
+
+use crate::common::{ChannelCount, SampleRate};
+use crate::math::nz;
+use crate::source::{SeekError, UniformSourceIterator};
+use crate::{Sample, Source};
+
+/// Mixes all `sources` into one, each multiplied by its gain.
+///
+/// The sources are converted to the largest channel count and sample rate among them. The
+/// mix plays until the longest source ended.
+///
+/// # Example
+///
+/// ```
+/// use rodio::source::{mix_all, SineWave, Source};
+/// use std::time::Duration;
+///
+/// let layers = mix_all(vec![
+///     (SineWave::new(110.0).boxed(), 0.5),
+///     (SineWave::new(220.0).boxed(), 0.25),
+/// ]);
+/// let ambience = layers.take_duration(Duration::from_secs(10));
+/// ```
+pub fn mix_all(sources: Vec<(Box<dyn Source + Send>, f32)>) -> MixAll {
+    let channels = sources
+        .iter()
+        .map(|(source, _)| source.channels())
+        .max()
+        .unwrap_or(nz!(1));
+    let sample_rate = sources
+        .iter()
+        .map(|(source, _)| source.sample_rate())
+        .max()
+        .unwrap_or(nz!(48000));
+    let total_duration = sources
+        .iter()
+        .map(|(source, _)| source.total_duration())
+        .try_fold(Duration::ZERO, |longest, duration| {
+            duration.map(|duration| longest.max(duration))
+        });
+    let inputs = sources
+        .into_iter()
+        .map(|(source, gain)| {
+            (
+                UniformSourceIterator::new(source, channels, sample_rate),
+                gain,
+            )
+        })
+        .collect();
+    MixAll {
+        inputs,
+        channels,
+        sample_rate,
+        total_duration,
+    }
+}
+
+/// Sums a list of sources with individual gains, see [`mix_all`].
+pub struct MixAll {
+    // Sources that are still playing, with their gains.
+    inputs: Vec<(UniformSourceIterator<Box<dyn Source + Send>>, f32)>,
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+    total_duration: Option<Duration>,
+}
+
+impl Iterator for MixAll {
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        let mut sum = None;
+        // Removing a source that ended keeps the others aligned, they all read whole frames.
+        self.inputs.retain_mut(|(input, gain)| match input.next() {
+            Some(sample) => {
+                *sum.get_or_insert(0.0) += sample * *gain;
+                true
+            }
+            None => false,
+        });
+        sum
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let min = self
+            .inputs
+            .iter()
+            .map(|(input, _)| input.size_hint().0)
+            .max()
+            .unwrap_or(0);
+        let max = self
+            .inputs
+            .iter()
+            .try_fold(0, |max, (input, _)| Some(max.max(input.size_hint().1?)));
+        (min, max)
+    }
+}
+
+impl Source for MixAll {
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.channels
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.total_duration
+    }
+
+    /// Seeks every source, fails if one of them can not seek.
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        for (input, _) in &mut self.inputs {
+            input.try_seek(pos)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+    use std::time::Duration;
+
+    use super::mix_all;
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::source::SineWave;
+    use crate::Source;
+
+    #[test]
+    fn keeps_relative_levels() {
+        let tone = |freq: f32| {
+            SineWave::new(freq)
+                .take_duration(Duration::from_secs(1))
+                .boxed()
+        };
+        let mixed = mix_all(vec![
+            (tone(1000.0), 1.0),
+            (tone(2000.0), 0.5),
+            (tone(3000.0), 0.25),
+        ]);
+        let samples: Vec<f32> = mixed.collect();
+        assert_eq!(samples.len(), 48000);
+
+        // The amplitude of each tone, measured by correlating with a sine of its frequency.
+        let level = |freq: f32| {
+            let (re, im) = samples
+                .iter()
+                .enumerate()
+                .fold((0.0, 0.0), |(re, im), (i, s)| {
+                    let phase = TAU * freq * i as f32 / 48000.0;
+                    (re + s * phase.cos(), im + s * phase.sin())
+                });
+            2.0 * f32::hypot(re, im) / samples.len() as f32
+        };
+        assert!((level(1000.0) - 1.0).abs() < 0.01, "{}", level(1000.0));
+        assert!((level(2000.0) - 0.5).abs() < 0.01, "{}", level(2000.0));
+        assert!((level(3000.0) - 0.25).abs() < 0.01, "{}", level(3000.0));
+    }
+
+    #[test]
+    fn converts_to_common_format() {
+        let mono = SamplesBuffer::new(nz!(1), nz!(1000), vec![1.0; 4]).boxed();
+        let stereo = SamplesBuffer::new(nz!(2), nz!(1000), vec![0.5, -0.5]).boxed();
+        let mixed = mix_all(vec![(mono, 0.5), (stereo, 1.0)]);
+        assert_eq!(mixed.channels(), nz!(2));
+        assert_eq!(mixed.total_duration(), Some(Duration::from_millis(4)));
+        let samples: Vec<f32> = mixed.collect();
+        assert_eq!(samples, [1.0, 0.0, 0.5, 0.5, 0.5, 0.5, 0.5, 0.5]);
+    }
+}
@@ -3,84 +3,150 @@
 use core::time::Duration;
 use std::sync::Arc;
 
+#[cfg(feature = "dither")]
+use crate::BitDepth;
 use crate::{
     buffer::SamplesBuffer,
     common::{assert_error_traits, ChannelCount, SampleRate},
-    math, BitDepth, Sample,
+    math, Sample,
 };
 
 use dasp_sample::FromSample;
 
+pub use self::adsr::{Adsr, AdsrHandle};
 pub use self::agc::{AutomaticGainControl, AutomaticGainControlSettings};
 pub use self::amplify::Amplify;
+pub use self::atomic_param::AtomicParam;
 pub use self::blt::BltFilter;
 pub use self::buffered::Buffered;
+pub use self::channel_delay::ChannelDelay;
 pub use self::channel_volume::ChannelVolume;
 pub use self::chirp::{chirp, Chirp};
+pub use self::clip_indicator::{ClipFlags, ClipIndicator};
+pub use self::clip_log::{ClipLog, ClipLogHandle};
+pub use self::convolve::Convolve;
+pub use self::correlation_meter::{CorrelationHandle, CorrelationMeter};
 pub use self::crossfade::Crossfade;
+pub use self::crossfeed::Crossfeed;
 pub use self::delay::Delay;
+pub use self::distance::DistanceAttenuation;
 pub use self::distortion::Distortion;
 pub use self::done::Done;
+pub use self::dtmf::Dtmf;
 pub use self::empty::Empty;
 pub use self::empty_callback::EmptyCallback;
+pub use self::equalizer::{EqBand, Equalizer, EqualizerHandle};
 pub use self::fadein::FadeIn;
 pub use self::fadeout::FadeOut;
 pub use self::from_factory::{from_factory, FromFactoryIter};
+pub use self::from_fn::{from_fn, FromFn};
 pub use self::from_iter::{from_iter, FromIter};
+pub use self::from_samples::{from_samples, FromSamples};
+pub use self::hrtf::{Hrir, HrirSet, Hrtf};
+pub use self::invert_polarity::InvertPolarity;
 pub use self::limit::{Limit, LimitSettings};
 pub use self::linear_ramp::LinearGainRamp;
+pub use self::loudness::measure_lufs;
+pub use self::map_samples::MapSamples;
+pub use self::metronome::Metronome;
 pub use self::mix::Mix;
+pub use self::mix_all::{mix_all, MixAll};
+pub use self::oscillator::Oscillator;
+pub use self::pad_end::PadEnd;
+pub use self::pan_envelope::PanEnvelope;
 pub use self::pausable::Pausable;
 pub use self::periodic::PeriodicAccess;
 pub use self::position::TrackPosition;
+pub use self::rate_adjustment::{RateAdjustment, MAX_RATE_ADJUSTMENT_PPM};
+pub use self::raw_pcm::{PcmFormat, RawPcmSource};
 pub use self::repeat::Repeat;
+pub use self::repeat_crossfade::RepeatCrossfade;
+pub use self::resample::{ResampleQuality, Resampler};
 pub use self::sawtooth::SawtoothWave;
+pub use self::select_channels::SelectChannels;
+pub use self::sequencer::Sequencer;
 pub use self::signal_generator::{Function, GeneratorFunction, SignalGenerator};
 pub use self::sine::SineWave;
 pub use self::skip::SkipDuration;
 pub use self::skippable::Skippable;
 pub use self::spatial::Spatial;
+pub use self::spectrum_tap::{SpectrumHandle, SpectrumTap};
 pub use self::speed::Speed;
 pub use self::square::SquareWave;
+pub use self::stereo_width::StereoWidth;
 pub use self::stoppable::Stoppable;
+pub use self::switchable::{switchable, SwitchHandle, Switchable};
 pub use self::take::TakeDuration;
+pub use self::to_mono::ToMono;
 pub use self::triangle::TriangleWave;
 pub use self::uniform::UniformSourceIterator;
 pub use self::zero::Zero;
 
+mod adsr;
 mod agc;
 mod amplify;
+mod atomic_param;
 mod blt;
 mod buffered;
+mod channel_delay;
 mod channel_volume;
 mod chirp;
+mod clip_indicator;
+mod clip_log;
+mod convolve;
+mod correlation_meter;
 mod crossfade;
+mod crossfeed;
 mod delay;
+mod distance;
 mod distortion;
 mod done;
+mod dtmf;
 mod empty;
 mod empty_callback;
+mod equalizer;
 mod fadein;
 mod fadeout;
 mod from_factory;
+mod from_fn;
 mod from_iter;
+mod from_samples;
+mod hrtf;
+mod invert_polarity;
 mod limit;
 mod linear_ramp;
+mod loudness;
+mod map_samples;
+mod metronome;
 mod mix;
+mod mix_all;
+mod oscillator;
+mod pad_end;
+mod pan_envelope;
 mod pausable;
 mod periodic;
 mod position;
+mod rate_adjustment;
+mod raw_pcm;
 mod repeat;
+mod repeat_crossfade;
+mod resample;
 mod sawtooth;
+mod select_channels;
+mod sequencer;
 mod signal_generator;
 mod sine;
 mod skip;
 mod skippable;
 mod spatial;
+mod spectrum_tap;
 mod speed;
 mod square;
+mod stereo_width;
 mod stoppable;
+mod switchable;
 mod take;
+mod to_mono;
 mod triangle;
 mod uniform;
 mod zero;
@@ -188,7 +254,33 @@ pub trait Source: Iterator<Item = Sample> {
     /// `None` indicates at the same time "infinite" or "unknown".
     fn total_duration(&self) -> Option<Duration>;
 
+    /// Returns a snapshot of the format of this source, useful when debugging adapters.
+    ///
+    /// The values are taken at the moment of the call and may change as the source advances,
+    /// see the section on spans above.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{SineWave, Source};
+    ///
+    /// let info = SineWave::new(440.0).describe();
+    /// println!("{info:?}");
+    /// ```
+    #[inline]
+    fn describe(&self) -> SourceInfo {
+        SourceInfo {
+            channels: self.channels(),
+            sample_rate: self.sample_rate(),
+            total_duration: self.total_duration(),
+            current_span_len: self.current_span_len(),
+        }
+    }
+
     /// Stores the source in a buffer in addition to returning it. This iterator can be cloned.
+    ///
+    /// Only the data a clone still has to play is kept. To seek even if this source can not,
+    /// keep everything with [`Buffered::seekable`].
     #[inline]
     fn buffered(self) -> Buffered<Self>
     where
@@ -197,6 +289,36 @@ pub trait Source: Iterator<Item = Sample> {
         buffered::buffered(self)
     }
 
+    /// Puts the source in a box, hiding its type.
+    ///
+    /// Chaining adapters builds long nested types, boxing makes it easy to store such a chain
+    /// in a struct or to pick between chains at runtime. The box is itself a [`Source`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use rodio::source::{SineWave, Source};
+    ///
+    /// struct Voice {
+    ///     source: Box<dyn Source + Send>,
+    /// }
+    ///
+    /// let voice = Voice {
+    ///     source: SineWave::new(440.0)
+    ///         .take_duration(Duration::from_secs(1))
+    ///         .fade_in(Duration::from_millis(10))
+    ///         .boxed(),
+    /// };
+    /// ```
+    #[inline]
+    fn boxed(self) -> Box<dyn Source + Send>
+    where
+        Self: Sized + Send + 'static,
+    {
+        Box::new(self)
+    }
+
     /// Applies dithering to the source at the specified bit depth.
     ///
     /// Dithering eliminates quantization artifacts during digital audio playback
@@ -244,6 +366,31 @@ pub trait Source: Iterator<Item = Sample> {
         repeat::repeat(self)
     }
 
+    /// Repeats this source forever, crossfading the end of each iteration into the beginning
+    /// of the next so the loop has no audible seam.
+    ///
+    /// Each iteration after the first starts `fade` in, as its beginning was played during the
+    /// crossfade. The fade should be shorter than half the source. Like
+    /// [`Source::repeat_infinite`] this stores the data in a buffer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{SineWave, Source};
+    /// use std::time::Duration;
+    ///
+    /// let ambience = SineWave::new(220.0)
+    ///     .take_duration(Duration::from_secs(4))
+    ///     .repeat_crossfade(Duration::from_millis(500));
+    /// ```
+    #[inline]
+    fn repeat_crossfade(self, fade: Duration) -> RepeatCrossfade<Self>
+    where
+        Self: Sized,
+    {
+        repeat_crossfade::repeat_crossfade(self, fade)
+    }
+
     /// Takes a certain duration of this source and then stops.
     #[inline]
     fn take_duration(self, duration: Duration) -> TakeDuration<Self>
@@ -253,11 +400,27 @@ pub trait Source: Iterator<Item = Sample> {
         take::take_duration(self, duration)
     }
 
+    /// Takes a certain duration of this source and then stops, fading out over the last
+    /// `fade` to avoid a click at the hard stop.
+    ///
+    /// If `fade` is longer than `total` the whole source fades out.
+    #[inline]
+    fn take_duration_faded(self, total: Duration, fade: Duration) -> TakeDuration<Self>
+    where
+        Self: Sized,
+    {
+        let mut source = take::take_duration(self, total);
+        source.set_filter_fadeout_end(fade.min(total));
+        source
+    }
+
     /// Delays the sound by a certain duration.
     ///
     /// The rate and channels of the silence will use the same format as the first span of the
-    /// source.
+    /// source. The total duration includes the delay. Use [`Source::pad_end`] to add silence
+    /// after the sound instead.
     #[inline]
+    #[doc(alias = "delay_start")]
     fn delay(self, duration: Duration) -> Delay<Self>
     where
         Self: Sized,
@@ -265,6 +428,47 @@ pub trait Source: Iterator<Item = Sample> {
         delay::delay(self, duration)
     }
 
+    /// Delays each channel by its own amount, for example to time-align microphones at
+    /// different distances.
+    ///
+    /// `delays` holds one delay per channel. Delays that are not a whole number of samples
+    /// are interpolated. The total duration is unchanged, the end of the delayed channels is
+    /// cut off.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of delays differs from the channel count.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{SineWave, Source};
+    /// use rodio::nz;
+    /// use std::time::Duration;
+    ///
+    /// let stereo = SineWave::new(440.0).reformat(nz!(2), nz!(48000));
+    /// let aligned = stereo.channel_delay(vec![Duration::ZERO, Duration::from_micros(125)]);
+    /// ```
+    #[inline]
+    fn channel_delay(self, delays: Vec<Duration>) -> ChannelDelay<Self>
+    where
+        Self: Sized,
+    {
+        channel_delay::channel_delay(self, delays)
+    }
+
+    /// Plays silence for a certain duration after the sound ends.
+    ///
+    /// The rate and channels of the silence will use the same format as the last span of the
+    /// source. The total duration includes the silence.
+    #[inline]
+    fn pad_end(self, duration: Duration) -> PadEnd<Self>
+    where
+        Self: Sized,
+    {
+        pad_end::pad_end(self, duration)
+    }
+
     /// Immediately skips a certain duration of this source.
     ///
     /// If the specified duration is longer than the source itself, `skip_duration` will skip to the end of the source.
@@ -286,6 +490,11 @@ pub trait Source: Iterator<Item = Sample> {
     }
 
     /// Amplifies the sound logarithmically by the given value.
+    ///
+    /// The value is in decibels and converted to a linear factor of `10^(value / 20)`, so
+    /// `0.0` leaves the sound unchanged and negative values attenuate it. For example `-6.0`
+    /// roughly halves the amplitude.
+    #[doc(alias = "amplify_db")]
     #[inline]
     fn amplify_decibel(self, value: f32) -> Amplify<Self>
     where
@@ -415,7 +624,8 @@ pub trait Source: Iterator<Item = Sample> {
 
     /// Mixes this sound fading out with another sound fading in for the given duration.
     ///
-    /// Only the crossfaded portion (beginning of self, beginning of other) is returned.
+    /// Only the crossfaded portion (beginning of self, beginning of other) is returned. Sounds
+    /// with different formats are converted to the higher sample rate and channel count.
     #[inline]
     fn take_crossfade_with<S: Source>(self, other: S, duration: Duration) -> Crossfade<Self, S>
     where
@@ -425,6 +635,38 @@ pub trait Source: Iterator<Item = Sample> {
         crossfade::crossfade(self, other, duration)
     }
 
+    /// Applies an attack, decay, sustain and release envelope, for example to shape the notes
+    /// of a synthesizer.
+    ///
+    /// The sound stays at `sustain_level` until [`Adsr::release`] or [`AdsrHandle::release`]
+    /// is called, and ends once the release is over.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use rodio::source::{SineWave, Source};
+    ///
+    /// let ms = Duration::from_millis;
+    /// let note = SineWave::new(440.0).adsr(ms(10), ms(100), 0.7, ms(300));
+    /// let key = note.handle();
+    /// // When the key is lifted:
+    /// key.release();
+    /// ```
+    #[inline]
+    fn adsr(
+        self,
+        attack: Duration,
+        decay: Duration,
+        sustain_level: f32,
+        release: Duration,
+    ) -> Adsr<Self>
+    where
+        Self: Sized,
+    {
+        adsr::adsr(self, attack, decay, sustain_level, release)
+    }
+
     /// Fades in the sound.
     #[inline]
     fn fade_in(self, duration: Duration) -> FadeIn<Self>
@@ -516,6 +758,29 @@ pub trait Source: Iterator<Item = Sample> {
         linear_ramp::linear_gain_ramp(self, duration, start_value, end_value, clamp_end)
     }
 
+    /// Applies the closure `f` to every sample, for effects that do not need a `Source` of
+    /// their own.
+    ///
+    /// The channel count, sample rate and duration are those of the source. The closure runs
+    /// on the audio thread when playing, it should not block.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{Source, SineWave};
+    ///
+    /// // Hard clip at half the full scale.
+    /// let clipped = SineWave::new(440.0).map_samples(|sample| sample.clamp(-0.5, 0.5));
+    /// ```
+    #[inline]
+    fn map_samples<F>(self, f: F) -> MapSamples<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(Sample) -> Sample,
+    {
+        map_samples::map_samples(self, f)
+    }
+
     /// Calls the `access` closure on `Self` the first time the source is iterated and every
     /// time `period` elapses.
     ///
@@ -557,8 +822,63 @@ pub trait Source: Iterator<Item = Sample> {
         speed::speed(self, ratio)
     }
 
+    /// Converts the source to the given channel count and sample rate.
+    ///
+    /// Useful to pin the output format, for example to match a recording target. If the source
+    /// already has the requested format the samples pass through unchanged.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{SineWave, Source};
+    /// use rodio::nz;
+    ///
+    /// let source = SineWave::new(440.0).reformat(nz!(2), nz!(44_100));
+    /// assert_eq!(source.channels(), nz!(2));
+    /// assert_eq!(source.sample_rate(), nz!(44_100));
+    /// ```
+    #[inline]
+    fn reformat(
+        self,
+        channels: ChannelCount,
+        sample_rate: SampleRate,
+    ) -> UniformSourceIterator<Self>
+    where
+        Self: Sized,
+    {
+        UniformSourceIterator::new(self, channels, sample_rate)
+    }
+
+    /// Keeps only the channels at `indices`, in that order, dropping the others.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `indices` is empty or contains an index that is not below the channel count.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::buffer::SamplesBuffer;
+    /// use rodio::nz;
+    /// use rodio::Source;
+    ///
+    /// let surround = SamplesBuffer::new(nz!(6), nz!(48000), vec![0.0; 6 * 48000]);
+    /// let stereo = surround.select_channels(&[0, 1]);
+    /// assert_eq!(stereo.channels(), nz!(2));
+    /// ```
+    #[inline]
+    fn select_channels(self, indices: &[usize]) -> SelectChannels<Self>
+    where
+        Self: Sized,
+    {
+        select_channels::select_channels(self, indices)
+    }
+
     /// Consumes the source and returns a SamplesBuffer
     ///
+    /// This fully decodes the source into memory. Cloning the returned buffer is cheap as the
+    /// samples are shared, which makes it ideal for sounds that are re-triggered often.
+    ///
     /// Use `take_duration` on infinite sources (like the microphone source) before
     /// calling `record` to prevent this from hanging forever.
     ///
@@ -584,6 +904,29 @@ pub trait Source: Iterator<Item = Sample> {
         SamplesBuffer::record_source(self)
     }
 
+    /// Consumes the source and returns a `SamplesBuffer` without the silence at its start and
+    /// end.
+    ///
+    /// Frames are silent when none of their samples exceed `threshold_db` dBFS. See
+    /// [`SamplesBuffer::trim_silence`], the source is recorded as by [`Source::record`].
+    ///
+    /// # Example
+    /// ```
+    /// # use rodio::source::SineWave;
+    /// # use rodio::Source;
+    /// # use std::time::Duration;
+    /// let clip = SineWave::new(440.0)
+    ///     .take_duration(Duration::from_millis(100))
+    ///     .delay(Duration::from_millis(50));
+    /// let clip = clip.trim_silence(-60.0);
+    /// ```
+    fn trim_silence(self, threshold_db: f32) -> SamplesBuffer
+    where
+        Self: Sized,
+    {
+        self.record().trim_silence(threshold_db)
+    }
+
     /// Adds a basic reverb effect.
     ///
     /// This function requires the source to implement `Clone`. This can be done by using
@@ -605,6 +948,41 @@ pub trait Source: Iterator<Item = Sample> {
         self.mix(echo)
     }
 
+    /// Convolves the source with an impulse response, for example a recorded room for a
+    /// convolution reverb.
+    ///
+    /// `ir` is applied to every channel and should have the sample rate of the source. The
+    /// output keeps playing for the length of `ir` after the source ends, see
+    /// [`Convolve::tail_duration`]. Mix in the source itself to control the dry/wet balance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ir` is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{SineWave, Source};
+    /// use std::time::Duration;
+    ///
+    /// // A crude room: exponentially decaying noise.
+    /// let ir: Vec<f32> = (0..4800u32)
+    ///     .map(|i| ((i * 7919) % 199) as f32 / 99.0 - 1.0)
+    ///     .enumerate()
+    ///     .map(|(i, noise)| noise * (-(i as f32) / 1000.0).exp() * 0.1)
+    ///     .collect();
+    /// let source = SineWave::new(440.0)
+    ///     .take_duration(Duration::from_millis(200))
+    ///     .convolve(ir);
+    /// ```
+    #[inline]
+    fn convolve(self, ir: Vec<f32>) -> Convolve<Self>
+    where
+        Self: Sized,
+    {
+        convolve::convolve(self, ir)
+    }
+
     /// Makes the sound pausable.
     // TODO: add example
     #[inline]
@@ -652,6 +1030,59 @@ pub trait Source: Iterator<Item = Sample> {
         position::track_position(self)
     }
 
+    /// Renders the source binaurally for an emitter at `emitter_position`.
+    ///
+    /// Uses [`HrirSet::spherical_head`] at the sample rate of the source. Use [`Hrtf::new`] to
+    /// supply a measured set of impulse responses instead. See [`Hrtf`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{SineWave, Source};
+    ///
+    /// // A source one meter to the left of a listener facing towards negative Z.
+    /// let source = SineWave::new(440.0).hrtf([-1.0, 0.0, 0.0], [-0.1, 0.0, 0.0], [0.1, 0.0, 0.0]);
+    /// assert_eq!(source.channels(), rodio::nz!(2));
+    /// ```
+    #[inline]
+    fn hrtf(self, emitter_position: [f32; 3], left_ear: [f32; 3], right_ear: [f32; 3]) -> Hrtf<Self>
+    where
+        Self: Sized,
+    {
+        let hrirs = std::sync::Arc::new(HrirSet::spherical_head(self.sample_rate()));
+        Hrtf::new(self, hrirs, emitter_position, left_ear, right_ear)
+    }
+
+    /// Attenuates the source with the distance between emitter and listener and filters out
+    /// high frequencies the further away it is, mimicking air absorption.
+    ///
+    /// Within `reference_distance` the source plays at full volume. `rolloff` controls how
+    /// fast the volume drops beyond that and `max_distance` is the distance after which the
+    /// source no longer gets quieter. Use [`DistanceAttenuation::set_positions`] to move the
+    /// emitter or listener, for example from within [`periodic_access`](Source::periodic_access).
+    /// See [`DistanceAttenuation`] for details.
+    #[inline]
+    fn distance_attenuation(
+        self,
+        emitter_position: [f32; 3],
+        listener_position: [f32; 3],
+        reference_distance: f32,
+        rolloff: f32,
+        max_distance: f32,
+    ) -> DistanceAttenuation<Self>
+    where
+        Self: Sized,
+    {
+        distance::distance_attenuation(
+            self,
+            emitter_position,
+            listener_position,
+            reference_distance,
+            rolloff,
+            max_distance,
+        )
+    }
+
     /// Applies a low-pass filter to the source.
     /// **Warning**: Probably buggy.
     #[inline]
@@ -693,6 +1124,250 @@ pub trait Source: Iterator<Item = Sample> {
         blt::high_pass_with_q(self, freq, q)
     }
 
+    /// Applies a low-pass filter whose cutoff frequency in Hz is read from `cutoff` while
+    /// playing, so it can be changed from another thread.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{AtomicParam, SineWave, Source};
+    ///
+    /// let cutoff = AtomicParam::new(5000.0);
+    /// let source = SineWave::new(440.0).low_pass_shared(cutoff.clone());
+    /// // Later, from any thread:
+    /// cutoff.set(800.0);
+    /// ```
+    #[inline]
+    fn low_pass_shared(self, cutoff: AtomicParam) -> BltFilter<Self>
+    where
+        Self: Sized,
+        Self: Source<Item = f32>,
+    {
+        blt::low_pass_shared(self, cutoff)
+    }
+
+    /// Applies a high-pass filter whose cutoff frequency in Hz is read from `cutoff` while
+    /// playing, so it can be changed from another thread.
+    #[inline]
+    fn high_pass_shared(self, cutoff: AtomicParam) -> BltFilter<Self>
+    where
+        Self: Sized,
+        Self: Source<Item = f32>,
+    {
+        blt::high_pass_shared(self, cutoff)
+    }
+
+    /// Applies an equalizer made of peaking filters, one per band, in series. The gains can
+    /// be changed while playing through [`Equalizer::handle`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{EqBand, SineWave, Source};
+    ///
+    /// let eq = SineWave::new(440.0).equalizer(vec![
+    ///     EqBand::new(100.0, 1.4, 3.0),
+    ///     EqBand::new(1000.0, 1.4, -2.0),
+    ///     EqBand::new(8000.0, 1.4, 0.0),
+    /// ]);
+    /// let handle = eq.handle();
+    /// // Later, from any thread:
+    /// handle.set_band_gain(2, 4.5);
+    /// ```
+    #[inline]
+    fn equalizer(self, bands: Vec<EqBand>) -> Equalizer<Self>
+    where
+        Self: Sized,
+    {
+        Equalizer::new(self, bands)
+    }
+
+    /// Latches a flag for each channel as soon as one of its samples exceeds `threshold` in
+    /// absolute value. The flags stay set until reset, see [`ClipIndicator`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{SineWave, Source};
+    ///
+    /// let source = SineWave::new(440.0).amplify(2.0).clip_indicator(1.0);
+    /// let flags = source.get_clip_flags();
+    /// // Hand `source` to a sink and poll `flags.clip_held()` from the UI.
+    /// # let _ = (source, flags);
+    /// ```
+    #[inline]
+    fn clip_indicator(self, threshold: f32) -> ClipIndicator<Self>
+    where
+        Self: Sized,
+    {
+        clip_indicator::clip_indicator(self, threshold)
+    }
+
+    /// Logs how many samples clip, fall outside `-1.0..=1.0`, in each second of the source.
+    /// See [`ClipLog`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{SineWave, Source};
+    /// use std::time::Duration;
+    ///
+    /// let render = SineWave::new(440.0)
+    ///     .amplify(1.2)
+    ///     .take_duration(Duration::from_secs(3))
+    ///     .clip_log();
+    /// let log = render.handle();
+    /// render.for_each(drop);
+    /// for (start, clipped) in log.events() {
+    ///     println!("{clipped} samples clipped in the second at {start:?}");
+    /// }
+    /// ```
+    #[inline]
+    fn clip_log(self) -> ClipLog<Self>
+    where
+        Self: Sized,
+    {
+        clip_log::clip_log(self)
+    }
+
+    /// Measures the phase correlation of the left and right channel, to catch stereo material
+    /// that cancels out in mono. See [`CorrelationMeter`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{SineWave, Source};
+    ///
+    /// let source = SineWave::new(440.0).correlation_meter();
+    /// let meter = source.handle();
+    /// // Hand `source` to a sink and poll `meter.correlation()` from the UI.
+    /// # let _ = (source, meter);
+    /// ```
+    #[inline]
+    fn correlation_meter(self) -> CorrelationMeter<Self>
+    where
+        Self: Sized,
+    {
+        correlation_meter::correlation_meter(self)
+    }
+
+    /// Keeps the most recent `fft_size` frames so a visualizer can read their spectrum, see
+    /// [`SpectrumTap`] for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fft_size` is not a power of two.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{SineWave, Source};
+    ///
+    /// let source = SineWave::new(440.0).spectrum_tap(2048);
+    /// let spectrum = source.handle();
+    /// // Hand `source` to a sink and poll `spectrum.magnitudes()` from the UI.
+    /// # let _ = (source, spectrum);
+    /// ```
+    #[inline]
+    fn spectrum_tap(self, fft_size: usize) -> SpectrumTap<Self>
+    where
+        Self: Sized,
+    {
+        spectrum_tap::spectrum_tap(self, fft_size)
+    }
+
+    /// Feeds part of each channel of a stereo source to the other one, making it sound less
+    /// wide on headphones.
+    ///
+    /// `amount` ranges from `0.0` (no crossfeed) to `1.0`. See [`Crossfeed`] for details.
+    #[inline]
+    fn crossfeed(self, amount: f32) -> Crossfeed<Self>
+    where
+        Self: Sized,
+    {
+        crossfeed::crossfeed(self, amount)
+    }
+
+    /// Flips the polarity of the given channels by negating their samples.
+    ///
+    /// Channels are numbered from zero. An empty slice inverts all channels.
+    #[inline]
+    fn invert_polarity(self, channels: &[usize]) -> InvertPolarity<Self>
+    where
+        Self: Sized,
+    {
+        invert_polarity::invert_polarity(self, channels)
+    }
+
+    /// Widens or narrows the stereo image by scaling the side component of each frame.
+    ///
+    /// A `width` of `0.0` collapses the sound to mono, `1.0` leaves it unchanged and values
+    /// above `1.0` make it wider. Sources that are not stereo pass through unchanged, see
+    /// [`StereoWidth`] for details.
+    #[inline]
+    fn stereo_width(self, width: f32) -> StereoWidth<Self>
+    where
+        Self: Sized,
+    {
+        stereo_width::stereo_width(self, width)
+    }
+
+    /// Folds this source down to a single channel.
+    ///
+    /// Stereo sources are mixed as `left * weights.0 + right * weights.1`, `None` uses
+    /// `(0.5, 0.5)`. Mono sources pass through unchanged and sources with more channels are
+    /// downmixed by averaging all channels, ignoring `weights`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::buffer::SamplesBuffer;
+    /// use rodio::nz;
+    /// use rodio::Source;
+    ///
+    /// let stereo = SamplesBuffer::new(nz!(2), nz!(44100), vec![1.0, 0.0, 0.5, 0.5]);
+    /// let mono: Vec<f32> = stereo.to_mono(None).collect();
+    /// assert_eq!(mono, [0.5, 0.5]);
+    /// ```
+    #[inline]
+    fn to_mono(self, weights: Option<(f32, f32)>) -> ToMono<Self>
+    where
+        Self: Sized,
+    {
+        to_mono::to_mono(self, weights)
+    }
+
+    /// Moves this source through the stereo field following an envelope.
+    ///
+    /// The source is mixed down to mono and panned with the constant-power law, so it keeps
+    /// its loudness while moving. `points` are pairs of a time and a position from `-1.0`
+    /// (left) through `0.0` (center) to `1.0` (right). The position is linearly interpolated
+    /// between points and holds the first and last value before and after them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `points` is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rodio::source::{SineWave, Source};
+    /// use std::time::Duration;
+    ///
+    /// // Passes from left to right over two seconds.
+    /// let passing = SineWave::new(440.0).pan_envelope(vec![
+    ///     (Duration::ZERO, -1.0),
+    ///     (Duration::from_secs(2), 1.0),
+    /// ]);
+    /// ```
+    #[inline]
+    fn pan_envelope(self, points: Vec<(Duration, f32)>) -> PanEnvelope<Self>
+    where
+        Self: Sized,
+    {
+        pan_envelope::pan_envelope(self, points)
+    }
+
     /// Applies a distortion effect to the sound.
     #[inline]
     fn distortion(self, gain: f32, threshold: f32) -> Distortion<Self>
@@ -728,6 +1403,31 @@ pub trait Source: Iterator<Item = Sample> {
             underlying_source: std::any::type_name::<Self>(),
         })
     }
+
+    /// Attempts to seek to the start of frame `frame`, a frame holding one sample per channel.
+    ///
+    /// Unlike [`Source::try_seek`] the position is not rounded, the next sample is the first
+    /// channel of that frame. Sources that can not seek to an exact frame fall back to
+    /// [`Source::try_seek`] with the matching position.
+    ///
+    /// # Errors
+    /// The same as [`Source::try_seek`].
+    fn try_seek_samples(&mut self, frame: u64) -> Result<(), SeekError> {
+        self.try_seek(crate::math::frame_to_duration(frame, self.sample_rate()))
+    }
+}
+
+/// The format of a source at a given moment, as returned by [`Source::describe`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceInfo {
+    /// See [`Source::channels`].
+    pub channels: ChannelCount,
+    /// See [`Source::sample_rate`].
+    pub sample_rate: SampleRate,
+    /// See [`Source::total_duration`].
+    pub total_duration: Option<Duration>,
+    /// See [`Source::current_span_len`].
+    pub current_span_len: Option<usize>,
 }
 
 // We might add decoders requiring new error types, without non_exhaustive
@@ -809,6 +1509,11 @@ macro_rules! source_pointer_impl {
             fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
                 (**self).try_seek(pos)
             }
+
+            #[inline]
+            fn try_seek_samples(&mut self, frame: u64) -> Result<(), SeekError> {
+                (**self).try_seek_samples(frame)
+            }
         }
     };
 }
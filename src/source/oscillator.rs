@@ -0,0 +1,169 @@
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::math::nz;
+use crate::source::{Function, SignalGenerator};
+use crate::Source;
+
+/// An infinite oscillator for synth sounds, with optional hard sync.
+///
+/// Produces the waveforms of a [`SignalGenerator`]. With [`Oscillator::sync_to`] the phase is
+/// reset at every period of a master oscillator that is not heard itself. The oscillator then
+/// repeats at the master frequency while its own frequency shapes the timbre, the classic
+/// sync sound when sweeping it.
+///
+/// # Example
+///
+/// ```
+/// use rodio::nz;
+/// use rodio::source::{Function, Oscillator};
+///
+/// let sync = Oscillator::new(nz!(48000), 660.0, Function::Sawtooth).sync_to(220.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Oscillator {
+    generator: SignalGenerator,
+    frequency: f32,
+    sync: Option<Sync>,
+}
+
+#[derive(Clone, Debug)]
+struct Sync {
+    frequency: f32,
+    // Phase of the master oscillator in periods, kept in `f64` so its resets stay on time.
+    phase: f64,
+    phase_step: f64,
+}
+
+impl Oscillator {
+    /// Creates an oscillator playing `function` at `frequency`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `frequency` is not greater than zero.
+    pub fn new(sample_rate: SampleRate, frequency: f32, function: Function) -> Oscillator {
+        Oscillator {
+            generator: SignalGenerator::new(sample_rate, frequency, function),
+            frequency,
+            sync: None,
+        }
+    }
+
+    /// Hard syncs the oscillator to a master oscillator at `master_frequency`, resetting its
+    /// phase at the start of every master period.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `master_frequency` is not greater than zero.
+    pub fn sync_to(mut self, master_frequency: f32) -> Oscillator {
+        assert!(
+            master_frequency > 0.0,
+            "master frequency must be greater than zero"
+        );
+        self.sync = Some(Sync {
+            frequency: master_frequency,
+            phase: 0.0,
+            phase_step: master_frequency as f64 / self.generator.sample_rate().get() as f64,
+        });
+        self.generator.set_phase(0.0);
+        self
+    }
+}
+
+impl Iterator for Oscillator {
+    type Item = f32;
+
+    #[inline]
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.generator.next();
+        if let Some(sync) = &mut self.sync {
+            sync.phase += sync.phase_step;
+            if sync.phase >= 1.0 {
+                sync.phase -= 1.0;
+                // The master period started between two samples, start the new cycle from
+                // that moment so the reset does not jitter.
+                let since_reset = sync.phase / sync.phase_step;
+                let phase = since_reset * self.generator.phase_step() as f64;
+                self.generator.set_phase(phase as f32);
+            }
+        }
+        sample
+    }
+}
+
+impl Source for Oscillator {
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        nz!(1)
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.generator.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        match &mut self.sync {
+            None => self.generator.try_seek(pos),
+            Some(sync) => {
+                sync.phase = (pos.as_secs_f64() * sync.frequency as f64).fract();
+                let since_reset = sync.phase / sync.frequency as f64;
+                self.generator
+                    .set_phase((since_reset * self.frequency as f64).fract() as f32);
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Oscillator;
+    use crate::math::nz;
+    use crate::source::{Function, SignalGenerator};
+    use crate::Source;
+
+    #[test]
+    fn sync_resets_at_master_frequency() {
+        // A master period of exactly 128 samples.
+        let synced: Vec<f32> = Oscillator::new(nz!(48000), 1000.0, Function::Sawtooth)
+            .sync_to(375.0)
+            .take(128 * 10)
+            .collect();
+        let free: Vec<f32> = SignalGenerator::new(nz!(48000), 1000.0, Function::Sawtooth)
+            .take(128)
+            .collect();
+        for period in synced.chunks(128) {
+            // Every master period starts the saw over.
+            assert_eq!(period[0], 0.0);
+            for (synced, free) in period.iter().zip(&free) {
+                assert!((synced - free).abs() < 1e-3, "{synced} != {free}");
+            }
+        }
+    }
+
+    #[test]
+    fn seeks_within_master_period() {
+        let mut osc = Oscillator::new(nz!(48000), 1000.0, Function::Sawtooth).sync_to(375.0);
+        let expected: Vec<f32> = osc.clone().skip(128 * 3 + 24).take(8).collect();
+        osc.try_seek(Duration::from_secs_f64((128 * 3 + 24) as f64 / 48000.0))
+            .unwrap();
+        for (seeked, expected) in osc.take(8).zip(expected) {
+            assert!((seeked - expected).abs() < 1e-3, "{seeked} != {expected}");
+        }
+    }
+}
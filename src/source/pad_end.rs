@@ -0,0 +1,181 @@
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds a `PadEnd` object.
+pub fn pad_end<I>(input: I, duration: Duration) -> PadEnd<I>
+where
+    I: Source,
+{
+    PadEnd {
+        input,
+        duration,
+        remaining_samples: None,
+    }
+}
+
+/// A source that plays silence for a certain duration after the given source ends.
+#[derive(Clone, Debug)]
+pub struct PadEnd<I> {
+    input: I,
+    duration: Duration,
+    // Number of silent samples left, `None` while the input is playing.
+    remaining_samples: Option<usize>,
+}
+
+impl<I> PadEnd<I>
+where
+    I: Source,
+{
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    /// The padding in samples, whole frames so the silence never ends mid-frame.
+    fn padding_samples(&self, duration: Duration) -> usize {
+        let frames = duration.as_nanos() * self.input.sample_rate().get() as u128 / 1_000_000_000;
+        frames as usize * self.input.channels().get() as usize
+    }
+}
+
+impl<I> Iterator for PadEnd<I>
+where
+    I: Source,
+{
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        if self.remaining_samples.is_none() {
+            if let Some(sample) = self.input.next() {
+                return Some(sample);
+            }
+            self.remaining_samples = Some(self.padding_samples(self.duration));
+        }
+        let remaining = self.remaining_samples.as_mut()?;
+        if *remaining == 0 {
+            return None;
+        }
+        *remaining -= 1;
+        Some(0.0)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining_samples {
+            Some(remaining) => (remaining, Some(remaining)),
+            None => {
+                let padding = self.padding_samples(self.duration);
+                let (min, max) = self.input.size_hint();
+                (min + padding, max.map(|max| max + padding))
+            }
+        }
+    }
+}
+
+impl<I> Source for PadEnd<I>
+where
+    I: Source,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        match self.remaining_samples {
+            Some(remaining) => Some(remaining),
+            None => match self.input.current_span_len() {
+                Some(0) => Some(self.padding_samples(self.duration)),
+                len => len,
+            },
+        }
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration().map(|val| val + self.duration)
+    }
+
+    /// Seeking past the end of the inner source positions the source in the silence, this
+    /// requires the inner source to know its total duration.
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        match self.input.total_duration() {
+            Some(input_duration) if pos >= input_duration => {
+                self.input.try_seek(input_duration)?;
+                let silence = self.duration.saturating_sub(pos - input_duration);
+                self.remaining_samples = Some(self.padding_samples(silence));
+            }
+            _ => {
+                self.input.try_seek(pos)?;
+                self.remaining_samples = None;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::Source;
+
+    #[test]
+    fn appends_silence() {
+        let source = SamplesBuffer::new(nz!(2), nz!(10), vec![1.0; 20]);
+        let padded = source.pad_end(Duration::from_millis(500));
+        assert_eq!(padded.total_duration(), Some(Duration::from_millis(1500)));
+        assert_eq!(padded.size_hint(), (30, Some(30)));
+
+        let output: Vec<f32> = padded.collect();
+        assert_eq!(output.len(), 30);
+        assert!(output[..20].iter().all(|s| *s == 1.0));
+        assert!(output[20..].iter().all(|s| *s == 0.0));
+    }
+
+    #[test]
+    fn pads_whole_frames() {
+        // 150 ms is 1.5 frames at 10 Hz, rounded down to one frame of 3 samples.
+        let source = SamplesBuffer::new(nz!(3), nz!(10), vec![1.0; 6]);
+        let padded: Vec<f32> = source.pad_end(Duration::from_millis(150)).collect();
+        assert_eq!(padded, [1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn seeks_into_silence() {
+        let source = SamplesBuffer::new(nz!(1), nz!(10), vec![1.0; 10]);
+        let mut padded = source.pad_end(Duration::from_secs(1));
+        padded.try_seek(Duration::from_millis(1500)).unwrap();
+        assert_eq!(padded.by_ref().count(), 5);
+
+        padded.try_seek(Duration::from_millis(500)).unwrap();
+        let output: Vec<f32> = padded.collect();
+        assert_eq!(output, [[1.0; 5].as_slice(), &[0.0; 10]].concat());
+    }
+}
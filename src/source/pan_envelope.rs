@@ -0,0 +1,203 @@
+use std::f32::consts::FRAC_PI_4;
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::math::nz;
+use crate::{Sample, Source};
+
+/// Internal function that builds a `PanEnvelope` object.
+///
+/// # Panics
+///
+/// Panics if `points` is empty.
+pub fn pan_envelope<I>(input: I, mut points: Vec<(Duration, f32)>) -> PanEnvelope<I>
+where
+    I: Source,
+{
+    assert!(!points.is_empty(), "the envelope needs at least one point");
+    points.sort_by_key(|(time, _)| *time);
+    for (_, pan) in &mut points {
+        *pan = pan.clamp(-1.0, 1.0);
+    }
+    PanEnvelope {
+        input,
+        points,
+        elapsed: 0.0,
+        right: None,
+    }
+}
+
+/// Places a source in the stereo field at a position that follows an envelope, see
+/// [`Source::pan_envelope`].
+#[derive(Clone, Debug)]
+pub struct PanEnvelope<I> {
+    input: I,
+    // Breakpoints sorted by time, with positions from -1.0 (left) to 1.0 (right).
+    points: Vec<(Duration, f32)>,
+    // Time of the next frame in seconds.
+    elapsed: f64,
+    // The right channel of the current frame.
+    right: Option<Sample>,
+}
+
+impl<I> PanEnvelope<I> {
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    /// Returns the pan position at `time` in seconds.
+    fn pan_at(&self, time: f64) -> f32 {
+        let next = self
+            .points
+            .partition_point(|(point, _)| point.as_secs_f64() <= time);
+        if next == 0 {
+            return self.points[0].1;
+        }
+        let Some((end, to)) = self.points.get(next) else {
+            return self.points[next - 1].1;
+        };
+        let (start, from) = self.points[next - 1];
+        let t = (time - start.as_secs_f64()) / (*end - start).as_secs_f64();
+        from + (to - from) * t as f32
+    }
+}
+
+impl<I> Iterator for PanEnvelope<I>
+where
+    I: Source,
+{
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        if let Some(right) = self.right.take() {
+            return Some(right);
+        }
+
+        let channels = self.input.channels().get();
+        let sample_rate = self.input.sample_rate().get();
+        let mut sum = self.input.next()?;
+        for _ in 1..channels {
+            sum += self.input.next().unwrap_or(0.0);
+        }
+        let mono = sum / channels as Sample;
+
+        // Constant power: the gains of both channels are the cosine and sine of an angle
+        // from 0 to a quarter turn.
+        let angle = (self.pan_at(self.elapsed) + 1.0) * FRAC_PI_4;
+        self.elapsed += 1.0 / sample_rate as f64;
+        self.right = Some(mono * angle.sin());
+        Some(mono * angle.cos())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let channels = self.input.channels().get() as usize;
+        let pending = self.right.is_some() as usize;
+        let (lower, upper) = self.input.size_hint();
+        (
+            lower / channels * 2 + pending,
+            upper.map(|upper| upper / channels * 2 + pending),
+        )
+    }
+}
+
+impl<I> Source for PanEnvelope<I>
+where
+    I: Source,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        let channels = self.input.channels().get() as usize;
+        self.input
+            .current_span_len()
+            .map(|len| len / channels * 2 + self.right.is_some() as usize)
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        nz!(2)
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.elapsed = pos.as_secs_f64();
+        self.right = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::source::SineWave;
+    use crate::Source;
+
+    #[test]
+    fn moves_from_left_to_right() {
+        let tone = SineWave::new(440.0).take_duration(Duration::from_secs(1));
+        let panned = tone.pan_envelope(vec![(Duration::ZERO, -1.0), (Duration::from_secs(1), 1.0)]);
+        assert_eq!(panned.channels(), nz!(2));
+        let output: Vec<f32> = panned.collect();
+        assert_eq!(output.len(), 2 * 48000);
+
+        let energy = |frames: &[f32], channel: usize| -> f32 {
+            frames.iter().skip(channel).step_by(2).map(|s| s * s).sum()
+        };
+        let start = &output[..2 * 4800];
+        let middle = &output[2 * 21600..2 * 26400];
+        let end = &output[2 * 43200..];
+        assert!(energy(start, 0) > 50.0 * energy(start, 1));
+        assert!(energy(end, 1) > 50.0 * energy(end, 0));
+        let balance = energy(middle, 0) / energy(middle, 1);
+        assert!((balance - 1.0).abs() < 0.1, "{balance}");
+    }
+
+    #[test]
+    fn keeps_power_constant() {
+        let input = SamplesBuffer::new(nz!(2), nz!(10), vec![0.5; 2 * 20]);
+        let output: Vec<f32> = input
+            .pan_envelope(vec![
+                (Duration::from_millis(500), 0.3),
+                (Duration::ZERO, -0.8),
+            ])
+            .collect();
+        assert_eq!(output.len(), 2 * 20);
+        for frame in output.chunks(2) {
+            let power = frame[0] * frame[0] + frame[1] * frame[1];
+            assert!((power - 0.25).abs() < 1e-6, "{frame:?}");
+        }
+        // The points are sorted and the position holds after the last one.
+        assert!(output[0] > output[1]);
+        assert_eq!(output[12..14], output[38..40]);
+    }
+}
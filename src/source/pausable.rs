@@ -130,4 +130,9 @@ where
     fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
         self.input.try_seek(pos)
     }
+
+    #[inline]
+    fn try_seek_samples(&mut self, frame: u64) -> Result<(), SeekError> {
+        self.input.try_seek_samples(frame)
+    }
 }
@@ -154,6 +154,16 @@ where
         }
         result
     }
+
+    #[inline]
+    fn try_seek_samples(&mut self, frame: u64) -> Result<(), SeekError> {
+        let result = self.input.try_seek_samples(frame);
+        if result.is_ok() {
+            self.offset_duration = frame as f64 / self.input.sample_rate().get() as f64;
+            self.samples_counted = 0;
+        }
+        result
+    }
 }
 
 #[cfg(test)]
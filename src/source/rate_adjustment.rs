@@ -0,0 +1,425 @@
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Largest adjustment accepted by [`RateAdjustment::set_ppm`], in parts per million.
+pub const MAX_RATE_ADJUSTMENT_PPM: f32 = 1000.0;
+/// How much the playback rate may change per frame, so adjustments do not cause audible jumps.
+const MAX_STEP_CHANGE: f64 = 1e-8;
+
+/// Slightly changes the playback rate of a source to follow an external clock.
+///
+/// The adjustment is given in parts per million (ppm). Positive values produce more samples and
+/// so slow playback down, negative values speed it up. Changes are applied gradually, the rate
+/// moves by at most 0.01 ppm per frame. Unlike [`Speed`](super::Speed) the sample rate reported
+/// stays the same, the input is resampled using linear interpolation.
+///
+/// On top of the adjustment the speed can be ramped, see
+/// [`ramp_speed`](RateAdjustment::ramp_speed), for example to slow a sound down to a halt like
+/// a tape stop.
+///
+/// As long as the adjustment is `0` and the speed `1.0` the input passes through untouched,
+/// including changes of its format. Once resampling started it continues until the source is
+/// seeked, interpolation only happens between frames of the same format.
+#[derive(Clone)]
+pub struct RateAdjustment<I>
+where
+    I: Source,
+{
+    input: I,
+    target_step: f64,
+    step: f64,
+    // Speed multiplying the step, ramped towards `target_speed` over `speed_frames_left`
+    // frames.
+    speed: f64,
+    target_speed: f64,
+    speed_delta: f64,
+    speed_frames_left: u64,
+    // False while the input passes through, `current` and `next` are only read once true.
+    resampling: bool,
+    // Position in the input frame while passing through, resampling starts on a frame
+    // boundary.
+    frame_pos: u16,
+    frame_channels: u16,
+    // Position between `current` and `next`, in frames.
+    frac: f64,
+    // The buffers are reused for every frame, an empty frame marks the end of the input.
+    current: Frame,
+    next: Frame,
+    output: Frame,
+    output_pos: usize,
+}
+
+#[derive(Clone)]
+struct Frame {
+    samples: Vec<Sample>,
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+}
+
+impl Frame {
+    fn new<I: Source>(input: &I) -> Frame {
+        Frame {
+            samples: Vec::with_capacity(input.channels().get() as usize),
+            channels: input.channels(),
+            sample_rate: input.sample_rate(),
+        }
+    }
+
+    /// Reads the next frame of `input`, returns false at its end.
+    fn read<I: Source>(&mut self, input: &mut I) -> bool {
+        self.samples.clear();
+        self.channels = input.channels();
+        self.sample_rate = input.sample_rate();
+        for _ in 0..self.channels.get() {
+            match input.next() {
+                Some(sample) => self.samples.push(sample),
+                None => {
+                    self.samples.clear();
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    #[inline]
+    fn same_format(&self, other: &Frame) -> bool {
+        self.channels == other.channels && self.sample_rate == other.sample_rate
+    }
+}
+
+impl<I> RateAdjustment<I>
+where
+    I: Source,
+{
+    /// Builds a new `RateAdjustment`, see [`set_ppm`](RateAdjustment::set_ppm).
+    pub fn new(input: I, ppm: f32) -> RateAdjustment<I> {
+        let frame = Frame::new(&input);
+        let mut ret = RateAdjustment {
+            target_step: 1.0,
+            step: 1.0,
+            speed: 1.0,
+            target_speed: 1.0,
+            speed_delta: 0.0,
+            speed_frames_left: 0,
+            resampling: false,
+            frame_pos: 0,
+            frame_channels: 0,
+            frac: 0.0,
+            current: frame.clone(),
+            next: frame.clone(),
+            output: frame,
+            output_pos: 0,
+            input,
+        };
+        ret.set_ppm(ppm);
+        // Start at the requested rate instead of ramping towards it.
+        ret.step = ret.target_step;
+        ret
+    }
+
+    /// Sets the rate adjustment in parts per million.
+    ///
+    /// The value is clamped to [`MAX_RATE_ADJUSTMENT_PPM`] in both directions.
+    #[inline]
+    pub fn set_ppm(&mut self, ppm: f32) {
+        let ppm = ppm.clamp(-MAX_RATE_ADJUSTMENT_PPM, MAX_RATE_ADJUSTMENT_PPM) as f64;
+        self.target_step = 1.0 / (1.0 + ppm * 1e-6);
+    }
+
+    /// Moves the speed linearly to `speed` over `duration`, without the clicks an instant
+    /// change causes. A speed of `0.5` plays at half speed and an octave lower, `0.0` holds
+    /// the sound still. Negative speeds are treated as `0.0`.
+    ///
+    /// The speed multiplies the rate set with [`set_ppm`](RateAdjustment::set_ppm). A ramp
+    /// that is in progress continues from the current speed.
+    pub fn ramp_speed(&mut self, speed: f32, duration: Duration) {
+        let speed = speed.max(0.0) as f64;
+        if speed == self.target_speed {
+            return;
+        }
+        self.target_speed = speed;
+        let frames = duration.as_secs_f64() * self.input.sample_rate().get() as f64;
+        let frames = frames.round() as u64;
+        if frames == 0 {
+            self.speed = speed;
+            self.speed_frames_left = 0;
+            return;
+        }
+        self.speed_delta = (speed - self.speed) / frames as f64;
+        self.speed_frames_left = frames;
+    }
+
+    /// Returns the current speed, see [`ramp_speed`](RateAdjustment::ramp_speed).
+    #[inline]
+    pub fn current_speed(&self) -> f32 {
+        self.speed as f32
+    }
+
+    /// Whether the output equals the input.
+    #[inline]
+    fn is_unchanged(&self) -> bool {
+        self.target_step == 1.0
+            && self.step == 1.0
+            && self.speed == 1.0
+            && self.speed_frames_left == 0
+    }
+
+    /// Passes the input through again after it was seeked.
+    fn restart(&mut self) {
+        self.resampling = false;
+        self.frame_pos = 0;
+        self.frac = 0.0;
+        self.output.samples.clear();
+        self.output_pos = 0;
+    }
+
+    #[inline]
+    fn next_unchanged(&mut self) -> Option<Sample> {
+        if self.frame_pos == 0 {
+            self.frame_channels = self.input.channels().get();
+        }
+        let sample = self.input.next()?;
+        self.frame_pos += 1;
+        if self.frame_pos == self.frame_channels {
+            self.frame_pos = 0;
+        }
+        Some(sample)
+    }
+
+    fn render_frame(&mut self) -> bool {
+        if self.current.is_empty() {
+            return false;
+        }
+
+        let current = &self.current;
+        let next = &self.next;
+        self.output.samples.clear();
+        self.output.channels = current.channels;
+        self.output.sample_rate = current.sample_rate;
+        if self.frac != 0.0 && !next.is_empty() && next.same_format(current) {
+            let frac = self.frac as f32;
+            self.output.samples.extend(
+                current
+                    .samples
+                    .iter()
+                    .zip(&next.samples)
+                    .map(|(a, b)| a + (b - a) * frac),
+            );
+        } else {
+            // The last frame of a format is held as there is nothing to interpolate towards.
+            self.output.samples.extend_from_slice(&current.samples);
+        }
+
+        self.step += (self.target_step - self.step).clamp(-MAX_STEP_CHANGE, MAX_STEP_CHANGE);
+        if self.speed_frames_left > 0 {
+            self.speed_frames_left -= 1;
+            self.speed = if self.speed_frames_left == 0 {
+                self.target_speed
+            } else {
+                self.speed + self.speed_delta
+            };
+        }
+        self.frac += self.step * self.speed;
+        while self.frac >= 1.0 && !self.current.is_empty() {
+            self.frac -= 1.0;
+            std::mem::swap(&mut self.current, &mut self.next);
+            self.next.read(&mut self.input);
+        }
+        true
+    }
+
+    /// Frame the next sample returned while resampling belongs to.
+    fn next_frame(&self) -> &Frame {
+        if self.output_pos < self.output.samples.len() || self.current.is_empty() {
+            &self.output
+        } else {
+            &self.current
+        }
+    }
+}
+
+impl<I> Iterator for RateAdjustment<I>
+where
+    I: Source,
+{
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        if !self.resampling {
+            if self.frame_pos != 0 || self.is_unchanged() {
+                return self.next_unchanged();
+            }
+            self.resampling = true;
+            self.current.read(&mut self.input);
+            self.next.read(&mut self.input);
+        }
+        if self.output_pos == self.output.samples.len() {
+            if !self.render_frame() {
+                return None;
+            }
+            self.output_pos = 0;
+        }
+        let sample = self.output.samples[self.output_pos];
+        self.output_pos += 1;
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+impl<I> Source for RateAdjustment<I>
+where
+    I: Source,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        let span_len = self.input.current_span_len();
+        if !self.resampling {
+            return span_len;
+        }
+        // The input is two frames ahead, end the span at each frame if its format may change.
+        span_len?;
+        if self.output_pos < self.output.samples.len() {
+            Some(self.output.samples.len() - self.output_pos)
+        } else {
+            Some(self.current.samples.len())
+        }
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        if self.resampling {
+            self.next_frame().channels
+        } else {
+            self.input.channels()
+        }
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        if self.resampling {
+            self.next_frame().sample_rate
+        } else {
+            self.input.sample_rate()
+        }
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.restart();
+        Ok(())
+    }
+
+    #[inline]
+    fn try_seek_samples(&mut self, frame: u64) -> Result<(), SeekError> {
+        self.input.try_seek_samples(frame)?;
+        self.restart();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::RateAdjustment;
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::source::{SineWave, Source};
+
+    #[test]
+    fn unadjusted_is_unchanged() {
+        let samples = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let source = SamplesBuffer::new(nz!(2), nz!(48000), samples.clone());
+        let output: Vec<f32> = RateAdjustment::new(source, 0.0).collect();
+        assert_eq!(output, samples);
+    }
+
+    #[test]
+    fn unadjusted_passes_format_changes_through() {
+        let source = crate::source::from_iter([
+            SamplesBuffer::new(nz!(1), nz!(1000), vec![1.0, 2.0]),
+            SamplesBuffer::new(nz!(2), nz!(2000), vec![3.0, 4.0]),
+        ]);
+        let mut source = RateAdjustment::new(source, 0.0);
+        assert_eq!(source.next(), Some(1.0));
+        assert_eq!(source.next(), Some(2.0));
+        assert_eq!(source.next(), Some(3.0));
+        assert_eq!(
+            (source.channels(), source.sample_rate()),
+            (nz!(2), nz!(2000))
+        );
+        assert_eq!(source.next(), Some(4.0));
+    }
+
+    #[test]
+    fn adjusting_starts_on_a_frame_boundary() {
+        let samples: Vec<f32> = (0..20).map(|i| i as f32).collect();
+        let mut source = RateAdjustment::new(SamplesBuffer::new(nz!(2), nz!(1000), samples), 0.0);
+        assert_eq!(source.next(), Some(0.0));
+        source.ramp_speed(0.5, Duration::ZERO);
+        assert_eq!(source.next(), Some(1.0));
+        let output: Vec<f32> = source.take(4).collect();
+        // Every frame keeps its left and right sample together, at half speed.
+        assert_eq!(output, [2.0, 3.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn positive_adjustment_lengthens_output() {
+        let tone = SineWave::new(440.0).take_duration(Duration::from_secs(10));
+        let input_len = tone.clone().count();
+        let output_len = RateAdjustment::new(tone, 100.0).count();
+        let expected = input_len as f64 * (1.0 + 100e-6);
+        assert!(
+            (output_len as f64 - expected).abs() <= 2.0,
+            "{output_len} samples, expected {expected}"
+        );
+    }
+
+    #[test]
+    fn speed_ramps_linearly() {
+        let ramp: Vec<f32> = (0..1000).map(|i| i as f32).collect();
+        let mut source = RateAdjustment::new(SamplesBuffer::new(nz!(1), nz!(1000), ramp), 0.0);
+        source.ramp_speed(0.0, Duration::from_millis(100));
+        let output: Vec<f32> = source.by_ref().take(200).collect();
+        // The input position advances by the speed of each frame, from 1.0 down to 0.0.
+        for (i, pair) in output.windows(2).enumerate() {
+            let expected = (1.0 - (i + 1) as f32 / 100.0).max(0.0);
+            assert!((pair[1] - pair[0] - expected).abs() < 1e-3, "{i}: {pair:?}");
+        }
+        assert_eq!(source.current_speed(), 0.0);
+        assert!((output[199] - 49.5).abs() < 1e-2, "{}", output[199]);
+
+        source.ramp_speed(2.0, Duration::ZERO);
+        let fast: Vec<f32> = source.take(3).collect();
+        assert!((fast[1] - fast[0] - 2.0).abs() < 1e-3, "{fast:?}");
+    }
+
+    #[test]
+    fn adjustment_is_bounded_and_smooth() {
+        let mut source = RateAdjustment::new(SineWave::new(440.0), 0.0);
+        source.set_ppm(1e6);
+        assert!((source.target_step - 1.0 / (1.0 + 1e-3)).abs() < 1e-12);
+        source.next();
+        assert!(source.step < 1.0 && 1.0 - source.step < 2.0 * super::MAX_STEP_CHANGE);
+    }
+}
@@ -0,0 +1,232 @@
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Encoding of the samples read by a [`RawPcmSource`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PcmFormat {
+    /// Unsigned 8 bit, silence is `128`.
+    U8,
+    /// Signed 16 bit, little endian.
+    I16Le,
+    /// Signed 16 bit, big endian.
+    I16Be,
+    /// Signed 24 bit packed in three bytes, little endian.
+    I24Le,
+    /// Signed 24 bit packed in three bytes, big endian.
+    I24Be,
+    /// Signed 32 bit, little endian.
+    I32Le,
+    /// Signed 32 bit, big endian.
+    I32Be,
+    /// 32 bit float, little endian.
+    F32Le,
+    /// 32 bit float, big endian.
+    F32Be,
+    /// 64 bit float, little endian.
+    F64Le,
+    /// 64 bit float, big endian.
+    F64Be,
+}
+
+impl PcmFormat {
+    /// Returns the number of bytes one sample takes.
+    pub fn bytes_per_sample(self) -> usize {
+        match self {
+            PcmFormat::U8 => 1,
+            PcmFormat::I16Le | PcmFormat::I16Be => 2,
+            PcmFormat::I24Le | PcmFormat::I24Be => 3,
+            PcmFormat::I32Le | PcmFormat::I32Be | PcmFormat::F32Le | PcmFormat::F32Be => 4,
+            PcmFormat::F64Le | PcmFormat::F64Be => 8,
+        }
+    }
+
+    /// Converts the bytes of one sample.
+    fn decode(self, bytes: &[u8]) -> Sample {
+        match self {
+            PcmFormat::U8 => (bytes[0] as f32 - 128.0) / 128.0,
+            PcmFormat::F32Le => f32::from_le_bytes(bytes.try_into().unwrap()),
+            PcmFormat::F32Be => f32::from_be_bytes(bytes.try_into().unwrap()),
+            PcmFormat::F64Le => f64::from_le_bytes(bytes.try_into().unwrap()) as f32,
+            PcmFormat::F64Be => f64::from_be_bytes(bytes.try_into().unwrap()) as f32,
+            PcmFormat::I16Le | PcmFormat::I24Le | PcmFormat::I32Le => {
+                // Sign extend by placing the bytes at the top of an `i32`.
+                let mut padded = [0; 4];
+                padded[4 - bytes.len()..].copy_from_slice(bytes);
+                i32::from_le_bytes(padded) as f32 / 2_147_483_648.0
+            }
+            PcmFormat::I16Be | PcmFormat::I24Be | PcmFormat::I32Be => {
+                let mut padded = [0; 4];
+                padded[..bytes.len()].copy_from_slice(bytes);
+                i32::from_be_bytes(padded) as f32 / 2_147_483_648.0
+            }
+        }
+    }
+}
+
+/// A source that plays raw interleaved PCM without any container, for example received over
+/// a network protocol.
+///
+/// # Example
+///
+/// ```
+/// use rodio::nz;
+/// use rodio::source::{PcmFormat, RawPcmSource};
+///
+/// let bytes = vec![0x00, 0x40, 0x00, 0xc0];
+/// let source = RawPcmSource::new(bytes, nz!(1), nz!(8000), PcmFormat::I16Le);
+/// assert_eq!(source.collect::<Vec<_>>(), [0.5, -0.5]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct RawPcmSource {
+    bytes: Vec<u8>,
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+    format: PcmFormat,
+    // Byte offset of the next sample.
+    pos: usize,
+    // Bytes that form whole frames.
+    end: usize,
+}
+
+impl RawPcmSource {
+    /// Builds a source reading `bytes` as interleaved samples in `format`.
+    ///
+    /// Bytes of an incomplete last frame are ignored.
+    pub fn new(
+        bytes: Vec<u8>,
+        channels: ChannelCount,
+        sample_rate: SampleRate,
+        format: PcmFormat,
+    ) -> RawPcmSource {
+        let frame_len = format.bytes_per_sample() * channels.get() as usize;
+        let end = bytes.len() - bytes.len() % frame_len;
+        RawPcmSource {
+            bytes,
+            channels,
+            sample_rate,
+            format,
+            pos: 0,
+            end,
+        }
+    }
+
+    /// Returns the encoding of the samples.
+    pub fn format(&self) -> PcmFormat {
+        self.format
+    }
+}
+
+impl Iterator for RawPcmSource {
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        if self.pos >= self.end {
+            return None;
+        }
+        let len = self.format.bytes_per_sample();
+        let sample = self.format.decode(&self.bytes[self.pos..self.pos + len]);
+        self.pos += len;
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.end - self.pos) / self.format.bytes_per_sample();
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for RawPcmSource {}
+
+impl Source for RawPcmSource {
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.channels
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        let frame_len = self.format.bytes_per_sample() * self.channels.get() as usize;
+        let frames = (self.end / frame_len) as f64;
+        Some(Duration::from_secs_f64(
+            frames / self.sample_rate.get() as f64,
+        ))
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        let frame_len = self.format.bytes_per_sample() * self.channels.get() as usize;
+        let frame = (pos.as_secs_f64() * self.sample_rate.get() as f64) as usize;
+        self.pos = frame.saturating_mul(frame_len).min(self.end);
+        Ok(())
+    }
+
+    #[inline]
+    fn try_seek_samples(&mut self, frame: u64) -> Result<(), SeekError> {
+        let frame_len = self.format.bytes_per_sample() as u64 * self.channels.get() as u64;
+        self.pos = frame.saturating_mul(frame_len).min(self.end as u64) as usize;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{PcmFormat, RawPcmSource};
+    use crate::math::nz;
+    use crate::Source;
+
+    #[test]
+    fn converts_i16_samples() {
+        let samples: [i16; 5] = [0, 16384, -16384, i16::MAX, i16::MIN];
+        let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        let source = RawPcmSource::new(bytes, nz!(1), nz!(8000), PcmFormat::I16Le);
+        assert_eq!(source.len(), 5);
+        let output: Vec<f32> = source.collect();
+        assert_eq!(output, [0.0, 0.5, -0.5, 32767.0 / 32768.0, -1.0]);
+
+        let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_be_bytes()).collect();
+        let big_endian = RawPcmSource::new(bytes, nz!(1), nz!(8000), PcmFormat::I16Be);
+        assert_eq!(big_endian.collect::<Vec<_>>(), output);
+    }
+
+    #[test]
+    fn reads_whole_frames() {
+        let bytes: Vec<u8> = [0.25f32, -0.25, 1.0]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .chain([0x01, 0x02])
+            .collect();
+        let mut source = RawPcmSource::new(bytes, nz!(2), nz!(1), PcmFormat::F32Le);
+        assert_eq!(source.total_duration(), Some(Duration::from_secs(1)));
+        assert_eq!(source.by_ref().collect::<Vec<_>>(), [0.25, -0.25]);
+
+        source.try_seek(Duration::ZERO).unwrap();
+        assert_eq!(source.next(), Some(0.25));
+    }
+
+    #[test]
+    fn converts_24_bit_samples() {
+        let bytes = vec![0x00, 0x00, 0x40, 0x00, 0x00, 0xc0];
+        let little = RawPcmSource::new(bytes, nz!(1), nz!(8000), PcmFormat::I24Le);
+        assert_eq!(little.collect::<Vec<_>>(), [0.5, -0.5]);
+        let bytes = vec![0x40, 0x00, 0x00, 0xc0, 0x00, 0x00];
+        let big = RawPcmSource::new(bytes, nz!(1), nz!(8000), PcmFormat::I24Be);
+        assert_eq!(big.collect::<Vec<_>>(), [0.5, -0.5]);
+    }
+}
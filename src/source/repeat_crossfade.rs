@@ -0,0 +1,201 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use super::{Buffered, SeekError};
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds a `RepeatCrossfade` object.
+pub fn repeat_crossfade<I>(input: I, fade: Duration) -> RepeatCrossfade<I>
+where
+    I: Source,
+{
+    let channels = input.channels().get() as usize;
+    let frames = (fade.as_secs_f64() * input.sample_rate().get() as f64).round() as usize;
+    let start = input.buffered();
+    RepeatCrossfade {
+        current: start.clone(),
+        start,
+        fade_len: frames * channels,
+        channels,
+        ahead: VecDeque::new(),
+        crossfade: VecDeque::new(),
+    }
+}
+
+/// A source that repeats the given source forever, crossfading the end of each iteration
+/// into the beginning of the next.
+///
+/// The crossfade is linear and starts at the beginning of the last `fade` of each iteration,
+/// the beginning of the next iteration plays in it so the loop is shorter by `fade`. The fade
+/// should be shorter than half the source.
+pub struct RepeatCrossfade<I>
+where
+    I: Source,
+{
+    start: Buffered<I>,
+    current: Buffered<I>,
+    // Length of the crossfade in samples.
+    fade_len: usize,
+    channels: usize,
+    // Samples read ahead of the output, to know when the last `fade_len` samples start.
+    ahead: VecDeque<Sample>,
+    // The crossfaded samples that have not been played yet.
+    crossfade: VecDeque<Sample>,
+}
+
+impl<I> RepeatCrossfade<I>
+where
+    I: Source,
+{
+    /// Mixes the end of the current iteration, held by `ahead`, with the beginning of the next.
+    fn start_crossfade(&mut self) {
+        self.current = self.start.clone();
+        let frames = (self.ahead.len() / self.channels) as f32;
+        for (i, tail) in self.ahead.drain(..).enumerate() {
+            let head = self.current.next().unwrap_or(0.0);
+            let t = ((i / self.channels) as f32 + 0.5) / frames;
+            self.crossfade.push_back(tail + (head - tail) * t);
+        }
+    }
+}
+
+impl<I> Iterator for RepeatCrossfade<I>
+where
+    I: Source,
+{
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        if let Some(sample) = self.crossfade.pop_front() {
+            return Some(sample);
+        }
+        while self.ahead.len() <= self.fade_len {
+            match self.current.next() {
+                Some(sample) => self.ahead.push_back(sample),
+                None => break,
+            }
+        }
+        if self.ahead.len() > self.fade_len {
+            return self.ahead.pop_front();
+        }
+
+        if self.ahead.is_empty() {
+            // The previous crossfade used up the whole iteration, or the source is empty.
+            self.current = self.start.clone();
+            let sample = self.current.next()?;
+            self.ahead.push_back(sample);
+            return self.next();
+        }
+        self.start_crossfade();
+        self.crossfade.pop_front()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // infinite
+        (0, None)
+    }
+}
+
+impl<I> Source for RepeatCrossfade<I>
+where
+    I: Source,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.start.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.start.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Seeks within the current iteration, a crossfade in progress is dropped.
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.current.try_seek(pos)?;
+        self.ahead.clear();
+        self.crossfade.clear();
+        Ok(())
+    }
+}
+
+impl<I> Clone for RepeatCrossfade<I>
+where
+    I: Source,
+{
+    #[inline]
+    fn clone(&self) -> RepeatCrossfade<I> {
+        RepeatCrossfade {
+            start: self.start.clone(),
+            current: self.current.clone(),
+            fade_len: self.fade_len,
+            channels: self.channels,
+            ahead: self.ahead.clone(),
+            crossfade: self.crossfade.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::Source;
+
+    #[test]
+    fn loops_without_discontinuity() {
+        // 10.3 periods of a 10 Hz tone, so plain repeating jumps at the seam.
+        let tone: Vec<f32> = (0..1030)
+            .map(|i| (TAU * 10.0 * i as f32 / 1000.0).sin())
+            .collect();
+        let max_step = |samples: &[f32]| {
+            samples
+                .windows(2)
+                .map(|w| (w[1] - w[0]).abs())
+                .fold(0.0, f32::max)
+        };
+        let smooth = max_step(&tone);
+
+        let plain: Vec<f32> = SamplesBuffer::new(nz!(1), nz!(1000), tone.clone())
+            .repeat_infinite()
+            .take(3000)
+            .collect();
+        assert!(max_step(&plain) > 10.0 * smooth);
+
+        let looped: Vec<f32> = SamplesBuffer::new(nz!(1), nz!(1000), tone.clone())
+            .repeat_crossfade(Duration::from_millis(100))
+            .take(3000)
+            .collect();
+        assert!(max_step(&looped) < 2.0 * smooth, "{}", max_step(&looped));
+        // Each iteration after the first is shorter by the fade.
+        assert_eq!(looped[..930], tone[..930]);
+        assert_eq!(looped[1030..1860], tone[100..930]);
+    }
+
+    #[test]
+    fn keeps_channels_in_order() {
+        let samples: Vec<f32> = (0..100).flat_map(|_| [1.0, -1.0]).collect();
+        let looped: Vec<f32> = SamplesBuffer::new(nz!(2), nz!(1000), samples)
+            .repeat_crossfade(Duration::from_millis(30))
+            .take(1000)
+            .collect();
+        assert!(looped.chunks(2).all(|frame| frame == [1.0, -1.0]));
+    }
+}
@@ -0,0 +1,321 @@
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+use std::time::Duration;
+
+use super::{SeekError, UniformSourceIterator};
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Number of steps between two input samples for which filter coefficients are precomputed.
+/// Coefficients in between are linearly interpolated.
+const PHASES: usize = 256;
+
+/// Trade-off between quality and processing cost of a [`Resampler`].
+///
+/// When downsampling the filter is made longer by the ratio between the rates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResampleQuality {
+    /// Uses 8 input frames on each side of an output frame.
+    Fast,
+    /// Uses 16 input frames on each side of an output frame.
+    #[default]
+    Balanced,
+    /// Uses 32 input frames on each side of an output frame, for offline processing.
+    High,
+}
+
+impl ResampleQuality {
+    fn half_taps(self) -> usize {
+        match self {
+            ResampleQuality::Fast => 8,
+            ResampleQuality::Balanced => 16,
+            ResampleQuality::High => 32,
+        }
+    }
+}
+
+/// Converts a source to another sample rate using band-limited (windowed sinc) interpolation.
+///
+/// Any ratio between the rates is supported, for example 44.1 kHz to 48 kHz. Frequencies that
+/// do not fit below half the target rate are filtered out before downsampling. The filter is
+/// split into 256 precomputed phases (a polyphase filter) so no trigonometry happens
+/// while playing.
+///
+/// Unlike the conversion done when playing on a device, this offers a choice of quality, see
+/// [`ResampleQuality`]. The input is converted to its initial channel count and sample rate
+/// should they change.
+#[derive(Clone)]
+pub struct Resampler<I>
+where
+    I: Source,
+{
+    input: UniformSourceIterator<I>,
+    channels: usize,
+    from: u64,
+    to: SampleRate,
+    half_taps: usize,
+    // `PHASES + 1` rows of `2 * half_taps` coefficients.
+    table: Vec<f32>,
+    // Input frames `first_frame..`, interleaved.
+    window: VecDeque<Sample>,
+    first_frame: i64,
+    // Number of input frames once the input ended.
+    input_frames: Option<u64>,
+    frames_read: u64,
+    // Index of the next output frame.
+    output_frame_index: u64,
+    output_frame: Vec<Sample>,
+    output_pos: usize,
+}
+
+impl<I> Resampler<I>
+where
+    I: Source,
+{
+    /// Builds a `Resampler` that converts `input` to `target_rate`.
+    pub fn new(input: I, target_rate: SampleRate, quality: ResampleQuality) -> Resampler<I> {
+        let channels = input.channels();
+        let sample_rate = input.sample_rate();
+        let input = UniformSourceIterator::new(input, channels, sample_rate);
+        let channels = channels.get() as usize;
+        // Lower the cutoff below the new Nyquist frequency when downsampling, the filter then
+        // needs to be proportionally longer.
+        let ratio = (target_rate.get() as f64 / sample_rate.get() as f64).min(1.0);
+        let cutoff = ratio * 0.95;
+        let half_taps = (quality.half_taps() as f64 / ratio).ceil() as usize;
+        let mut table = Vec::with_capacity((PHASES + 1) * 2 * half_taps);
+        for phase in 0..=PHASES {
+            let frac = phase as f64 / PHASES as f64;
+            // Tap `k` weights input frame `i + k`, with `k` from `1 - half_taps` to `half_taps`.
+            for k in 1 - half_taps as i64..=half_taps as i64 {
+                table.push(kernel(frac - k as f64, cutoff, half_taps) as f32);
+            }
+        }
+
+        let mut resampler = Resampler {
+            input,
+            channels,
+            from: sample_rate.get() as u64,
+            to: target_rate,
+            half_taps,
+            table,
+            window: VecDeque::new(),
+            first_frame: 0,
+            input_frames: None,
+            frames_read: 0,
+            output_frame_index: 0,
+            output_frame: Vec::with_capacity(channels),
+            output_pos: 0,
+        };
+        resampler.reset_window();
+        resampler
+    }
+
+    fn reset_window(&mut self) {
+        // Frames before the start are silent.
+        let history = self.half_taps - 1;
+        self.window.clear();
+        self.window.resize(history * self.channels, 0.0);
+        self.first_frame = -(history as i64);
+        self.input_frames = None;
+        self.frames_read = 0;
+        self.output_frame_index = 0;
+        self.output_frame.clear();
+        self.output_pos = 0;
+    }
+
+    fn read_frame(&mut self) {
+        let start = self.window.len();
+        if self.input_frames.is_none() {
+            self.window.extend(self.input.by_ref().take(self.channels));
+            if self.window.len() - start == self.channels {
+                self.frames_read += 1;
+                return;
+            }
+            self.input_frames = Some(self.frames_read);
+        }
+        // Past the end, pad with silence.
+        self.window.resize(start + self.channels, 0.0);
+    }
+
+    fn render_frame(&mut self) -> bool {
+        let to = self.to.get() as u64;
+        let position = self.output_frame_index * self.from;
+        let frame = (position / to) as i64;
+        let frac = (position % to) as f64 / to as f64;
+
+        let last_needed = frame + self.half_taps as i64;
+        while self.first_frame + (self.window.len() / self.channels) as i64 <= last_needed {
+            self.read_frame();
+        }
+        if self
+            .input_frames
+            .is_some_and(|frames| frame >= frames as i64)
+        {
+            return false;
+        }
+        let first_needed = frame + 1 - self.half_taps as i64;
+        let obsolete = (first_needed - self.first_frame) as usize;
+        self.window.drain(..obsolete * self.channels);
+        self.first_frame = first_needed;
+
+        let taps = 2 * self.half_taps;
+        let phase = frac * PHASES as f64;
+        let row = phase as usize;
+        let weight = (phase - row as f64) as f32;
+        let (current, next) = self.table[row * taps..(row + 2) * taps].split_at(taps);
+
+        self.output_frame.clear();
+        self.output_frame.resize(self.channels, 0.0);
+        for (tap, (a, b)) in current.iter().zip(next).enumerate() {
+            let coefficient = a + (b - a) * weight;
+            let input = tap * self.channels;
+            for (channel, out) in self.output_frame.iter_mut().enumerate() {
+                *out += self.window[input + channel] * coefficient;
+            }
+        }
+        self.output_frame_index += 1;
+        true
+    }
+}
+
+/// Blackman windowed sinc low-pass, `cutoff` relative to the Nyquist frequency.
+fn kernel(x: f64, cutoff: f64, half_taps: usize) -> f64 {
+    let half_width = half_taps as f64;
+    if x.abs() >= half_width {
+        return 0.0;
+    }
+    let sinc = if x == 0.0 {
+        1.0
+    } else {
+        (PI * cutoff * x).sin() / (PI * cutoff * x)
+    };
+    let n = (x + half_width) / (2.0 * half_width);
+    let window = 0.42 - 0.5 * (2.0 * PI * n).cos() + 0.08 * (4.0 * PI * n).cos();
+    cutoff * sinc * window
+}
+
+impl<I> Iterator for Resampler<I>
+where
+    I: Source,
+{
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        if self.output_pos == self.output_frame.len() {
+            if !self.render_frame() {
+                return None;
+            }
+            self.output_pos = 0;
+        }
+        let sample = self.output_frame[self.output_pos];
+        self.output_pos += 1;
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+impl<I> Source for Resampler<I>
+where
+    I: Source,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.to
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.reset_window();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+    use std::time::Duration;
+
+    use super::{ResampleQuality, Resampler};
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::Source;
+
+    fn tone(sample_rate: u32, channels: u16) -> SamplesBuffer {
+        let samples: Vec<f32> = (0..sample_rate)
+            .flat_map(|i| {
+                let sample = (TAU * 1000.0 * i as f32 / sample_rate as f32).sin();
+                std::iter::repeat_n(sample, channels as usize)
+            })
+            .collect();
+        SamplesBuffer::new(
+            channels.try_into().unwrap(),
+            sample_rate.try_into().unwrap(),
+            samples,
+        )
+    }
+
+    #[test]
+    fn keeps_frequency_and_level() {
+        for (from, to) in [
+            (44100, 48000),
+            (48000, 44100),
+            (22050, 96000),
+            (96000, 8000),
+        ] {
+            let resampled = Resampler::new(
+                tone(from, 1),
+                to.try_into().unwrap(),
+                ResampleQuality::Balanced,
+            );
+            assert_eq!(resampled.sample_rate().get(), to);
+            assert_eq!(resampled.total_duration(), Some(Duration::from_secs(1)));
+
+            let output: Vec<f32> = resampled.collect();
+            assert_eq!(output.len(), to as usize, "{from} -> {to}");
+            let crossings = output
+                .windows(2)
+                .filter(|w| w[0] < 0.0 && w[1] >= 0.0)
+                .count();
+            assert!(crossings.abs_diff(1000) <= 1, "{from} -> {to}: {crossings}");
+            let peak = output[to as usize / 4..3 * to as usize / 4]
+                .iter()
+                .fold(0.0f32, |peak, s| peak.max(s.abs()));
+            assert!((peak - 1.0).abs() < 0.02, "{from} -> {to}: peak {peak}");
+        }
+    }
+
+    #[test]
+    fn matches_exact_sine() {
+        let resampled = Resampler::new(tone(44100, 2), nz!(48000), ResampleQuality::High);
+        let output: Vec<f32> = resampled.collect();
+        for frame in 4800..43200 {
+            let expected = (TAU * 1000.0 * frame as f32 / 48000.0).sin();
+            for channel in 0..2 {
+                let sample = output[frame * 2 + channel];
+                assert!((sample - expected).abs() < 1e-3, "{sample} != {expected}");
+            }
+        }
+    }
+}
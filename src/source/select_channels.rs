@@ -0,0 +1,173 @@
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds a `SelectChannels` object.
+pub fn select_channels<I>(input: I, indices: &[usize]) -> SelectChannels<I>
+where
+    I: Source,
+{
+    let channels = input.channels().get() as usize;
+    assert!(!indices.is_empty(), "at least one channel must be selected");
+    assert!(
+        indices.len() <= u16::MAX as usize,
+        "rodio supports only up to u16::MAX (65_535) channels"
+    );
+    if let Some(invalid) = indices.iter().find(|&&idx| idx >= channels) {
+        panic!("channel index {invalid} is out of range for a source with {channels} channels");
+    }
+
+    SelectChannels {
+        input,
+        indices: indices.to_vec(),
+        frame: Vec::with_capacity(channels),
+        pos_in_frame: indices.len(),
+    }
+}
+
+/// Picks some of the channels of a source, dropping the others.
+///
+/// Output channel `n` is input channel `indices[n]`. Channels may be selected more than once.
+/// Should the channel count of the input change, channels that no longer exist are silent.
+#[derive(Clone, Debug)]
+pub struct SelectChannels<I> {
+    input: I,
+    indices: Vec<usize>,
+    frame: Vec<Sample>,
+    pos_in_frame: usize,
+}
+
+impl<I> SelectChannels<I> {
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> Iterator for SelectChannels<I>
+where
+    I: Source,
+{
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        if self.pos_in_frame == self.indices.len() {
+            let channels = self.input.channels().get() as usize;
+            self.frame.clear();
+            self.frame.extend(self.input.by_ref().take(channels));
+            if self.frame.len() < channels {
+                return None;
+            }
+            self.pos_in_frame = 0;
+        }
+
+        let sample = self
+            .frame
+            .get(self.indices[self.pos_in_frame])
+            .copied()
+            .unwrap_or(0.0);
+        self.pos_in_frame += 1;
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let channels = self.input.channels().get() as usize;
+        let remaining_in_frame = self.indices.len() - self.pos_in_frame;
+        let (lower, upper) = self.input.size_hint();
+        (
+            lower / channels * self.indices.len() + remaining_in_frame,
+            upper.map(|upper| upper / channels * self.indices.len() + remaining_in_frame),
+        )
+    }
+}
+
+impl<I> Source for SelectChannels<I>
+where
+    I: Source,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        let channels = self.input.channels().get() as usize;
+        let remaining_in_frame = self.indices.len() - self.pos_in_frame;
+        self.input
+            .current_span_len()
+            .map(|len| len / channels * self.indices.len() + remaining_in_frame)
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        ChannelCount::new(self.indices.len() as u16).expect("checked to be non-empty in new")
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.pos_in_frame = self.indices.len();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::Source;
+
+    fn four_channels() -> SamplesBuffer {
+        SamplesBuffer::new(
+            nz!(4),
+            nz!(48000),
+            vec![1.0, 2.0, 3.0, 4.0, 11.0, 12.0, 13.0, 14.0],
+        )
+    }
+
+    #[test]
+    fn extracts_stereo_pair() {
+        let source = four_channels().select_channels(&[0, 1]);
+        assert_eq!(source.channels(), nz!(2));
+        assert_eq!(source.collect::<Vec<_>>(), [1.0, 2.0, 11.0, 12.0]);
+    }
+
+    #[test]
+    fn reorders_and_duplicates() {
+        let source = four_channels().select_channels(&[3, 3, 0]);
+        assert_eq!(source.channels(), nz!(3));
+        assert_eq!(
+            source.collect::<Vec<_>>(),
+            [4.0, 4.0, 1.0, 14.0, 14.0, 11.0]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn rejects_invalid_index() {
+        four_channels().select_channels(&[0, 4]);
+    }
+}
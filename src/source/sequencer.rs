@@ -0,0 +1,255 @@
+use std::time::Duration;
+
+use super::{Function, SeekError, SignalGenerator};
+use crate::common::{ChannelCount, SampleRate};
+use crate::math::nz;
+use crate::Source;
+
+/// Time for a note to reach full level.
+const ATTACK: Duration = Duration::from_millis(5);
+/// Time for a note to fade out after it ends.
+const RELEASE: Duration = Duration::from_millis(20);
+
+/// A source that plays scheduled notes, for example a melody for chiptune playback.
+///
+/// Each note is a `(start, duration, frequency)` event synthesized with the waveform of a
+/// [`SignalGenerator`]. Notes fade in over 5 ms and fade out over 20 ms after their duration
+/// to avoid clicks. Overlapping notes are summed, so the output can exceed `1.0` when several
+/// notes play at once; use [`Source::amplify`] to make room.
+///
+/// Always has one channel. The source ends once the last note faded out.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use rodio::nz;
+/// use rodio::source::{Function, Sequencer};
+///
+/// let beat = Duration::from_millis(250);
+/// let melody = [(Duration::ZERO, beat, 440.0), (beat, beat, 554.37), (beat * 2, beat, 659.26)];
+/// let sequencer = Sequencer::new(nz!(48000), Function::Square, melody);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Sequencer {
+    sample_rate: SampleRate,
+    function: Function,
+    // Start and length of each note in samples and its frequency, sorted by start.
+    notes: Vec<(u64, u64, f32)>,
+    next_note: usize,
+    voices: Vec<Voice>,
+    position: u64,
+    end: u64,
+}
+
+#[derive(Clone, Debug)]
+struct Voice {
+    generator: SignalGenerator,
+    // Samples played since the note started.
+    elapsed: u64,
+    len: u64,
+}
+
+impl Sequencer {
+    /// Builds a sequencer playing `notes` as `(start, duration, frequency)` events.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a frequency is not greater than zero.
+    pub fn new(
+        sample_rate: SampleRate,
+        function: Function,
+        notes: impl IntoIterator<Item = (Duration, Duration, f32)>,
+    ) -> Sequencer {
+        let to_samples =
+            |duration: Duration| (duration.as_secs_f64() * sample_rate.get() as f64).round() as u64;
+        let mut notes: Vec<(u64, u64, f32)> = notes
+            .into_iter()
+            .map(|(start, duration, frequency)| {
+                assert!(frequency > 0.0, "frequency must be greater than zero");
+                (to_samples(start), to_samples(duration), frequency)
+            })
+            .collect();
+        notes.sort_by_key(|(start, _, _)| *start);
+        let release = to_samples(RELEASE);
+        let end = notes
+            .iter()
+            .map(|(start, len, _)| start + len + release)
+            .max()
+            .unwrap_or(0);
+
+        Sequencer {
+            sample_rate,
+            function,
+            notes,
+            next_note: 0,
+            voices: Vec::new(),
+            position: 0,
+            end,
+        }
+    }
+
+    fn start_voice(&mut self, frequency: f32, len: u64, elapsed: u64) {
+        let mut generator =
+            SignalGenerator::new(self.sample_rate, frequency, self.function.clone());
+        if elapsed > 0 {
+            let offset = elapsed as f64 / self.sample_rate.get() as f64;
+            let _ = generator.try_seek(Duration::from_secs_f64(offset));
+        }
+        self.voices.push(Voice {
+            generator,
+            elapsed,
+            len,
+        });
+    }
+}
+
+impl Iterator for Sequencer {
+    type Item = f32;
+
+    #[inline]
+    fn next(&mut self) -> Option<f32> {
+        if self.position >= self.end {
+            return None;
+        }
+        while let Some(&(start, len, frequency)) = self.notes.get(self.next_note) {
+            if start > self.position {
+                break;
+            }
+            self.start_voice(frequency, len, 0);
+            self.next_note += 1;
+        }
+
+        let rate = self.sample_rate.get() as f32;
+        let attack = ATTACK.as_secs_f32() * rate;
+        let release = RELEASE.as_secs_f32() * rate;
+        let mut sample = 0.0;
+        for voice in &mut self.voices {
+            let elapsed = voice.elapsed as f32;
+            let gain = if voice.elapsed < voice.len {
+                (elapsed / attack).min(1.0)
+            } else {
+                // Fade out from the level reached, short notes may not have reached full level.
+                let level = (voice.len as f32 / attack).min(1.0);
+                level * (1.0 - (elapsed - voice.len as f32) / release)
+            };
+            sample += voice.generator.next().unwrap_or(0.0) * gain.max(0.0);
+            voice.elapsed += 1;
+        }
+        self.voices
+            .retain(|voice| voice.elapsed < voice.len + release as u64);
+        self.position += 1;
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end.saturating_sub(self.position) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Sequencer {}
+
+impl Source for Sequencer {
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        nz!(1)
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.sample_rate
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f64(
+            self.end as f64 / self.sample_rate.get() as f64,
+        ))
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        let position = (pos.as_secs_f64() * self.sample_rate.get() as f64).round() as u64;
+        self.position = position.min(self.end);
+        self.voices.clear();
+        let release = (RELEASE.as_secs_f64() * self.sample_rate.get() as f64) as u64;
+        self.next_note = self
+            .notes
+            .partition_point(|(start, _, _)| *start <= self.position);
+        for index in 0..self.next_note {
+            let (start, len, frequency) = self.notes[index];
+            let elapsed = self.position - start;
+            if elapsed < len + release {
+                self.start_voice(frequency, len, elapsed);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::TAU;
+    use std::time::Duration;
+
+    use super::Sequencer;
+    use crate::math::nz;
+    use crate::source::Function;
+    use crate::Source;
+
+    /// Level of `freq` in `samples` at 48 kHz.
+    fn level(samples: &[f32], freq: f32) -> f32 {
+        let (sin, cos) = samples
+            .iter()
+            .enumerate()
+            .fold((0.0, 0.0), |(sin, cos), (i, s)| {
+                let phase = TAU * freq * i as f32 / 48000.0;
+                (sin + s * phase.sin(), cos + s * phase.cos())
+            });
+        2.0 * (sin * sin + cos * cos).sqrt() / samples.len() as f32
+    }
+
+    #[test]
+    fn plays_overlapping_notes() {
+        let notes = [
+            (Duration::ZERO, Duration::from_secs(1), 440.0),
+            (Duration::from_millis(500), Duration::from_secs(1), 1000.0),
+        ];
+        let sequencer = Sequencer::new(nz!(48000), Function::Sine, notes);
+        assert_eq!(
+            sequencer.total_duration(),
+            Some(Duration::from_millis(1520))
+        );
+        let output: Vec<f32> = sequencer.collect();
+        assert_eq!(output.len(), 72960);
+
+        let first = &output[4800..19200];
+        assert!(level(first, 440.0) > 0.9);
+        assert!(level(first, 1000.0) < 0.01);
+        let both = &output[28800..43200];
+        assert!(level(both, 440.0) > 0.9);
+        assert!(level(both, 1000.0) > 0.9);
+        let second = &output[52800..67200];
+        assert!(level(second, 440.0) < 0.01);
+        assert!(level(second, 1000.0) > 0.9);
+    }
+
+    #[test]
+    fn seeks_into_playing_note() {
+        let notes = [(Duration::ZERO, Duration::from_secs(1), 440.0)];
+        let mut sequencer = Sequencer::new(nz!(48000), Function::Sine, notes);
+        let played: Vec<f32> = sequencer.by_ref().take(24010).collect();
+        sequencer.try_seek(Duration::from_millis(500)).unwrap();
+        let after_seek: Vec<f32> = sequencer.take(10).collect();
+        for (a, b) in played[24000..].iter().zip(&after_seek) {
+            assert!((a - b).abs() < 1e-3, "{a} != {b}");
+        }
+    }
+}
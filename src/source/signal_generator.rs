@@ -125,6 +125,20 @@ impl SignalGenerator {
     }
 }
 
+impl SignalGenerator {
+    /// Returns how far the phase advances per sample, in periods.
+    #[inline]
+    pub(crate) fn phase_step(&self) -> f32 {
+        self.phase_step
+    }
+
+    /// Sets the phase of the next sample, in periods.
+    #[inline]
+    pub(crate) fn set_phase(&mut self, phase: f32) {
+        self.phase = phase.rem_euclid(1.0);
+    }
+}
+
 impl Iterator for SignalGenerator {
     type Item = f32;
 
@@ -252,4 +252,13 @@ mod tests {
             channels: 4, sample rate: 96000, seconds: 10, seconds to skip: 10;
         }
     }
+
+    #[test]
+    fn skip_duration_adjusts_total_duration() {
+        let tone = SamplesBuffer::new(nz!(2), nz!(48000), vec![0.5; 2 * 48000]);
+        let skipped = tone.skip_duration(Duration::from_millis(500));
+
+        assert_eq!(skipped.total_duration(), Some(Duration::from_millis(500)));
+        assert_eq!(skipped.count(), 48000);
+    }
 }
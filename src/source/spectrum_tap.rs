@@ -0,0 +1,244 @@
+use std::f32::consts::TAU;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::math::Fft;
+use crate::{Sample, Source};
+
+/// Internal function that builds a `SpectrumTap` object.
+///
+/// # Panics
+///
+/// Panics if `fft_size` is not a power of two of at least 2.
+pub fn spectrum_tap<I>(input: I, fft_size: usize) -> SpectrumTap<I>
+where
+    I: Source,
+{
+    assert!(
+        fft_size >= 2 && fft_size.is_power_of_two(),
+        "the FFT size must be a power of two"
+    );
+    SpectrumTap {
+        shared: Arc::new(Shared {
+            window: Mutex::new(vec![0.0; fft_size]),
+            fft: Fft::new(fft_size),
+            sample_rate: AtomicU32::new(input.sample_rate().get()),
+        }),
+        input,
+        recent: vec![0.0; fft_size],
+        write_pos: 0,
+        frames_since_copy: 0,
+        frame_sum: 0.0,
+        channel: 0,
+    }
+}
+
+/// Passes a source through unchanged while keeping its most recent samples for a spectrum
+/// visualizer, see [`SpectrumHandle`].
+///
+/// Channels are averaged to a single signal. The audio thread only copies samples, the
+/// spectrum is computed on the thread calling [`SpectrumHandle::magnitudes`].
+#[derive(Debug)]
+pub struct SpectrumTap<I> {
+    input: I,
+    shared: Arc<Shared>,
+    // The most recent frames, a ring buffer written at `write_pos`.
+    recent: Vec<Sample>,
+    write_pos: usize,
+    frames_since_copy: usize,
+    frame_sum: Sample,
+    // The channel of the next sample.
+    channel: u16,
+}
+
+#[derive(Debug)]
+struct Shared {
+    // The most recent frames, oldest first.
+    window: Mutex<Vec<Sample>>,
+    fft: Fft,
+    sample_rate: AtomicU32,
+}
+
+/// Reads the spectrum of the samples recently played by a [`SpectrumTap`], from any thread.
+#[derive(Clone, Debug)]
+pub struct SpectrumHandle(Arc<Shared>);
+
+impl<I> SpectrumTap<I> {
+    /// Returns a handle to read the spectrum, for example from a UI thread.
+    #[inline]
+    pub fn handle(&self) -> SpectrumHandle {
+        SpectrumHandle(self.shared.clone())
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    fn copy_window(&mut self) {
+        // Try again later rather than block the audio thread.
+        let Ok(mut window) = self.shared.window.try_lock() else {
+            return;
+        };
+        let (newest, oldest) = self.recent.split_at(self.write_pos);
+        window[..oldest.len()].copy_from_slice(oldest);
+        window[oldest.len()..].copy_from_slice(newest);
+        self.frames_since_copy = 0;
+    }
+}
+
+impl SpectrumHandle {
+    /// Returns the magnitude of each frequency bin of the most recent `fft_size` frames.
+    ///
+    /// There are `fft_size / 2` bins, bin `n` is centered on [`SpectrumHandle::frequency`] of
+    /// `n`. A Hann window is applied, a full scale sine has a magnitude of about `1.0`.
+    pub fn magnitudes(&self) -> Vec<f32> {
+        let window = self.0.window.lock().unwrap().clone();
+        let len = window.len();
+        let mut re: Vec<f32> = window
+            .iter()
+            .enumerate()
+            .map(|(i, sample)| sample * (0.5 - 0.5 * (TAU * i as f32 / len as f32).cos()))
+            .collect();
+        let mut im = vec![0.0; len];
+        self.0.fft.process(&mut re, &mut im);
+        // The Hann window halves the amplitude.
+        let scale = 4.0 / len as f32;
+        re.iter()
+            .zip(&im)
+            .take(len / 2)
+            .map(|(re, im)| (re * re + im * im).sqrt() * scale)
+            .collect()
+    }
+
+    /// Returns the frequency in Hz at the center of a bin of [`SpectrumHandle::magnitudes`].
+    pub fn frequency(&self, bin: usize) -> f32 {
+        let len = self.0.window.lock().unwrap().len();
+        bin as f32 * self.0.sample_rate.load(Ordering::Relaxed) as f32 / len as f32
+    }
+}
+
+impl<I> Iterator for SpectrumTap<I>
+where
+    I: Source,
+{
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        let sample = self.input.next()?;
+        let channels = self.input.channels().get();
+        self.frame_sum += sample;
+        self.channel += 1;
+        if self.channel >= channels {
+            self.recent[self.write_pos] = self.frame_sum / channels as Sample;
+            self.write_pos = (self.write_pos + 1) % self.recent.len();
+            self.frame_sum = 0.0;
+            self.channel = 0;
+            // Publish four times per window.
+            self.frames_since_copy += 1;
+            if self.frames_since_copy >= self.recent.len() / 4 {
+                self.shared
+                    .sample_rate
+                    .store(self.input.sample_rate().get(), Ordering::Relaxed);
+                self.copy_window();
+            }
+        }
+        Some(sample)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.input.size_hint()
+    }
+}
+
+impl<I> ExactSizeIterator for SpectrumTap<I> where I: Source + ExactSizeIterator {}
+
+impl<I> Source for SpectrumTap<I>
+where
+    I: Source,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        self.input.current_span_len()
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.frame_sum = 0.0;
+        self.channel = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::math::nz;
+    use crate::source::SineWave;
+    use crate::Source;
+
+    #[test]
+    fn peak_at_tone_frequency() {
+        // 1500 Hz is the center of bin 32 for an FFT of 1024 frames at 48 kHz.
+        let mut tap = SineWave::new(1500.0).spectrum_tap(1024);
+        let handle = tap.handle();
+        assert!(handle.magnitudes().iter().all(|m| *m == 0.0));
+
+        tap.by_ref().take(4800).for_each(drop);
+        let magnitudes = handle.magnitudes();
+        assert_eq!(magnitudes.len(), 512);
+        let peak = (0..magnitudes.len())
+            .max_by(|a, b| magnitudes[*a].total_cmp(&magnitudes[*b]))
+            .unwrap();
+        assert_eq!(peak, 32);
+        assert_eq!(handle.frequency(peak), 1500.0);
+        assert!(
+            (magnitudes[peak] - 1.0).abs() < 0.01,
+            "{}",
+            magnitudes[peak]
+        );
+        assert!(magnitudes[40..].iter().all(|m| *m < 0.01));
+    }
+
+    #[test]
+    fn averages_channels() {
+        let stereo = SineWave::new(1500.0).reformat(nz!(2), nz!(48000));
+        let mut tap = stereo.spectrum_tap(256);
+        tap.by_ref().take(4800).for_each(drop);
+        let magnitudes = tap.handle().magnitudes();
+        assert!((magnitudes[8] - 1.0).abs() < 0.01, "{}", magnitudes[8]);
+    }
+}
@@ -142,4 +142,10 @@ where
         let pos_accounting_for_speedup = pos.mul_f32(self.factor);
         self.input.try_seek(pos_accounting_for_speedup)
     }
+
+    /// Seeks the inner source to the same frame, the speed does not change the frame count.
+    #[inline]
+    fn try_seek_samples(&mut self, frame: u64) -> Result<(), SeekError> {
+        self.input.try_seek_samples(frame)
+    }
 }
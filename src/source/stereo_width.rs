@@ -0,0 +1,166 @@
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Internal function that builds a `StereoWidth` object.
+pub fn stereo_width<I>(input: I, width: f32) -> StereoWidth<I>
+where
+    I: Source,
+{
+    StereoWidth {
+        input,
+        width,
+        right: None,
+    }
+}
+
+/// Widens or narrows the stereo image using mid-side processing.
+///
+/// Each frame is split into its mid `(L + R) / 2` and side `(L - R) / 2` components. The side
+/// component is multiplied by the width before converting back to left and right. A width of
+/// `0.0` collapses the sound to mono, `1.0` leaves it unchanged and larger values make it wider.
+///
+/// Only stereo is processed, sources with any other channel count pass through unchanged.
+#[derive(Clone, Debug)]
+pub struct StereoWidth<I> {
+    input: I,
+    width: f32,
+    // The right sample of the current frame, computed together with the left one.
+    right: Option<Sample>,
+}
+
+impl<I> StereoWidth<I> {
+    /// Modifies the stereo width.
+    #[inline]
+    pub fn set_width(&mut self, width: f32) {
+        self.width = width;
+    }
+
+    /// Returns the stereo width.
+    #[inline]
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> Iterator for StereoWidth<I>
+where
+    I: Source,
+{
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        if let Some(right) = self.right.take() {
+            return Some(right);
+        }
+
+        let stereo = self.input.channels().get() == 2;
+        let left = self.input.next()?;
+        if !stereo {
+            return Some(left);
+        }
+        let Some(right) = self.input.next() else {
+            return Some(left);
+        };
+
+        let mid = (left + right) * 0.5;
+        let side = (left - right) * 0.5 * self.width;
+        self.right = Some(mid - side);
+        Some(mid + side)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let pending = self.right.is_some() as usize;
+        let (lower, upper) = self.input.size_hint();
+        (lower + pending, upper.map(|upper| upper + pending))
+    }
+}
+
+impl<I> ExactSizeIterator for StereoWidth<I> where I: Source + ExactSizeIterator {}
+
+impl<I> Source for StereoWidth<I>
+where
+    I: Source,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        let pending = self.right.is_some() as usize;
+        self.input.current_span_len().map(|len| len + pending)
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.right = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::Source;
+
+    fn stereo() -> SamplesBuffer {
+        SamplesBuffer::new(nz!(2), nz!(48000), vec![1.0, 0.0, 0.5, -0.5])
+    }
+
+    #[test]
+    fn zero_width_is_mono() {
+        let output: Vec<f32> = stereo().stereo_width(0.0).collect();
+        assert_eq!(output, [0.5, 0.5, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn unit_width_is_unchanged() {
+        let output: Vec<f32> = stereo().stereo_width(1.0).collect();
+        assert_eq!(output, [1.0, 0.0, 0.5, -0.5]);
+    }
+
+    #[test]
+    fn widens_and_ignores_mono() {
+        let output: Vec<f32> = stereo().stereo_width(2.0).collect();
+        assert_eq!(output, [1.5, -0.5, 1.0, -1.0]);
+
+        let mono = SamplesBuffer::new(nz!(1), nz!(48000), vec![1.0, 0.0, 0.5]);
+        assert_eq!(mono.stereo_width(0.0).collect::<Vec<_>>(), [1.0, 0.0, 0.5]);
+    }
+}
@@ -0,0 +1,211 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::{SeekError, UniformSourceIterator};
+use crate::common::{ChannelCount, SampleRate};
+use crate::{Sample, Source};
+
+/// Builds a source that plays `initial` until it is switched to another source.
+///
+/// Use the returned [`SwitchHandle`] to crossfade to a new source while playing, for example
+/// to make music follow the state of a game. The output keeps the channel count and sample
+/// rate of `initial`, sources switched to are converted to it.
+pub fn switchable<S>(initial: S) -> (SwitchHandle, Switchable)
+where
+    S: Source + Send + 'static,
+{
+    let handle = SwitchHandle(Arc::new(Inner {
+        has_pending: AtomicBool::new(false),
+        pending: Mutex::new(None),
+        channels: initial.channels(),
+        sample_rate: initial.sample_rate(),
+    }));
+    let output = Switchable {
+        current: handle.convert(initial),
+        fading_out: None,
+        fade_frames: 0,
+        fade_pos: 0,
+        channel: 0,
+        handle: handle.clone(),
+    };
+    (handle, output)
+}
+
+/// Controls a [`Switchable`] source from any thread.
+#[derive(Clone)]
+pub struct SwitchHandle(Arc<Inner>);
+
+struct Inner {
+    has_pending: AtomicBool,
+    pending: Mutex<Option<(Box<dyn Source + Send>, Duration)>>,
+    channels: ChannelCount,
+    sample_rate: SampleRate,
+}
+
+impl SwitchHandle {
+    /// Crossfades from the current source to `source` over `crossfade`.
+    ///
+    /// The switch happens at the start of the next frame the [`Switchable`] plays. This never
+    /// blocks the audio thread, if it is called again before that only the last source is
+    /// switched to.
+    pub fn switch_to<S>(&self, source: S, crossfade: Duration)
+    where
+        S: Source + Send + 'static,
+    {
+        let source = self.convert(source);
+        *self.0.pending.lock().unwrap() = Some((source, crossfade));
+        self.0.has_pending.store(true, Ordering::SeqCst);
+    }
+
+    fn convert<S>(&self, source: S) -> Box<dyn Source + Send>
+    where
+        S: Source + Send + 'static,
+    {
+        Box::new(UniformSourceIterator::new(
+            source,
+            self.0.channels,
+            self.0.sample_rate,
+        ))
+    }
+}
+
+/// A source that can be switched to another source while playing, see [`switchable`].
+///
+/// It ends when the source it switched to last ends.
+pub struct Switchable {
+    current: Box<dyn Source + Send>,
+    fading_out: Option<Box<dyn Source + Send>>,
+    fade_frames: u64,
+    // Number of frames of the crossfade played so far.
+    fade_pos: u64,
+    // The channel of the next sample.
+    channel: u16,
+    handle: SwitchHandle,
+}
+
+impl Switchable {
+    fn start_pending_switch(&mut self) {
+        // Try again on the next frame rather than block the audio thread.
+        let Ok(mut pending) = self.handle.0.pending.try_lock() else {
+            return;
+        };
+        self.handle.0.has_pending.store(false, Ordering::SeqCst);
+        let Some((source, crossfade)) = pending.take() else {
+            return;
+        };
+        self.fading_out = Some(std::mem::replace(&mut self.current, source));
+        self.fade_frames =
+            (crossfade.as_secs_f64() * self.handle.0.sample_rate.get() as f64).round() as u64;
+        self.fade_pos = 0;
+    }
+}
+
+impl Iterator for Switchable {
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        if self.channel == 0 && self.handle.0.has_pending.load(Ordering::SeqCst) {
+            self.start_pending_switch();
+        }
+
+        let current = self.current.next();
+        let sample = match &mut self.fading_out {
+            Some(fading_out) if self.fade_pos < self.fade_frames => {
+                let gain = self.fade_pos as f32 / self.fade_frames as f32;
+                let old = fading_out.next();
+                if current.is_none() && old.is_none() {
+                    return None;
+                }
+                current.unwrap_or(0.0) * gain + old.unwrap_or(0.0) * (1.0 - gain)
+            }
+            _ => {
+                self.fading_out = None;
+                current?
+            }
+        };
+
+        self.channel += 1;
+        if self.channel == self.handle.0.channels.get() {
+            self.channel = 0;
+            if self.fading_out.is_some() {
+                self.fade_pos += 1;
+            }
+        }
+        Some(sample)
+    }
+}
+
+impl Source for Switchable {
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        None
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        self.handle.0.channels
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.handle.0.sample_rate
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Seeks the current source, ending any crossfade.
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.current.try_seek(pos)?;
+        self.fading_out = None;
+        self.channel = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::switchable;
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::source::SineWave;
+    use crate::Source;
+
+    #[test]
+    fn crossfades_to_new_source() {
+        let a = SamplesBuffer::new(nz!(2), nz!(1000), vec![1.0; 200]);
+        let b = SamplesBuffer::new(nz!(2), nz!(1000), vec![-1.0; 200]);
+        let (handle, mut output) = switchable(a);
+        assert!(output.by_ref().take(20).all(|s| s == 1.0));
+
+        handle.switch_to(b, Duration::from_millis(10));
+        let fade: Vec<f32> = output.by_ref().take(20).collect();
+        assert_eq!(fade[..2], [1.0, 1.0]);
+        // Both channels follow the same gain.
+        assert!(fade.chunks(2).all(|frame| frame[0] == frame[1]));
+        assert!(fade.windows(2).all(|w| w[1] <= w[0]), "{fade:?}");
+
+        let after: Vec<f32> = output.collect();
+        assert_eq!(after.len(), 180);
+        assert!(after.iter().all(|s| *s == -1.0));
+    }
+
+    #[test]
+    fn converts_switched_source() {
+        let (handle, mut output) = switchable(SineWave::new(440.0));
+        handle.switch_to(
+            SamplesBuffer::new(nz!(2), nz!(22050), vec![0.5; 100]),
+            Duration::ZERO,
+        );
+        assert_eq!(output.channels(), nz!(1));
+        assert_eq!(output.sample_rate(), nz!(48000));
+        assert!(output.by_ref().skip(10).take(50).all(|s| s == 0.5));
+    }
+}
@@ -23,6 +23,7 @@ where
 #[derive(Clone, Debug)]
 enum DurationFilter {
     FadeOut,
+    FadeOutEnd(Duration),
 }
 impl DurationFilter {
     fn apply<I: Iterator>(&self, sample: Sample, parent: &TakeDuration<I>) -> Sample {
@@ -32,6 +33,18 @@ impl DurationFilter {
                 let total = parent.requested_duration.as_millis() as f32;
                 sample * remaining / total
             }
+            DurationFilter::FadeOutEnd(fade) => {
+                // The last sample is emitted with one sample duration remaining, subtract
+                // it so the fade reaches zero exactly.
+                let remaining = parent
+                    .remaining_duration
+                    .saturating_sub(parent.duration_per_sample);
+                if remaining >= *fade {
+                    sample
+                } else {
+                    sample * remaining.as_secs_f32() / fade.as_secs_f32()
+                }
+            }
         }
     }
 }
@@ -87,6 +100,12 @@ where
         self.filter = Some(DurationFilter::FadeOut);
     }
 
+    /// Make the truncated source end with a FadeOut. The fadeout only covers the
+    /// last `fade` of the take source.
+    pub fn set_filter_fadeout_end(&mut self, fade: Duration) {
+        self.filter = Some(DurationFilter::FadeOutEnd(fade));
+    }
+
     /// Remove any filter set.
     pub fn clear_filter(&mut self) {
         self.filter = None;
@@ -110,7 +129,7 @@ where
             }
         }
 
-        if self.remaining_duration <= self.duration_per_sample {
+        if self.remaining_duration < self.duration_per_sample {
             None
         } else if let Some(sample) = self.input.next() {
             let sample = match &self.filter {
@@ -135,8 +154,12 @@ where
 {
     #[inline]
     fn current_span_len(&self) -> Option<usize> {
-        let remaining_nanos = self.remaining_duration.as_secs() * NANOS_PER_SEC
-            + self.remaining_duration.subsec_nanos() as u64;
+        // Saturates, the remaining duration may be as long as `Duration::MAX`.
+        let remaining_nanos = self
+            .remaining_duration
+            .as_secs()
+            .saturating_mul(NANOS_PER_SEC)
+            .saturating_add(self.remaining_duration.subsec_nanos() as u64);
         let nanos_per_sample = self.duration_per_sample.as_secs() * NANOS_PER_SEC
             + self.duration_per_sample.subsec_nanos() as u64;
         let remaining_samples = (remaining_nanos / nanos_per_sample) as usize;
@@ -175,3 +198,32 @@ where
         self.input.try_seek(pos)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::source::Source;
+
+    #[test]
+    fn take_duration_exact_length() {
+        let source = SamplesBuffer::new(nz!(2), nz!(1000), vec![1.0; 2000]);
+        let taken = source.take_duration(Duration::from_millis(500));
+        assert_eq!(taken.count(), 1000);
+    }
+
+    #[test]
+    fn take_duration_faded_ramps_to_zero() {
+        let source = SamplesBuffer::new(nz!(1), nz!(1000), vec![1.0; 2000]);
+        let taken: Vec<_> = source
+            .take_duration_faded(Duration::from_millis(500), Duration::from_millis(100))
+            .collect();
+
+        assert_eq!(taken.len(), 500);
+        assert!(taken[..400].iter().all(|s| *s == 1.0));
+        assert!(taken[400..].windows(2).all(|w| w[1] < w[0]));
+        assert_eq!(taken.last(), Some(&0.0));
+    }
+}
@@ -0,0 +1,159 @@
+use std::time::Duration;
+
+use super::SeekError;
+use crate::common::{ChannelCount, SampleRate};
+use crate::math::nz;
+use crate::{Sample, Source};
+
+/// Internal function that builds a `ToMono` object.
+pub fn to_mono<I>(input: I, weights: Option<(f32, f32)>) -> ToMono<I>
+where
+    I: Source,
+{
+    ToMono {
+        input,
+        weights: weights.unwrap_or((0.5, 0.5)),
+    }
+}
+
+/// Folds a source down to a single channel, see [`Source::to_mono`].
+#[derive(Clone, Debug)]
+pub struct ToMono<I> {
+    input: I,
+    // Weights of the left and right channel of stereo input.
+    weights: (f32, f32),
+}
+
+impl<I> ToMono<I> {
+    /// Returns a reference to the inner source.
+    #[inline]
+    pub fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Returns a mutable reference to the inner source.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.input
+    }
+
+    /// Returns the inner source.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> Iterator for ToMono<I>
+where
+    I: Source,
+{
+    type Item = Sample;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample> {
+        let channels = self.input.channels().get();
+        let first = self.input.next()?;
+        match channels {
+            1 => Some(first),
+            2 => {
+                let second = self.input.next().unwrap_or(0.0);
+                Some(first * self.weights.0 + second * self.weights.1)
+            }
+            _ => {
+                let mut sum = first;
+                for _ in 1..channels {
+                    sum += self.input.next().unwrap_or(0.0);
+                }
+                Some(sum / channels as Sample)
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let channels = self.input.channels().get() as usize;
+        let (lower, upper) = self.input.size_hint();
+        (
+            lower.div_ceil(channels),
+            upper.map(|upper| upper.div_ceil(channels)),
+        )
+    }
+}
+
+impl<I> ExactSizeIterator for ToMono<I> where I: Source + ExactSizeIterator {}
+
+impl<I> Source for ToMono<I>
+where
+    I: Source,
+{
+    #[inline]
+    fn current_span_len(&self) -> Option<usize> {
+        let channels = self.input.channels().get() as usize;
+        self.input
+            .current_span_len()
+            .map(|len| len.div_ceil(channels))
+    }
+
+    #[inline]
+    fn channels(&self) -> ChannelCount {
+        nz!(1)
+    }
+
+    #[inline]
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    #[inline]
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    #[inline]
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::source::SineWave;
+    use crate::Source;
+
+    #[test]
+    fn hard_left_folds_to_half_amplitude() {
+        let left_only = SineWave::new(440.0)
+            .take(4800)
+            .collect::<Vec<_>>()
+            .into_iter()
+            .flat_map(|s| [s, 0.0])
+            .collect::<Vec<_>>();
+        let mono = SamplesBuffer::new(nz!(2), nz!(48000), left_only.clone()).to_mono(None);
+        assert_eq!(mono.channels(), nz!(1));
+        assert_eq!(mono.size_hint(), (4800, Some(4800)));
+        let folded: Vec<f32> = mono.collect();
+        for (mono, left) in folded.iter().zip(left_only.iter().step_by(2)) {
+            assert_eq!(*mono, left * 0.5);
+        }
+    }
+
+    #[test]
+    fn weights_and_other_channel_counts() {
+        let stereo = SamplesBuffer::new(nz!(2), nz!(1000), vec![1.0, 0.5, -1.0, 1.0]);
+        let weighted: Vec<f32> = stereo.to_mono(Some((1.0, 2.0))).collect();
+        assert_eq!(weighted, [2.0, 1.0]);
+
+        let mono = SamplesBuffer::new(nz!(1), nz!(1000), vec![0.25, -0.75]);
+        assert_eq!(
+            mono.to_mono(Some((3.0, 3.0))).collect::<Vec<_>>(),
+            [0.25, -0.75]
+        );
+
+        let surround = SamplesBuffer::new(nz!(3), nz!(1000), vec![0.3, 0.6, 0.9]);
+        let folded: Vec<f32> = surround.to_mono(None).collect();
+        assert!((folded[0] - 0.6).abs() < 1e-6);
+    }
+}
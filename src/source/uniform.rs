@@ -2,7 +2,7 @@ use std::cmp;
 use std::time::Duration;
 
 use super::SeekError;
-use crate::common::{ChannelCount, SampleRate};
+use crate::common::{ChannelCount, ChannelLayout, SampleRate};
 use crate::conversions::{ChannelCountConverter, SampleRateConverter};
 use crate::Source;
 
@@ -17,7 +17,11 @@ where
     I: Source,
 {
     inner: Option<ChannelCountConverter<SampleRateConverter<Take<I>>>>,
+    // Whether the current span already has the target format, its samples then bypass the
+    // converters.
+    passthrough: bool,
     target_channels: ChannelCount,
+    target_layout: Option<ChannelLayout>,
     target_sample_rate: SampleRate,
     total_duration: Option<Duration>,
 }
@@ -33,13 +37,49 @@ where
         input: I,
         target_channels: ChannelCount,
         target_sample_rate: SampleRate,
+    ) -> UniformSourceIterator<I> {
+        UniformSourceIterator::build(input, target_channels, None, target_sample_rate)
+    }
+
+    /// Wrap a `Source` and lazily convert its samples to a specific sample-rate, mixing its
+    /// channels to the speakers of `target_layout`. See
+    /// [`ChannelCountConverter::with_layout`] for how channels are mixed.
+    #[inline]
+    pub fn with_layout(
+        input: I,
+        target_layout: ChannelLayout,
+        target_sample_rate: SampleRate,
+    ) -> UniformSourceIterator<I> {
+        let target_channels = target_layout.channel_count();
+        UniformSourceIterator::build(
+            input,
+            target_channels,
+            Some(target_layout),
+            target_sample_rate,
+        )
+    }
+
+    fn build(
+        input: I,
+        target_channels: ChannelCount,
+        target_layout: Option<ChannelLayout>,
+        target_sample_rate: SampleRate,
     ) -> UniformSourceIterator<I> {
         let total_duration = input.total_duration();
-        let input = UniformSourceIterator::bootstrap(input, target_channels, target_sample_rate);
+        let passthrough =
+            input.channels() == target_channels && input.sample_rate() == target_sample_rate;
+        let input = UniformSourceIterator::bootstrap(
+            input,
+            target_channels,
+            target_layout,
+            target_sample_rate,
+        );
 
         UniformSourceIterator {
             inner: Some(input),
+            passthrough,
             target_channels,
+            target_layout,
             target_sample_rate,
             total_duration,
         }
@@ -49,6 +89,7 @@ where
     fn bootstrap(
         input: I,
         target_channels: ChannelCount,
+        target_layout: Option<ChannelLayout>,
         target_sample_rate: SampleRate,
     ) -> ChannelCountConverter<SampleRateConverter<Take<I>>> {
         // Limit the span length to something reasonable
@@ -63,7 +104,21 @@ where
         };
         let input =
             SampleRateConverter::new(input, from_sample_rate, target_sample_rate, from_channels);
-        ChannelCountConverter::new(input, from_channels, target_channels)
+        match target_layout {
+            Some(layout) => ChannelCountConverter::with_layout(input, from_channels, layout),
+            None => ChannelCountConverter::new(input, from_channels, target_channels),
+        }
+    }
+
+    /// Returns the next sample of the current span.
+    #[inline]
+    fn next_in_span(&mut self) -> Option<I::Item> {
+        let inner = self.inner.as_mut().unwrap();
+        if self.passthrough {
+            inner.inner_mut().inner_mut().next()
+        } else {
+            inner.next()
+        }
     }
 }
 
@@ -75,18 +130,22 @@ where
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(value) = self.inner.as_mut().unwrap().next() {
+        if let Some(value) = self.next_in_span() {
             return Some(value);
         }
 
         let input = self.inner.take().unwrap().into_inner().into_inner().iter;
 
-        let mut input =
-            UniformSourceIterator::bootstrap(input, self.target_channels, self.target_sample_rate);
-
-        let value = input.next();
+        self.passthrough = input.channels() == self.target_channels
+            && input.sample_rate() == self.target_sample_rate;
+        let input = UniformSourceIterator::bootstrap(
+            input,
+            self.target_channels,
+            self.target_layout,
+            self.target_sample_rate,
+        );
         self.inner = Some(input);
-        value
+        self.next_in_span()
     }
 
     #[inline]
@@ -127,6 +186,20 @@ where
             Ok(())
         }
     }
+
+    #[inline]
+    fn try_seek_samples(&mut self, frame: u64) -> Result<(), SeekError> {
+        let Some(input) = self.inner.as_mut() else {
+            return Ok(());
+        };
+        let source = input.inner_mut().inner_mut().inner_mut();
+        if source.sample_rate() == self.target_sample_rate {
+            source.try_seek_samples(frame)
+        } else {
+            let pos = crate::math::frame_to_duration(frame, self.target_sample_rate);
+            source.try_seek(pos)
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -182,3 +255,44 @@ where
 }
 
 impl<I> ExactSizeIterator for Take<I> where I: ExactSizeIterator {}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::Source;
+
+    #[test]
+    fn reformat_mono_48k_to_stereo_44k1() {
+        let input = SamplesBuffer::new(nz!(1), nz!(48000), vec![0.5; 4800]);
+        let output = input.reformat(nz!(2), nz!(44100));
+
+        assert_eq!(output.channels(), nz!(2));
+        assert_eq!(output.sample_rate(), nz!(44100));
+        // 0.1 s of mono 48 kHz becomes 0.1 s of stereo 44.1 kHz.
+        let len = output.count();
+        assert!((len as i64 - 8820).abs() <= 2, "unexpected length {len}");
+    }
+
+    #[test]
+    fn reformat_is_identity_when_format_matches() {
+        let data = vec![1.0, -1.0, 0.5, -0.5, 0.25, -0.25];
+        let input = SamplesBuffer::new(nz!(2), nz!(44100), data.clone());
+        let output: Vec<_> = input.reformat(nz!(2), nz!(44100)).collect();
+        assert_eq!(output, data);
+    }
+
+    #[test]
+    fn spans_that_match_pass_through() {
+        let first = vec![1.0, -1.0, 0.5, -0.5];
+        let second = vec![0.25, -0.25];
+        let spans = [
+            SamplesBuffer::new(nz!(2), nz!(44100), first.clone()),
+            SamplesBuffer::new(nz!(2), nz!(44100), second.clone()),
+        ];
+        let output: Vec<_> = crate::source::from_iter(spans)
+            .reformat(nz!(2), nz!(44100))
+            .collect();
+        assert_eq!(output, [first, second].concat());
+    }
+}
@@ -55,6 +55,40 @@ impl SpatialSink {
         self.positions.lock().unwrap().right_ear = pos;
     }
 
+    /// Moves the listener to `pos`, the ears keep their position relative to it.
+    ///
+    /// The listener is positioned at the center between the two ears.
+    pub fn set_listener_position(&self, pos: [f32; 3]) {
+        let mut positions = self.positions.lock().unwrap();
+        let center = midpoint(positions.left_ear, positions.right_ear);
+        let offset = sub(pos, center);
+        positions.left_ear = add(positions.left_ear, offset);
+        positions.right_ear = add(positions.right_ear, offset);
+    }
+
+    /// Turns the listener so it faces `forward` with the top of its head towards `up`.
+    ///
+    /// The ears are rotated around the listener position keeping their distance. Coordinates
+    /// are right handed: facing `[0.0, 0.0, -1.0]` with up `[0.0, 1.0, 0.0]` puts the right
+    /// ear towards positive x. Vectors that are zero or parallel are ignored.
+    pub fn set_listener_orientation(&self, forward: [f32; 3], up: [f32; 3]) {
+        let right = cross(forward, up);
+        let length = dot(right, right).sqrt();
+        if length == 0.0 || !length.is_finite() {
+            return;
+        }
+        let mut positions = self.positions.lock().unwrap();
+        let center = midpoint(positions.left_ear, positions.right_ear);
+        let half_width = dot(
+            sub(positions.right_ear, center),
+            sub(positions.right_ear, center),
+        )
+        .sqrt();
+        let offset = right.map(|v| v / length * half_width);
+        positions.left_ear = sub(center, offset);
+        positions.right_ear = add(center, offset);
+    }
+
     /// Appends a sound to the queue of sounds to play.
     #[inline]
     pub fn append<S>(&self, source: S)
@@ -63,13 +97,16 @@ impl SpatialSink {
         f32: FromSample<S::Item>,
     {
         let positions = self.positions.clone();
-        let pos_lock = self.positions.lock().unwrap();
-        let source = Spatial::new(
-            source,
-            pos_lock.emitter_position,
-            pos_lock.left_ear,
-            pos_lock.right_ear,
-        )
+        // The lock must be released before appending, the sink reads the first samples.
+        let source = {
+            let pos_lock = self.positions.lock().unwrap();
+            Spatial::new(
+                source,
+                pos_lock.emitter_position,
+                pos_lock.left_ear,
+                pos_lock.right_ear,
+            )
+        }
         .periodic_access(Duration::from_millis(10), move |i| {
             let pos = positions.lock().unwrap();
             i.set_positions(pos.emitter_position, pos.left_ear, pos.right_ear);
@@ -220,3 +257,78 @@ impl SpatialSink {
         self.sink.get_pos()
     }
 }
+
+fn add(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn midpoint(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    add(a, b).map(|v| v / 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SpatialSink;
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::mixer::mixer;
+    use crate::source::Source;
+
+    fn constant() -> SamplesBuffer {
+        SamplesBuffer::new(nz!(1), nz!(48000), vec![1.0; 48000])
+    }
+
+    /// Level of the left and right channel 20 ms from now, once the positions took effect.
+    fn levels(output: &mut impl Source) -> [f32; 2] {
+        let frame: Vec<f32> = output.by_ref().skip(1920).take(2).collect();
+        [frame[0], frame[1]]
+    }
+
+    #[test]
+    fn moving_emitter_left_raises_left_channel() {
+        let (mixer, mut output) = mixer(nz!(2), nz!(48000));
+        let sink =
+            SpatialSink::connect_new(&mixer, [0.0, 0.0, -1.0], [-0.1, 0.0, 0.0], [0.1, 0.0, 0.0]);
+        sink.append(constant());
+        let [left, right] = levels(&mut output);
+        assert!((left - right).abs() < 1e-3, "{left} {right}");
+
+        sink.set_emitter_position([-1.0, 0.0, -0.2]);
+        let [left, right] = levels(&mut output);
+        assert!(left > right * 1.5, "{left} {right}");
+    }
+
+    #[test]
+    fn listener_orientation_rotates_ears() {
+        let (mixer, mut output) = mixer(nz!(2), nz!(48000));
+        let sink =
+            SpatialSink::connect_new(&mixer, [-1.0, 0.0, 0.5], [-0.1, 0.0, 0.0], [0.1, 0.0, 0.0]);
+        sink.append(constant());
+        sink.set_listener_position([0.0, 0.0, 0.5]);
+        // Facing the emitter on the left, both ears are equally far away.
+        sink.set_listener_orientation([-1.0, 0.0, 0.0], [0.0, 1.0, 0.0]);
+        let [left, right] = levels(&mut output);
+        assert!((left - right).abs() < 1e-3, "{left} {right}");
+
+        // Facing positive z the emitter is on the right.
+        sink.set_listener_orientation([0.0, 0.0, 1.0], [0.0, 1.0, 0.0]);
+        let [left, right] = levels(&mut output);
+        assert!(right > left * 1.5, "{left} {right}");
+    }
+}
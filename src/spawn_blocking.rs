@@ -0,0 +1,107 @@
+//! Runs blocking work on a separate thread and exposes the result as a future.
+//!
+//! This does not depend on any async runtime. Every call spawns a short-lived
+//! thread, which is fine for the rare and slow operations it is used for
+//! (device enumeration, querying configs).
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+struct Shared<T> {
+    result: Option<std::thread::Result<T>>,
+    waker: Option<Waker>,
+}
+
+/// Future returned by [`spawn_blocking`].
+pub(crate) struct SpawnBlocking<T> {
+    shared: Arc<Mutex<Shared<T>>>,
+}
+
+/// Runs `f` on a new thread. The returned future resolves once `f` returns.
+///
+/// If `f` panics the panic is resumed on the thread polling the future.
+pub(crate) fn spawn_blocking<F, T>(f: F) -> SpawnBlocking<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let shared = Arc::new(Mutex::new(Shared {
+        result: None,
+        waker: None,
+    }));
+
+    let thread_shared = shared.clone();
+    std::thread::spawn(move || {
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f));
+        let mut shared = thread_shared.lock().unwrap();
+        shared.result = Some(result);
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    });
+
+    SpawnBlocking { shared }
+}
+
+impl<T> Future for SpawnBlocking<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut shared = self.shared.lock().unwrap();
+        match shared.result.take() {
+            Some(Ok(value)) => Poll::Ready(value),
+            Some(Err(panic)) => std::panic::resume_unwind(panic),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::spawn_blocking;
+    use std::future::Future;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+    use std::thread::Thread;
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    /// Polls `future` to completion on the current thread, a minimal runtime for tests.
+    pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(value) => return value,
+                Poll::Pending => std::thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    fn resolves_with_result() {
+        let value = block_on(spawn_blocking(|| {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            42
+        }));
+        assert_eq!(value, 42);
+    }
+
+    #[test]
+    #[should_panic(expected = "boom")]
+    fn propagates_panic() {
+        block_on(spawn_blocking(|| panic!("boom")));
+    }
+}
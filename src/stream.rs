@@ -5,17 +5,24 @@
 //!
 //! There is also a convenience function `play` for using that output mixer to
 //! play a single sound.
-use crate::common::{assert_error_traits, ChannelCount, SampleRate};
+use crate::common::{assert_error_traits, ChannelCount, ChannelLayout, SampleRate};
 use crate::math::nz;
-use crate::mixer::{mixer, Mixer};
+use crate::mixer::{mixer, mixer_with_layout, Mixer};
 use crate::sink::Sink;
+#[cfg(feature = "dither")]
+use crate::source::DitherAlgorithm;
+#[cfg(feature = "async")]
+use crate::spawn_blocking::spawn_blocking;
 use crate::{decoder, Source};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use cpal::{BufferSize, Sample, SampleFormat, StreamConfig, I24};
+use cpal::{BufferSize, FromSample, Sample, SampleFormat, StreamConfig, I24};
 use std::fmt;
 use std::io::{Read, Seek};
 use std::marker::Sync;
 use std::num::NonZero;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 const HZ_44100: SampleRate = nz!(44_100);
 
@@ -44,9 +51,15 @@ const HZ_44100: SampleRate = nz!(44_100);
 /// ```
 pub struct OutputStream {
     config: OutputStreamConfig,
+    device_name: Option<String>,
     mixer: Mixer,
+    frames_played: Arc<AtomicU64>,
+    // The bits of an `f32`.
+    master_volume: Arc<AtomicU32>,
+    fade: Arc<Mutex<Fade>>,
+    playing: AtomicBool,
     log_on_drop: bool,
-    _stream: cpal::Stream,
+    stream: cpal::Stream,
 }
 
 impl OutputStream {
@@ -60,6 +73,131 @@ impl OutputStream {
         &self.config
     }
 
+    /// The sample rate the device plays at.
+    ///
+    /// This is the rate that was negotiated, which differs from the one asked for when
+    /// [`OutputStreamBuilder::open_stream_or_fallback`] fell back to another config.
+    pub fn sample_rate(&self) -> SampleRate {
+        self.config.sample_rate
+    }
+
+    /// The number of channels the device plays, see [`OutputStream::sample_rate`].
+    pub fn channels(&self) -> ChannelCount {
+        self.config.channel_count
+    }
+
+    /// The config the cpal stream was built with, for querying driver specific properties.
+    pub fn stream_config(&self) -> StreamConfig {
+        StreamConfig::from(&self.config)
+    }
+
+    /// The name of the device the stream plays on, `None` if the backend can not provide it.
+    pub fn device_name(&self) -> Option<String> {
+        self.device_name.clone()
+    }
+
+    /// Total number of frames written to the device since the stream was opened.
+    ///
+    /// A frame holds one sample for each channel. This includes the silence written while
+    /// nothing is playing, so dividing by the sample rate gives the playback clock of the
+    /// device independent of wall time.
+    pub fn frames_played(&self) -> u64 {
+        self.frames_played.load(Ordering::Relaxed)
+    }
+
+    /// Total number of samples the device requested since the stream was opened, that is
+    /// [`OutputStream::frames_played`] times the channel count.
+    pub fn samples_requested(&self) -> u64 {
+        self.frames_played() * self.config.channel_count.get() as u64
+    }
+
+    /// A clock that advances with the samples written to the device.
+    ///
+    /// The clock can be cloned and moved to other threads, it keeps working after the stream
+    /// is dropped but no longer advances.
+    pub fn playback_clock(&self) -> PlaybackClock {
+        PlaybackClock {
+            frames_played: self.frames_played.clone(),
+            sample_rate: self.config.sample_rate,
+        }
+    }
+
+    /// Gets the volume applied to everything played on the stream.
+    pub fn master_volume(&self) -> f32 {
+        f32::from_bits(self.master_volume.load(Ordering::Relaxed))
+    }
+
+    /// Changes the volume applied to everything played on the stream, after mixing.
+    ///
+    /// The value `1.0` is the "normal" volume, it multiplies the volume of each
+    /// [`Sink`]. The volume moves to the new value over 5 ms to avoid clicks.
+    pub fn set_master_volume(&self, value: f32) {
+        self.master_volume.store(value.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Suspends the device, for example to save power while nothing needs to play.
+    ///
+    /// Unlike [`Sink::pause`] this stops the whole stream: no samples are requested from the
+    /// mixer and the [`PlaybackClock`] stands still until [`OutputStream::play`] is called.
+    /// Some platforms do not support pausing a stream.
+    pub fn pause(&self) -> Result<(), StreamError> {
+        self.stream.pause().map_err(StreamError::PauseStreamError)?;
+        self.playing.store(false, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Resumes a stream suspended with [`OutputStream::pause`].
+    ///
+    /// The stream plays at full volume at once, also when it was paused with
+    /// [`OutputStream::pause_faded`].
+    pub fn play(&self) -> Result<(), StreamError> {
+        *self.fade.lock().unwrap() = Fade::to(1.0, Duration::ZERO);
+        self.stream.play().map_err(StreamError::PlayStreamError)?;
+        self.playing.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Fades everything out over `fade` and then suspends the device like
+    /// [`OutputStream::pause`], which avoids the click of stopping in the middle of a sound.
+    ///
+    /// Blocks until the device has played the fade. The fade is applied after the master
+    /// volume, [`OutputStream::master_volume`] and the state of each [`Sink`] do not change.
+    pub fn pause_faded(&self, fade: Duration) -> Result<(), StreamError> {
+        *self.fade.lock().unwrap() = Fade::to(0.0, fade);
+        // The fade starts at the next update of the master volume.
+        self.wait_for_device(fade + 2 * MASTER_VOLUME_PERIOD);
+        self.pause()
+    }
+
+    /// Resumes a suspended stream and fades everything in over `fade`, see
+    /// [`OutputStream::pause_faded`].
+    pub fn play_faded(&self, fade: Duration) -> Result<(), StreamError> {
+        *self.fade.lock().unwrap() = Fade {
+            from: Some(0.0),
+            to: 1.0,
+            over: fade,
+        };
+        self.stream.play().map_err(StreamError::PlayStreamError)?;
+        self.playing.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Waits until the device played `duration` more audio. Gives up after a while in case
+    /// the device stopped requesting samples.
+    fn wait_for_device(&self, duration: Duration) {
+        let frames = (duration.as_secs_f64() * self.config.sample_rate.get() as f64).ceil();
+        let target = self.frames_played() + frames as u64;
+        let deadline = Instant::now() + 4 * duration + Duration::from_millis(100);
+        while self.frames_played() < target && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    /// Returns `false` while the stream is suspended by [`OutputStream::pause`].
+    pub fn is_playing(&self) -> bool {
+        self.playing.load(Ordering::Relaxed)
+    }
+
     /// When [`OutputStream`] is dropped a message is logged to stderr or
     /// emitted through tracing if the tracing feature is enabled.
     pub fn log_on_drop(&mut self, enabled: bool) {
@@ -67,6 +205,32 @@ impl OutputStream {
     }
 }
 
+/// Sample accurate clock of an [`OutputStream`], see [`OutputStream::playback_clock`].
+///
+/// Unlike wall time the clock only advances while the device consumes samples, it stands still
+/// while the stream is stopped. This makes it suitable for scheduling sounds relative to what
+/// has actually been played.
+#[derive(Clone, Debug)]
+pub struct PlaybackClock {
+    frames_played: Arc<AtomicU64>,
+    sample_rate: SampleRate,
+}
+
+impl PlaybackClock {
+    /// The number of samples per channel written to the device since the stream was opened.
+    pub fn samples(&self) -> u64 {
+        self.frames_played.load(Ordering::Relaxed)
+    }
+
+    /// The time it takes to play the samples written to the device so far.
+    pub fn elapsed(&self) -> Duration {
+        let samples = self.samples();
+        let rate = self.sample_rate.get() as u64;
+        let nanos = (samples % rate) * 1_000_000_000 / rate;
+        Duration::new(samples / rate, nanos as u32)
+    }
+}
+
 impl Drop for OutputStream {
     fn drop(&mut self) {
         if self.log_on_drop && !std::thread::panicking() {
@@ -82,36 +246,61 @@ impl fmt::Debug for OutputStream {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("OutputStream")
             .field("config", &self.config)
+            .field("device_name", &self.device_name)
             .finish_non_exhaustive()
     }
 }
 
 /// Describes the output stream's configuration
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct OutputStreamConfig {
     channel_count: ChannelCount,
+    channel_layout: Option<ChannelLayout>,
     sample_rate: SampleRate,
     buffer_size: BufferSize,
-    sample_format: SampleFormat,
+    // `None` until a format is chosen, the device's default format is used then.
+    sample_format: Option<SampleFormat>,
+    #[cfg(feature = "dither")]
+    dither: Option<DitherAlgorithm>,
 }
 
 impl Default for OutputStreamConfig {
     fn default() -> Self {
         Self {
             channel_count: nz!(2),
+            channel_layout: None,
             sample_rate: HZ_44100,
             buffer_size: BufferSize::Default,
-            sample_format: SampleFormat::F32,
+            sample_format: None,
+            #[cfg(feature = "dither")]
+            dither: None,
         }
     }
 }
 
 impl OutputStreamConfig {
+    fn from_supported(config: &cpal::SupportedStreamConfig) -> OutputStreamConfig {
+        OutputStreamConfig {
+            channel_count: NonZero::new(config.channels())
+                .expect("no valid cpal config has zero channels"),
+            sample_rate: NonZero::new(config.sample_rate().0)
+                .expect("no valid cpal config has zero sample rate"),
+            sample_format: Some(config.sample_format()),
+            ..Default::default()
+        }
+    }
+
     /// Access the output stream config's channel count.
     pub fn channel_count(&self) -> ChannelCount {
         self.channel_count
     }
 
+    /// Access the output stream config's channel layout, if one was requested with
+    /// [`OutputStreamBuilder::with_channels_layout`].
+    pub fn channel_layout(&self) -> Option<ChannelLayout> {
+        self.channel_layout
+    }
+
     /// Access the output stream config's sample rate.
     pub fn sample_rate(&self) -> SampleRate {
         self.sample_rate
@@ -123,8 +312,34 @@ impl OutputStreamConfig {
     }
 
     /// Access the output stream config's sample format.
+    ///
+    /// The config of an open stream holds the format it uses. Until a format is chosen
+    /// this returns [`SampleFormat::F32`], the format used when the device's default
+    /// format can not be queried.
     pub fn sample_format(&self) -> SampleFormat {
-        self.sample_format
+        self.sample_format.unwrap_or(SampleFormat::F32)
+    }
+
+    /// Access the output stream config's dither algorithm, if dithering was enabled with
+    /// [`OutputStreamBuilder::with_dither`].
+    #[cfg(feature = "dither")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "dither")))]
+    pub fn dither(&self) -> Option<DitherAlgorithm> {
+        self.dither
+    }
+
+    /// Returns this config with the device's default sample format if none was chosen.
+    fn with_device_sample_format(&self, device: &cpal::Device) -> OutputStreamConfig {
+        let sample_format = self.sample_format.or_else(|| {
+            device
+                .default_output_config()
+                .ok()
+                .map(|config| config.sample_format())
+        });
+        OutputStreamConfig {
+            sample_format,
+            ..*self
+        }
     }
 }
 
@@ -162,6 +377,7 @@ where
 {
     device: Option<cpal::Device>,
     config: OutputStreamConfig,
+    prefill: bool,
     error_callback: E,
 }
 
@@ -170,6 +386,7 @@ impl Default for OutputStreamBuilder {
         Self {
             device: None,
             config: OutputStreamConfig::default(),
+            prefill: false,
             error_callback: default_error_callback,
         }
     }
@@ -222,12 +439,126 @@ impl OutputStreamBuilder {
                     .ok_or(original_err)
             })
     }
+
+    /// Async variant of [`OutputStreamBuilder::from_device`].
+    ///
+    /// Querying the device's default config runs on a separate thread so it does not block
+    /// the async runtime.
+    #[cfg(feature = "async")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+    pub async fn from_device_async(
+        device: cpal::Device,
+    ) -> Result<OutputStreamBuilder, StreamError> {
+        spawn_blocking(move || Self::from_device(device)).await
+    }
+
+    /// Async variant of [`OutputStreamBuilder::from_default_device`].
+    ///
+    /// Probing the host for its default device runs on a separate thread so it does not block
+    /// the async runtime.
+    #[cfg(feature = "async")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+    pub async fn from_default_device_async() -> Result<OutputStreamBuilder, StreamError> {
+        spawn_blocking(Self::from_default_device).await
+    }
+
+    /// Async variant of [`OutputStreamBuilder::open_default_stream`].
+    ///
+    /// Device probing, including the configurations tried when the default one fails, runs on
+    /// a separate thread. The stream itself is then opened on the thread polling the returned
+    /// future. See [`OutputStreamBuilder::open_stream_async`] for why.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use rodio::OutputStreamBuilder;
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let stream_handle = OutputStreamBuilder::open_default_stream_async().await?;
+    /// let mixer = stream_handle.mixer();
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+    pub async fn open_default_stream_async() -> Result<OutputStream, StreamError> {
+        let original_err = match Self::from_default_device_async().await {
+            Ok(builder) => match builder.open_stream_async().await {
+                Ok(stream) => return Ok(stream),
+                Err(err) => err,
+            },
+            Err(err) => err,
+        };
+
+        let devices = match output_devices_async().await {
+            Ok(devices) => devices,
+            Err(err) => {
+                #[cfg(feature = "tracing")]
+                tracing::error!("error getting list of output devices: {err}");
+                #[cfg(not(feature = "tracing"))]
+                eprintln!("error getting list of output devices: {err}");
+                return Err(original_err);
+            }
+        };
+        for device in devices {
+            if let Ok(builder) = Self::from_device_async(device).await {
+                if let Ok(stream) = builder.open_stream_or_fallback_async().await {
+                    return Ok(stream);
+                }
+            }
+        }
+        Err(original_err)
+    }
+
+    /// Async variant of [`OutputStreamBuilder::open_stream_or_fallback`], the supported
+    /// configurations are queried on a separate thread.
+    #[cfg(feature = "async")]
+    async fn open_stream_or_fallback_async(self) -> Result<OutputStream, StreamError> {
+        let device = self.device.clone().expect("output device specified");
+        let prefill = self.prefill;
+        let error_callback = self.error_callback;
+
+        let err = match self.open_stream() {
+            Ok(stream) => return Ok(stream),
+            Err(err) => err,
+        };
+        for supported_config in supported_output_configs_async(device.clone()).await? {
+            if let Ok(stream) = OutputStreamBuilder::default()
+                .with_device(device.clone())
+                .with_supported_config(&supported_config)
+                .with_prefill(prefill)
+                .with_error_callback(error_callback)
+                .open_stream()
+            {
+                return Ok(stream);
+            }
+        }
+        Err(err)
+    }
 }
 
 impl<E> OutputStreamBuilder<E>
 where
     E: FnMut(cpal::StreamError) + Send + 'static,
 {
+    /// Sets the default output device of an audio host, such as JACK instead of ALSA, and its
+    /// default parameters. See [`available_hosts`] for the hosts that can be used.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StreamError::HostUnavailable`] if the host can not be used on this system and
+    /// [`StreamError::NoDevice`] if it has no output device.
+    pub fn with_host(self, host: cpal::HostId) -> Result<OutputStreamBuilder<E>, StreamError> {
+        let device = cpal::host_from_id(host)
+            .map_err(StreamError::HostUnavailable)?
+            .default_output_device()
+            .ok_or(StreamError::NoDevice)?;
+        let default_config = device
+            .default_output_config()
+            .map_err(StreamError::DefaultStreamConfigError)?;
+        Ok(self
+            .with_device(device)
+            .with_supported_config(&default_config))
+    }
+
     /// Sets output audio device keeping all existing stream parameters intact.
     /// This method is useful if you want to set other parameters yourself.
     /// To also set parameters that are appropriate for the device use [Self::from_device()] instead.
@@ -240,6 +571,23 @@ where
     pub fn with_channels(mut self, channel_count: ChannelCount) -> OutputStreamBuilder<E> {
         assert!(channel_count.get() > 0);
         self.config.channel_count = channel_count;
+        self.config.channel_layout = None;
+        self
+    }
+
+    /// Sets the output stream's channels to a speaker layout.
+    ///
+    /// This sets the channel count to that of the layout and records the layout in the
+    /// stream's config. Sources played on the stream are mixed to the speakers of the layout,
+    /// see [`ChannelLayout`]. The channels are expected in the order documented at
+    /// [`ChannelLayout::positions`], which is the order used by WAVE files and most operating
+    /// systems. Use [`ChannelLayout::channel_of`] to find the channel of a
+    /// speaker, for example to route a source to the LFE channel with
+    /// [`Source::select_channels`] or
+    /// [`ChannelVolume`](crate::source::ChannelVolume).
+    pub fn with_channels_layout(mut self, layout: ChannelLayout) -> OutputStreamBuilder<E> {
+        self.config.channel_count = layout.channel_count();
+        self.config.channel_layout = Some(layout);
         self
     }
 
@@ -293,8 +641,23 @@ where
     }
 
     /// Select scalar type that will carry a sample.
+    ///
+    /// If no format is chosen, the stream uses the default format of the device.
     pub fn with_sample_format(mut self, sample_format: SampleFormat) -> OutputStreamBuilder<E> {
-        self.config.sample_format = sample_format;
+        self.config.sample_format = Some(sample_format);
+        self
+    }
+
+    /// Dithers the output before it is converted to an integer sample format, disabled by
+    /// default.
+    ///
+    /// Converting to 16 bits or less adds distortion to quiet sounds, dithering turns it into
+    /// a constant low noise floor. Nothing is added when the stream uses a float format.
+    /// [`DitherAlgorithm::TPDF`] suits most uses, pass `None` to disable dithering again.
+    #[cfg(feature = "dither")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "dither")))]
+    pub fn with_dither(mut self, algorithm: Option<DitherAlgorithm>) -> OutputStreamBuilder<E> {
+        self.config.dither = algorithm;
         self
     }
 
@@ -304,14 +667,7 @@ where
         mut self,
         config: &cpal::SupportedStreamConfig,
     ) -> OutputStreamBuilder<E> {
-        self.config = OutputStreamConfig {
-            channel_count: NonZero::new(config.channels())
-                .expect("no valid cpal config has zero channels"),
-            sample_rate: NonZero::new(config.sample_rate().0)
-                .expect("no valid cpal config has zero sample rate"),
-            sample_format: config.sample_format(),
-            ..Default::default()
-        };
+        self.config = OutputStreamConfig::from_supported(config);
         self
     }
 
@@ -322,6 +678,7 @@ where
                 .expect("no valid cpal config has zero channels"),
             sample_rate: NonZero::new(config.sample_rate.0)
                 .expect("no valid cpal config has zero sample rate"),
+            channel_layout: None,
             buffer_size: config.buffer_size,
             ..self.config
         };
@@ -336,15 +693,44 @@ where
         OutputStreamBuilder {
             device: self.device,
             config: self.config,
+            prefill: self.prefill,
             error_callback: callback,
         }
     }
 
+    /// Starts the stream with silence and fades in the mixer, disabled by default.
+    ///
+    /// The first device buffer (10 ms if the buffer size is not fixed) is filled with silence
+    /// without waiting for the mixer, so the first callbacks always have data ready. Sources
+    /// added right after opening the stream then fade in over 5 ms instead of starting with a
+    /// pop. This delays the start of playback by the length of the silence.
+    pub fn with_prefill(mut self, prefill: bool) -> OutputStreamBuilder<E> {
+        self.prefill = prefill;
+        self
+    }
+
     /// Open output stream using parameters configured so far.
     pub fn open_stream(self) -> Result<OutputStream, StreamError> {
         let device = self.device.as_ref().expect("output device specified");
 
-        OutputStream::open(device, &self.config, self.error_callback)
+        OutputStream::open(device, &self.config, self.prefill, self.error_callback)
+    }
+
+    /// Async variant of [`OutputStreamBuilder::open_stream`].
+    ///
+    /// If no device has been set the default output device is looked up on a separate
+    /// thread. The stream is opened on the thread polling the returned future as
+    /// `cpal::Stream` can not be sent between threads on all platforms.
+    #[cfg(feature = "async")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+    pub async fn open_stream_async(mut self) -> Result<OutputStream, StreamError> {
+        if self.device.is_none() {
+            let device = spawn_blocking(|| cpal::default_host().default_output_device())
+                .await
+                .ok_or(StreamError::NoDevice)?;
+            self.device = Some(device);
+        }
+        self.open_stream()
     }
 
     /// Try opening a new output stream with the builder's current stream configuration.
@@ -358,11 +744,14 @@ where
         let device = self.device.as_ref().expect("output device specified");
         let error_callback = &self.error_callback;
 
-        OutputStream::open(device, &self.config, error_callback.clone()).or_else(|err| {
+        let prefill = self.prefill;
+
+        OutputStream::open(device, &self.config, prefill, error_callback.clone()).or_else(|err| {
             for supported_config in supported_output_configs(device)? {
                 if let Ok(handle) = OutputStreamBuilder::default()
                     .with_device(device.clone())
                     .with_supported_config(&supported_config)
+                    .with_prefill(prefill)
                     .with_error_callback(error_callback.clone())
                     .open_stream()
                 {
@@ -376,14 +765,26 @@ where
 
 /// A convenience function. Plays a sound once.
 /// Returns a `Sink` that can be used to control the sound.
+///
+/// The input is decoded with a [`Decoder`](crate::Decoder), use [`play_source`] to play a
+/// source that is already decoded or generated.
 pub fn play<R>(mixer: &Mixer, input: R) -> Result<Sink, PlayError>
 where
     R: Read + Seek + Send + Sync + 'static,
 {
     let input = decoder::Decoder::new(input)?;
+    Ok(play_source(mixer, input))
+}
+
+/// A convenience function. Plays a source once.
+/// Returns a `Sink` that can be used to control the sound.
+pub fn play_source<S>(mixer: &Mixer, source: S) -> Sink
+where
+    S: Source + Send + 'static,
+{
     let sink = Sink::connect_new(mixer);
-    sink.append(input);
-    Ok(sink)
+    sink.append(source);
+    sink
 }
 
 impl From<&OutputStreamConfig> for StreamConfig {
@@ -416,12 +817,16 @@ where
 assert_error_traits!(PlayError);
 
 /// Errors that might occur when interfacing with audio output.
+#[non_exhaustive]
 #[derive(Debug, thiserror::Error)]
 pub enum StreamError {
     /// Could not start playing the stream, see [cpal::PlayStreamError] for
     /// details.
     #[error("Could not start playing the stream")]
     PlayStreamError(#[source] cpal::PlayStreamError),
+    /// Could not pause the stream, see [cpal::PauseStreamError] for details.
+    #[error("Could not pause the stream")]
+    PauseStreamError(#[source] cpal::PauseStreamError),
     /// Failed to get the stream config for the given device. See
     /// [cpal::DefaultStreamConfigError] for details.
     #[error("Failed to get the stream config for the given device")]
@@ -436,10 +841,14 @@ pub enum StreamError {
     /// Could not find any output device
     #[error("Could not find any output device")]
     NoDevice,
+    /// The requested audio host is not available on this system, see
+    /// [cpal::HostUnavailable].
+    #[error("The audio host is not available")]
+    HostUnavailable(#[source] cpal::HostUnavailable),
     /// New cpal sample format that rodio does not yet support please open
-    /// an issue if you run into this.
-    #[error("New cpal sample format that rodio does not yet support please open an issue if you run into this.")]
-    UnsupportedSampleFormat,
+    /// an issue if you run into this. Holds the requested format.
+    #[error("Sample format {0} is not yet supported by rodio, please open an issue if you run into this.")]
+    UnsupportedSampleFormat(SampleFormat),
 }
 
 impl OutputStream {
@@ -452,80 +861,307 @@ impl OutputStream {
     fn open<E>(
         device: &cpal::Device,
         config: &OutputStreamConfig,
+        prefill: bool,
         error_callback: E,
     ) -> Result<OutputStream, StreamError>
     where
         E: FnMut(cpal::StreamError) + Send + 'static,
     {
         Self::validate_config(config);
-        let (controller, source) = mixer(config.channel_count, config.sample_rate);
-        Self::init_stream(device, config, source, error_callback).and_then(|stream| {
+        let config = &config.with_device_sample_format(device);
+        let (controller, source) = match config.channel_layout {
+            Some(layout) if layout.channel_count() == config.channel_count => {
+                mixer_with_layout(layout, config.sample_rate)
+            }
+            _ => mixer(config.channel_count, config.sample_rate),
+        };
+        let frames_played = Arc::new(AtomicU64::new(0));
+        let master_volume = Arc::new(AtomicU32::new(1.0f32.to_bits()));
+        let fade = Arc::new(Mutex::new(Fade::to(1.0, Duration::ZERO)));
+        let source = with_master_volume(source, master_volume.clone(), fade.clone());
+        #[cfg(feature = "dither")]
+        let stream = match (config.dither, output_bit_depth(config.sample_format())) {
+            (Some(algorithm), Some(bits)) => Self::start(
+                device,
+                config,
+                dithered(source, bits, algorithm),
+                prefill,
+                frames_played.clone(),
+                error_callback,
+            ),
+            _ => Self::start(
+                device,
+                config,
+                source,
+                prefill,
+                frames_played.clone(),
+                error_callback,
+            ),
+        };
+        #[cfg(not(feature = "dither"))]
+        let stream = Self::start(
+            device,
+            config,
+            source,
+            prefill,
+            frames_played.clone(),
+            error_callback,
+        );
+        stream.and_then(|stream| {
             stream.play().map_err(StreamError::PlayStreamError)?;
             Ok(Self {
-                _stream: stream,
+                stream,
                 mixer: controller,
                 config: *config,
+                device_name: device.name().ok(),
+                frames_played,
+                master_volume,
+                fade,
+                playing: AtomicBool::new(true),
                 log_on_drop: true,
             })
         })
     }
 
+    fn start<S, E>(
+        device: &cpal::Device,
+        config: &OutputStreamConfig,
+        source: S,
+        prefill: bool,
+        frames_played: Arc<AtomicU64>,
+        error_callback: E,
+    ) -> Result<cpal::Stream, StreamError>
+    where
+        S: Source + Send + 'static,
+        E: FnMut(cpal::StreamError) + Send + 'static,
+    {
+        if prefill {
+            Self::init_stream(
+                device,
+                config,
+                prefilled(source, config),
+                frames_played,
+                error_callback,
+            )
+        } else {
+            Self::init_stream(device, config, source, frames_played, error_callback)
+        }
+    }
+
     fn init_stream<S, E>(
         device: &cpal::Device,
         config: &OutputStreamConfig,
-        mut samples: S,
+        samples: S,
+        frames_played: Arc<AtomicU64>,
         error_callback: E,
     ) -> Result<cpal::Stream, StreamError>
     where
         S: Source + Send + 'static,
         E: FnMut(cpal::StreamError) + Send + 'static,
     {
-        let cpal_config = config.into();
-
-        macro_rules! build_output_streams {
-            ($($sample_format:tt, $generic:ty);+) => {
-                match config.sample_format {
-                    $(
-                        cpal::SampleFormat::$sample_format => device.build_output_stream::<$generic, _, _>(
-                            &cpal_config,
-                            move |data, _| {
-                                data.iter_mut().for_each(|d| {
-                                    *d = samples
-                                        .next()
-                                        .map(Sample::from_sample)
-                                        .unwrap_or(<$generic>::EQUILIBRIUM)
-                                })
-                            },
-                            error_callback,
-                            None,
-                        ),
-                    )+
-                    _ => return Err(StreamError::UnsupportedSampleFormat),
-                }
-            };
+        let build = BuildStream {
+            device,
+            config: &config.into(),
+            channels: config.channel_count,
+            samples,
+            frames_played,
+            error_callback,
+        };
+        with_sample_type(config.sample_format(), build)?.map_err(StreamError::BuildStreamError)
+    }
+}
+
+/// Work generic over the sample type of an output stream, see [`with_sample_type`].
+trait SampleTypeFn {
+    type Output;
+
+    fn call<T>(self) -> Self::Output
+    where
+        T: cpal::SizedSample + FromSample<f32> + Send + 'static;
+}
+
+/// Calls `f` with the Rust type of the samples of `format`.
+fn with_sample_type<F: SampleTypeFn>(format: SampleFormat, f: F) -> Result<F::Output, StreamError> {
+    Ok(match format {
+        SampleFormat::F32 => f.call::<f32>(),
+        SampleFormat::F64 => f.call::<f64>(),
+        SampleFormat::I8 => f.call::<i8>(),
+        SampleFormat::I16 => f.call::<i16>(),
+        SampleFormat::I24 => f.call::<I24>(),
+        SampleFormat::I32 => f.call::<i32>(),
+        SampleFormat::I64 => f.call::<i64>(),
+        SampleFormat::U8 => f.call::<u8>(),
+        SampleFormat::U16 => f.call::<u16>(),
+        // TODO: uncomment when https://github.com/RustAudio/cpal/pull/1011 is merged
+        // SampleFormat::U24 => f.call::<U24>(),
+        SampleFormat::U32 => f.call::<u32>(),
+        SampleFormat::U64 => f.call::<u64>(),
+        format => return Err(StreamError::UnsupportedSampleFormat(format)),
+    })
+}
+
+/// Builds the cpal stream playing `samples` in the sample type it is called with.
+struct BuildStream<'a, S, E> {
+    device: &'a cpal::Device,
+    config: &'a StreamConfig,
+    channels: ChannelCount,
+    samples: S,
+    frames_played: Arc<AtomicU64>,
+    error_callback: E,
+}
+
+impl<S, E> SampleTypeFn for BuildStream<'_, S, E>
+where
+    S: Source + Send + 'static,
+    E: FnMut(cpal::StreamError) + Send + 'static,
+{
+    type Output = Result<cpal::Stream, cpal::BuildStreamError>;
+
+    fn call<T>(self) -> Self::Output
+    where
+        T: cpal::SizedSample + FromSample<f32> + Send + 'static,
+    {
+        let BuildStream {
+            device,
+            config,
+            channels,
+            mut samples,
+            frames_played,
+            error_callback,
+        } = self;
+        device.build_output_stream::<T, _, _>(
+            config,
+            move |data, _| {
+                fill_output_buffer::<T, _>(data, &mut samples, channels, &frames_played);
+            },
+            error_callback,
+            None,
+        )
+    }
+}
+
+/// How often the master volume and the fade of an [`OutputStream`] are updated.
+const MASTER_VOLUME_PERIOD: Duration = Duration::from_millis(5);
+
+/// A fade of the whole output, see [`OutputStream::pause_faded`].
+#[derive(Clone, Copy, Debug)]
+struct Fade {
+    // The gain to jump to before fading, taken once applied.
+    from: Option<f32>,
+    to: f32,
+    over: Duration,
+}
+
+impl Fade {
+    fn to(to: f32, over: Duration) -> Fade {
+        Fade {
+            from: None,
+            to,
+            over,
         }
+    }
+}
 
-        let result = build_output_streams!(
-            F32, f32;
-            F64, f64;
-            I8, i8;
-            I16, i16;
-            I24, I24;
-            I32, i32;
-            I64, i64;
-            U8, u8;
-            U16, u16;
-            // TODO: uncomment when https://github.com/RustAudio/cpal/pull/1011 is merged
-            // U24, U24;
-            U32, u32;
-            U64, u64
-        );
+/// Applies the master volume of an [`OutputStream`], ramping to it when it changes, and its
+/// fade.
+fn with_master_volume<S: Source>(
+    source: S,
+    volume: Arc<AtomicU32>,
+    fade: Arc<Mutex<Fade>>,
+) -> impl Source {
+    source
+        .amplify(1.0)
+        .amplify(1.0)
+        .periodic_access(MASTER_VOLUME_PERIOD, move |amplify| {
+            amplify.ramp_factor(
+                f32::from_bits(volume.load(Ordering::Relaxed)),
+                MASTER_VOLUME_PERIOD,
+            );
+            let mut fade = fade.lock().unwrap();
+            let faded = amplify.inner_mut();
+            if let Some(from) = fade.from.take() {
+                faded.set_factor(from);
+            }
+            faded.ramp_factor(fade.to, fade.over);
+        })
+}
 
-        result.map_err(StreamError::BuildStreamError)
+/// Returns the bit depth a stream of `format` quantizes to, if dithering it is worthwhile.
+#[cfg(feature = "dither")]
+fn output_bit_depth(format: SampleFormat) -> Option<crate::BitDepth> {
+    match format {
+        SampleFormat::I8 | SampleFormat::U8 => Some(nz!(8)),
+        SampleFormat::I16 | SampleFormat::U16 => Some(nz!(16)),
+        SampleFormat::I24 => Some(nz!(24)),
+        // The precision of a sample is no more than 24 bits.
+        _ => None,
     }
 }
 
+/// Dithers `source` and rounds it to the steps of `bits`, so the conversion to the integer
+/// format, which truncates, does not distort it again.
+#[cfg(feature = "dither")]
+fn dithered<S: Source>(
+    source: S,
+    bits: crate::BitDepth,
+    algorithm: DitherAlgorithm,
+) -> impl Source {
+    let steps = (1u32 << (bits.get() - 1)) as f32;
+    source
+        .dither(bits, algorithm)
+        .map_samples(move |sample| (sample * steps).round() / steps)
+}
+
+/// Length of the fade in of a stream opened with [`OutputStreamBuilder::with_prefill`].
+const PREFILL_FADE: Duration = Duration::from_millis(5);
+
+/// Precedes the output with one device buffer of silence and fades it in.
+fn prefilled<S: Source>(
+    source: S,
+    config: &OutputStreamConfig,
+) -> crate::source::Delay<crate::source::FadeIn<S>> {
+    let silence = match config.buffer_size {
+        BufferSize::Fixed(frames) => {
+            Duration::from_secs_f64(frames as f64 / config.sample_rate.get() as f64)
+        }
+        BufferSize::Default => Duration::from_millis(10),
+    };
+    source.fade_in(PREFILL_FADE).delay(silence)
+}
+
+/// Fills a device buffer from `samples`, writing silence once they run out.
+///
+/// Returns `false` if the samples ran out before the buffer was full.
+fn fill_output_buffer<T, S>(
+    data: &mut [T],
+    samples: &mut S,
+    channels: ChannelCount,
+    frames_played: &AtomicU64,
+) -> bool
+where
+    T: Sample + FromSample<f32>,
+    S: Source,
+{
+    let mut filled = true;
+    data.iter_mut().for_each(|d| {
+        *d = samples.next().map(Sample::from_sample).unwrap_or_else(|| {
+            filled = false;
+            T::EQUILIBRIUM
+        })
+    });
+    frames_played.fetch_add(
+        (data.len() / channels.get() as usize) as u64,
+        Ordering::Relaxed,
+    );
+    filled
+}
+
 /// Return all formats supported by the device.
+///
+/// # Errors
+///
+/// Returns [`StreamError::SupportedStreamConfigsError`] if the configurations could not be
+/// enumerated, for example because the device was disconnected. No partial list is returned.
 pub fn supported_output_configs(
     device: &cpal::Device,
 ) -> Result<impl Iterator<Item = cpal::SupportedStreamConfig>, StreamError> {
@@ -547,3 +1183,456 @@ pub fn supported_output_configs(
         formats
     }))
 }
+
+/// Lists the audio hosts (backends) that are available on this system, for example ALSA and
+/// JACK on Linux. Pick one with [`OutputStreamBuilder::with_host`].
+///
+/// Only hosts compiled into cpal are listed. JACK and ASIO need the `jack` or `asio` feature
+/// of the `cpal` dependency, enable it by adding `cpal` with that feature to your
+/// dependencies.
+pub fn available_hosts() -> Vec<cpal::HostId> {
+    cpal::available_hosts()
+}
+
+/// Lists the configurations an output stream can be opened with on `device`.
+///
+/// The default configuration of the device comes first, followed by the others from most to
+/// least preferred. Every configuration appears once. Unlike [`supported_output_configs`]
+/// this returns rodio's [`OutputStreamConfig`], which is convenient for presenting the options
+/// to the user. Apply the chosen one with [`OutputStreamBuilder::with_channels`],
+/// [`OutputStreamBuilder::with_sample_rate`] and [`OutputStreamBuilder::with_sample_format`].
+pub fn output_stream_configs(
+    device: &cpal::Device,
+) -> Result<Vec<OutputStreamConfig>, StreamError> {
+    let default = device.default_output_config().ok();
+    let mut configs: Vec<OutputStreamConfig> = Vec::new();
+    for config in default.into_iter().chain(supported_output_configs(device)?) {
+        let config = OutputStreamConfig::from_supported(&config);
+        if !configs.contains(&config) {
+            configs.push(config);
+        }
+    }
+    Ok(configs)
+}
+
+/// Async variant of [`supported_output_configs`].
+///
+/// Querying the device runs on a separate thread so it does not block the async runtime.
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub async fn supported_output_configs_async(
+    device: cpal::Device,
+) -> Result<Vec<cpal::SupportedStreamConfig>, StreamError> {
+    spawn_blocking(move || supported_output_configs(&device).map(Iterator::collect)).await
+}
+
+/// Lists the output devices of the default host without blocking the async runtime.
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub async fn output_devices_async() -> Result<Vec<cpal::Device>, cpal::DevicesError> {
+    spawn_blocking(|| cpal::default_host().output_devices().map(Iterator::collect)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fill_output_buffer, PlaybackClock};
+    use crate::buffer::SamplesBuffer;
+    use crate::math::nz;
+    use crate::source::SineWave;
+    use crate::{ChannelLayout, OutputStreamBuilder, Source};
+    use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn open_stream_async_completes() {
+        use crate::spawn_blocking::tests::block_on;
+
+        // Without a sound card the futures resolve to an error, never hang or panic.
+        if let Ok(stream) = block_on(OutputStreamBuilder::default().open_stream_async()) {
+            stream
+                .mixer()
+                .add(SineWave::new(440.0).take_duration(Duration::ZERO));
+        }
+        if let Ok(stream) = block_on(OutputStreamBuilder::open_default_stream_async()) {
+            assert!(stream.config().channel_count().get() > 0);
+        }
+    }
+
+    #[test]
+    fn channels_layout_sets_channel_count() {
+        let builder =
+            OutputStreamBuilder::default().with_channels_layout(ChannelLayout::Surround5_1);
+        assert_eq!(builder.config.channel_count(), nz!(6));
+        assert_eq!(
+            builder.config.channel_layout(),
+            Some(ChannelLayout::Surround5_1)
+        );
+
+        // Choosing a channel count afterwards drops the layout.
+        let builder = builder.with_channels(nz!(2));
+        assert_eq!(builder.config.channel_layout(), None);
+    }
+
+    #[test]
+    fn default_builder_uses_device_sample_format() {
+        use cpal::traits::{DeviceTrait, HostTrait};
+        use std::num::NonZero;
+
+        assert_eq!(OutputStreamBuilder::default().config.sample_format, None);
+
+        // Only runs where an output device is available.
+        let Some(device) = cpal::default_host().default_output_device() else {
+            return;
+        };
+        let Ok(default) = device.default_output_config() else {
+            return;
+        };
+        let stream = OutputStreamBuilder::default()
+            .with_device(device)
+            .with_sample_rate(NonZero::new(default.sample_rate().0).unwrap())
+            .with_channels(NonZero::new(default.channels()).unwrap())
+            .open_stream()
+            .unwrap();
+        assert_eq!(stream.config().sample_format(), default.sample_format());
+    }
+
+    #[test]
+    fn default_config_is_listed() {
+        use cpal::traits::HostTrait;
+
+        // Only runs where an output device is available.
+        let Some(device) = cpal::default_host().default_output_device() else {
+            return;
+        };
+        let Ok(default) = OutputStreamBuilder::from_device(device.clone()) else {
+            return;
+        };
+        let configs = super::output_stream_configs(&device).unwrap();
+        assert_eq!(configs.first(), Some(&default.config));
+    }
+
+    #[test]
+    fn exposes_device_name_and_stream_config() {
+        // Only runs where an output device is available.
+        let Ok(stream) = OutputStreamBuilder::open_default_stream() else {
+            return;
+        };
+        assert!(stream.device_name().is_some_and(|name| !name.is_empty()));
+        let config = stream.stream_config();
+        assert_eq!(config.channels, stream.config().channel_count().get());
+        assert_eq!(config.sample_rate.0, stream.config().sample_rate().get());
+    }
+
+    #[test]
+    fn getters_reflect_fallback_config() {
+        // Only runs where an output device is available.
+        let Ok(builder) = OutputStreamBuilder::from_default_device() else {
+            return;
+        };
+        // No device plays at 1 Hz, so the stream falls back to a supported config.
+        let Ok(stream) = builder.with_sample_rate(nz!(1)).open_stream_or_fallback() else {
+            return;
+        };
+        let config = stream.stream_config();
+        assert_ne!(stream.sample_rate(), nz!(1));
+        assert_eq!(stream.sample_rate().get(), config.sample_rate.0);
+        assert_eq!(stream.channels().get(), config.channels);
+        assert_eq!(stream.mixer().sample_rate(), stream.sample_rate());
+    }
+
+    #[test]
+    fn pause_and_resume_stream() {
+        // Only runs where an output device is available that supports pausing.
+        let Ok(stream) = OutputStreamBuilder::open_default_stream() else {
+            return;
+        };
+        assert!(stream.is_playing());
+        if stream.pause().is_err() {
+            return;
+        }
+        assert!(!stream.is_playing());
+        stream.play().unwrap();
+        assert!(stream.is_playing());
+
+        stream.pause_faded(Duration::from_millis(10)).unwrap();
+        assert!(!stream.is_playing());
+        stream.play_faded(Duration::from_millis(10)).unwrap();
+        assert!(stream.is_playing());
+    }
+
+    #[test]
+    fn sample_formats_map_to_their_type() {
+        use super::{with_sample_type, SampleTypeFn};
+        use cpal::{FromSample, SampleFormat, SizedSample};
+
+        struct FormatOf;
+
+        impl SampleTypeFn for FormatOf {
+            type Output = SampleFormat;
+
+            fn call<T>(self) -> SampleFormat
+            where
+                T: SizedSample + FromSample<f32> + Send + 'static,
+            {
+                T::FORMAT
+            }
+        }
+
+        use SampleFormat::*;
+        for format in [I8, I16, I24, I32, I64, U8, U16, U32, U64, F32, F64] {
+            assert_eq!(with_sample_type(format, FormatOf).unwrap(), format);
+        }
+    }
+
+    #[test]
+    fn open_stream_on_each_host() {
+        use cpal::traits::HostTrait;
+
+        let hosts = super::available_hosts();
+        assert!(hosts.contains(&cpal::default_host().id()));
+        for host in hosts {
+            // Only opens a stream where the host has an output device.
+            let Ok(builder) = OutputStreamBuilder::default().with_host(host) else {
+                continue;
+            };
+            if let Ok(stream) = builder.open_stream() {
+                assert!(stream.device_name().is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn open_surround_stream() {
+        use cpal::traits::HostTrait;
+
+        // Only runs where the default device supports 5.1.
+        let Some(device) = cpal::default_host().default_output_device() else {
+            return;
+        };
+        let Some(config) = super::supported_output_configs(&device)
+            .into_iter()
+            .flatten()
+            .find(|config| config.channels() == 6)
+        else {
+            return;
+        };
+
+        let stream = OutputStreamBuilder::default()
+            .with_device(device)
+            .with_supported_config(&config)
+            .with_channels_layout(ChannelLayout::Surround5_1)
+            .open_stream()
+            .unwrap();
+        assert_eq!(stream.config().channel_count(), nz!(6));
+        assert_eq!(
+            stream.config().channel_layout(),
+            Some(ChannelLayout::Surround5_1)
+        );
+        assert_eq!(stream.mixer().channels(), nz!(6));
+    }
+
+    #[test]
+    fn frames_played_matches_rate_times_duration() {
+        let mut source = SamplesBuffer::new(nz!(2), nz!(8000), vec![0.5; 2 * 8000])
+            .take_duration(Duration::from_millis(500));
+        let frames_played = AtomicU64::new(0);
+        let mut data = [0.0f32; 400];
+
+        let mut written = 0;
+        while written < 8000 {
+            fill_output_buffer(&mut data, &mut source, nz!(2), &frames_played);
+            written += data.len();
+        }
+
+        assert_eq!(frames_played.load(Ordering::Relaxed), 4000);
+        // The source ended so the last buffer is silence.
+        fill_output_buffer(&mut data, &mut source, nz!(2), &frames_played);
+        assert!(data.iter().all(|s| *s == 0.0));
+        assert_eq!(frames_played.load(Ordering::Relaxed), 4200);
+    }
+
+    #[test]
+    #[cfg(feature = "dither")]
+    fn dither_keeps_quiet_tone_at_16_bits() {
+        use crate::source::DitherAlgorithm;
+        use std::f32::consts::TAU;
+
+        let config = OutputStreamBuilder::default()
+            .with_sample_format(cpal::SampleFormat::I16)
+            .with_dither(Some(DitherAlgorithm::TPDF))
+            .config;
+        let bits = super::output_bit_depth(config.sample_format()).unwrap();
+        assert_eq!(config.dither(), Some(DitherAlgorithm::TPDF));
+        assert_eq!(super::output_bit_depth(cpal::SampleFormat::F32), None);
+
+        // A 1 kHz tone of 0.4 LSB, which plain rounding turns into silence.
+        let lsb = 1.0 / 32768.0;
+        let render = |mut source: Box<dyn Source>| {
+            let mut data = vec![0i16; 48000];
+            fill_output_buffer(&mut data, &mut source, nz!(1), &AtomicU64::new(0));
+            data
+        };
+        let level = |data: &[i16]| {
+            let (re, im) = data
+                .iter()
+                .enumerate()
+                .fold((0.0, 0.0), |(re, im), (i, s)| {
+                    let phase = TAU * 1000.0 * i as f32 / 48000.0;
+                    let s = *s as f32 * lsb;
+                    (re + s * phase.cos(), im + s * phase.sin())
+                });
+            2.0 * f32::hypot(re, im) / data.len() as f32
+        };
+        let tone = || SineWave::new(1000.0).amplify(0.4 * lsb);
+
+        let plain = render(Box::new(tone()));
+        assert!(plain.iter().all(|s| *s == 0));
+
+        let dithered = render(Box::new(super::dithered(
+            tone(),
+            bits,
+            DitherAlgorithm::TPDF,
+        )));
+        assert!(dithered.iter().any(|s| *s != 0));
+        let level = level(&dithered) / lsb;
+        assert!((level - 0.4).abs() < 0.1, "{level}");
+    }
+
+    #[test]
+    fn prefill_avoids_startup_underruns() {
+        let config = OutputStreamBuilder::default()
+            .with_channels(nz!(2))
+            .with_sample_rate(nz!(48000))
+            .with_buffer_size(cpal::BufferSize::Fixed(480))
+            .config;
+
+        // The application adds a sound only after the stream started.
+        let first_callbacks = |prefill: bool| {
+            let (mixer, source) = crate::mixer::mixer(nz!(2), nz!(48000));
+            let mut source: Box<dyn Source> = if prefill {
+                Box::new(super::prefilled(source, &config))
+            } else {
+                Box::new(source)
+            };
+            let frames_played = AtomicU64::new(0);
+            let mut output = Vec::new();
+            let mut underruns = 0;
+            for callback in 0..4 {
+                let mut data = [0.0f32; 960];
+                if !fill_output_buffer(&mut data, &mut source, nz!(2), &frames_played) {
+                    underruns += 1;
+                }
+                output.extend(data);
+                if callback == 0 {
+                    mixer.add(SamplesBuffer::new(nz!(1), nz!(48000), vec![1.0; 48000]));
+                }
+            }
+            (underruns, output)
+        };
+
+        let (underruns, output) = first_callbacks(false);
+        assert_eq!(underruns, 1);
+        // The sound starts abruptly at full level.
+        assert_eq!(output[960], 1.0);
+
+        let (underruns, output) = first_callbacks(true);
+        assert_eq!(underruns, 0);
+        assert!(output[..960].iter().all(|s| *s == 0.0));
+        // The sound fades in over 5 ms.
+        assert!(output[960] < 0.01);
+        assert!((output[960 + 480] - 1.0).abs() < 0.01);
+        assert_eq!(output[2 * 960], 1.0);
+    }
+
+    #[test]
+    fn master_volume_scales_output() {
+        let (mixer, source) = crate::mixer::mixer(nz!(2), nz!(48000));
+        mixer.add(SamplesBuffer::new(nz!(2), nz!(48000), vec![1.0; 2 * 48000]));
+        let volume = Arc::new(AtomicU32::new(1.0f32.to_bits()));
+        let fade = Arc::new(Mutex::new(super::Fade::to(1.0, Duration::ZERO)));
+        let mut source = super::with_master_volume(source, volume.clone(), fade);
+        let frames_played = AtomicU64::new(0);
+        let mut data = [0.0f32; 960];
+
+        fill_output_buffer(&mut data, &mut source, nz!(2), &frames_played);
+        assert!(data.iter().all(|s| *s == 1.0));
+
+        volume.store(0.5f32.to_bits(), Ordering::Relaxed);
+        fill_output_buffer(&mut data, &mut source, nz!(2), &frames_played);
+        // The change is ramped.
+        assert!(data.windows(2).all(|w| w[0] - w[1] < 0.01));
+        fill_output_buffer(&mut data, &mut source, nz!(2), &frames_played);
+        assert!(data.iter().all(|s| *s == 0.5));
+    }
+
+    #[test]
+    fn fade_ramps_output_to_silence() {
+        let (mixer, source) = crate::mixer::mixer(nz!(2), nz!(48000));
+        mixer.add(SamplesBuffer::new(nz!(2), nz!(48000), vec![1.0; 2 * 48000]));
+        let volume = Arc::new(AtomicU32::new(0.5f32.to_bits()));
+        let fade = Arc::new(Mutex::new(super::Fade::to(1.0, Duration::ZERO)));
+        let mut source = super::with_master_volume(source, volume, fade.clone());
+        let frames_played = AtomicU64::new(0);
+        let mut data = [0.0f32; 960];
+        fill_output_buffer(&mut data, &mut source, nz!(2), &frames_played);
+
+        // What `pause_faded` plays before suspending the device.
+        *fade.lock().unwrap() = super::Fade::to(0.0, Duration::from_millis(20));
+        let mut output = vec![0.0f32; 2 * 1440];
+        fill_output_buffer(&mut output, &mut source, nz!(2), &frames_played);
+        assert!(output[0] > 0.49);
+        assert!(output.windows(2).all(|w| w[1] <= w[0]));
+        assert!(output[output.len() - 2 * 240..].iter().all(|s| *s == 0.0));
+
+        // `play_faded` starts from silence.
+        *fade.lock().unwrap() = super::Fade {
+            from: Some(0.0),
+            to: 1.0,
+            over: Duration::from_millis(20),
+        };
+        fill_output_buffer(&mut output, &mut source, nz!(2), &frames_played);
+        assert!(output[0] < 0.01);
+        assert_eq!(output[output.len() - 1], 0.5);
+    }
+
+    #[test]
+    fn play_source_returns_controllable_sink() {
+        let (mixer, mut output) = crate::mixer::mixer(nz!(1), nz!(48000));
+        let sink = super::play_source(&mixer, SineWave::new(1000.0));
+        sink.set_volume(0.5);
+
+        let peak = output
+            .by_ref()
+            .take(4800)
+            .fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+        assert!((peak - 0.5).abs() < 0.01, "{peak}");
+        assert!(!sink.empty());
+
+        // Controls apply while the sink is playing.
+        sink.stop();
+        output.by_ref().take(480).for_each(drop);
+        assert!(sink.empty());
+    }
+
+    #[test]
+    fn playback_clock_follows_played_samples() {
+        let source = SamplesBuffer::new(nz!(2), nz!(8000), vec![0.5; 2 * 2000]);
+        let mut source = source.take_duration(Duration::from_millis(250));
+        let frames_played = Arc::new(AtomicU64::new(0));
+        let clock = PlaybackClock {
+            frames_played: frames_played.clone(),
+            sample_rate: nz!(8000),
+        };
+        let mut data = [0.0f32; 500];
+
+        for buffer in 1..=8 {
+            fill_output_buffer(&mut data, &mut source, nz!(2), &frames_played);
+            assert_eq!(clock.samples(), buffer * 250);
+        }
+        assert_eq!(source.next(), None);
+        assert_eq!(clock.samples(), 2000);
+        assert_eq!(clock.elapsed(), Duration::from_millis(250));
+    }
+}
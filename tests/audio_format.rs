@@ -0,0 +1,48 @@
+#![allow(dead_code)]
+#![allow(unused_imports)]
+
+use std::io::BufReader;
+use std::path::Path;
+
+use rodio::decoder::AudioFormat;
+use rodio::Decoder;
+
+use rstest::rstest;
+
+#[cfg(any(
+    feature = "claxon",
+    feature = "hound",
+    feature = "lewton",
+    feature = "minimp3",
+    feature = "symphonia-flac",
+    feature = "symphonia-mp3",
+    feature = "symphonia-vorbis",
+    feature = "symphonia-wav",
+    all(feature = "symphonia-isomp4", feature = "symphonia-aac"),
+))]
+#[rstest]
+#[cfg_attr(
+    any(feature = "hound", feature = "symphonia-wav"),
+    case("music.wav", AudioFormat::Wav)
+)]
+#[cfg_attr(
+    any(feature = "claxon", feature = "symphonia-flac"),
+    case("music.flac", AudioFormat::Flac)
+)]
+#[cfg_attr(
+    any(feature = "minimp3", feature = "symphonia-mp3"),
+    case("music.mp3", AudioFormat::Mp3)
+)]
+#[cfg_attr(
+    all(feature = "symphonia-isomp4", feature = "symphonia-aac"),
+    case("music.m4a", AudioFormat::Aac)
+)]
+#[cfg_attr(
+    any(feature = "lewton", feature = "symphonia-vorbis"),
+    case("music.ogg", AudioFormat::Vorbis)
+)]
+fn reports_detected_format(#[case] asset: &str, #[case] expected: AudioFormat) {
+    let file = std::fs::File::open(Path::new("assets").join(asset)).unwrap();
+    let decoder: Decoder<BufReader<std::fs::File>> = Decoder::try_from(file).unwrap();
+    assert_eq!(decoder.format(), expected);
+}
@@ -0,0 +1,47 @@
+#![cfg(all(feature = "symphonia-caf", feature = "symphonia-pcm"))]
+
+use std::io::Cursor;
+
+use rodio::{Decoder, Source};
+
+/// Builds a stereo 16-bit big endian PCM CAF file in memory.
+fn caf_16bit(samples: &[i16]) -> Vec<u8> {
+    const SAMPLE_RATE: f64 = 22050.0;
+    let mut desc = Vec::new();
+    desc.extend_from_slice(&SAMPLE_RATE.to_be_bytes());
+    desc.extend_from_slice(b"lpcm");
+    desc.extend_from_slice(&0u32.to_be_bytes()); // format flags: integer, big endian
+    desc.extend_from_slice(&4u32.to_be_bytes()); // bytes per packet
+    desc.extend_from_slice(&1u32.to_be_bytes()); // frames per packet
+    desc.extend_from_slice(&2u32.to_be_bytes()); // channels
+    desc.extend_from_slice(&16u32.to_be_bytes()); // bits per channel
+
+    let mut data = 0u32.to_be_bytes().to_vec(); // edit count
+    data.extend(samples.iter().flat_map(|s| s.to_be_bytes()));
+
+    let mut caf = Vec::new();
+    caf.extend_from_slice(b"caff");
+    caf.extend_from_slice(&1u16.to_be_bytes()); // version
+    caf.extend_from_slice(&0u16.to_be_bytes()); // flags
+    caf.extend_from_slice(b"desc");
+    caf.extend_from_slice(&(desc.len() as i64).to_be_bytes());
+    caf.extend_from_slice(&desc);
+    caf.extend_from_slice(b"data");
+    caf.extend_from_slice(&(data.len() as i64).to_be_bytes());
+    caf.extend_from_slice(&data);
+    caf
+}
+
+#[test]
+fn test_caf_pcm() {
+    let samples: Vec<i16> = (0..2000).map(|i| (i * 16) as i16).collect();
+    let decoder = Decoder::try_from(Cursor::new(caf_16bit(&samples))).unwrap();
+    assert_eq!(decoder.sample_rate().get(), 22050);
+    assert_eq!(decoder.channels().get(), 2);
+
+    let decoded: Vec<f32> = decoder.collect();
+    assert_eq!(decoded.len(), samples.len());
+    for (decoded, sample) in decoded.iter().zip(&samples) {
+        assert_eq!(*decoded, *sample as f32 / 32768.0);
+    }
+}
@@ -0,0 +1,83 @@
+use std::io::{Cursor, Read};
+use std::num::NonZero;
+
+use rodio::buffer::SamplesBuffer;
+use rodio::decoder::{AudioFormat, DecoderError};
+use rodio::{Decoder, Source};
+
+const MAGIC: &[u8] = b"RAWF";
+
+/// A made up format: the magic bytes, the sample rate and then mono `f32` samples, all little
+/// endian.
+fn encode(sample_rate: u32, samples: &[f32]) -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend(sample_rate.to_le_bytes());
+    bytes.extend(samples.iter().flat_map(|s| s.to_le_bytes()));
+    bytes
+}
+
+fn decode<R: Read>(mut data: R) -> Result<Box<dyn Source + Send>, DecoderError> {
+    let mut bytes = Vec::new();
+    data.read_to_end(&mut bytes)
+        .map_err(|e| DecoderError::IoError(e.to_string()))?;
+    let rate = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
+    let samples: Vec<f32> = bytes[8..]
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+        .collect();
+    let sample_rate = NonZero::new(rate).ok_or(DecoderError::UnrecognizedFormat)?;
+    Ok(Box::new(SamplesBuffer::new(
+        NonZero::new(1).unwrap(),
+        sample_rate,
+        samples,
+    )))
+}
+
+#[test]
+fn decodes_through_custom_decoder() {
+    let samples = [0.5, -0.25, 1.0, 0.0];
+    let decoder = Decoder::builder()
+        .with_data(Cursor::new(encode(8000, &samples)))
+        .with_custom_decoder(|head| head.starts_with(MAGIC), decode)
+        .build()
+        .unwrap();
+    assert_eq!(decoder.format(), AudioFormat::Custom);
+    assert_eq!(decoder.channels().get(), 1);
+    assert_eq!(decoder.sample_rate().get(), 8000);
+    assert_eq!(decoder.collect::<Vec<_>>(), samples);
+}
+
+#[test]
+fn custom_decoder_loops() {
+    let samples = [0.5, -0.25];
+    let decoder = Decoder::builder()
+        .with_data(Cursor::new(encode(8000, &samples)))
+        .with_custom_decoder(|head| head.starts_with(MAGIC), decode)
+        .build_looped()
+        .unwrap();
+    assert_eq!(
+        decoder.take(5).collect::<Vec<_>>(),
+        [0.5, -0.25, 0.5, -0.25, 0.5]
+    );
+}
+
+#[test]
+fn unmatched_custom_decoder_is_skipped() {
+    let result = Decoder::builder()
+        .with_data(Cursor::new(b"not audio at all".to_vec()))
+        .with_custom_decoder(|head| head.starts_with(MAGIC), decode)
+        .build();
+    assert!(matches!(result, Err(DecoderError::UnrecognizedFormat)));
+}
+
+#[cfg(any(feature = "hound", feature = "wav"))]
+#[test]
+fn builtin_decoders_still_apply() {
+    let file = std::fs::File::open("assets/audacity16bit.wav").unwrap();
+    let mut decoder = Decoder::builder()
+        .with_data(std::io::BufReader::new(file))
+        .with_custom_decoder(|head| head.starts_with(MAGIC), decode)
+        .build()
+        .unwrap();
+    assert!(decoder.any(|x| x != 0.0));
+}
@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+use rodio::buffer::SamplesBuffer;
+use rodio::nz;
+use rodio::source::{Source, SourceInfo};
+
+#[test]
+fn describe_reflects_outermost_format() {
+    let source = SamplesBuffer::new(nz!(1), nz!(22050), vec![0.0; 22050])
+        .amplify(0.5)
+        .reformat(nz!(2), nz!(44100))
+        .take_duration(Duration::from_millis(500))
+        .fade_in(Duration::from_millis(10));
+
+    assert_eq!(
+        source.describe(),
+        SourceInfo {
+            channels: nz!(2),
+            sample_rate: nz!(44100),
+            total_duration: Some(Duration::from_millis(500)),
+            current_span_len: source.current_span_len(),
+        }
+    );
+}
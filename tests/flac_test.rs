@@ -22,3 +22,152 @@ fn test_flac_encodings() {
         assert_eq!(decoder.total_duration(), Some(Duration::from_secs(3)));
     }
 }
+
+#[cfg(any(feature = "claxon", feature = "symphonia-flac"))]
+#[test]
+fn test_flac_seek_table() {
+    use std::io::{Cursor, Read, Seek, SeekFrom};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    struct CountingReader<R> {
+        inner: R,
+        bytes_read: Arc<AtomicU64>,
+    }
+
+    impl<R: Read> Read for CountingReader<R> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = self.inner.read(buf)?;
+            self.bytes_read.fetch_add(n as u64, Ordering::Relaxed);
+            Ok(n)
+        }
+    }
+
+    impl<R: Seek> Seek for CountingReader<R> {
+        fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    let flac = with_seek_table(&std::fs::read("assets/music.flac").unwrap());
+    let reference: Vec<f32> = rodio::Decoder::try_from(Cursor::new(flac.clone()))
+        .unwrap()
+        .collect();
+
+    let bytes_read = Arc::new(AtomicU64::new(0));
+    let reader = CountingReader {
+        inner: Cursor::new(flac.clone()),
+        bytes_read: bytes_read.clone(),
+    };
+    let mut decoder = rodio::Decoder::builder()
+        .with_data(reader)
+        .with_byte_len(flac.len() as u64)
+        .with_seekable(true)
+        .with_hint("flac")
+        .build()
+        .unwrap();
+    let sample_rate = decoder.sample_rate().get() as usize;
+    let channels = decoder.channels().get() as usize;
+
+    bytes_read.store(0, Ordering::Relaxed);
+    decoder.try_seek(Duration::from_secs(8)).unwrap();
+    let samples: Vec<f32> = decoder.by_ref().take(1000).collect();
+
+    let start = 8 * sample_rate * channels;
+    assert_eq!(samples, reference[start..start + 1000]);
+    assert!(
+        bytes_read.load(Ordering::Relaxed) < flac.len() as u64 / 4,
+        "seeking decoded from the start, read {} of {} bytes",
+        bytes_read.load(Ordering::Relaxed),
+        flac.len()
+    );
+}
+
+/// Inserts a SEEKTABLE with a point for every tenth frame into a FLAC file with a fixed block
+/// size.
+#[cfg(any(feature = "claxon", feature = "symphonia-flac"))]
+fn with_seek_table(flac: &[u8]) -> Vec<u8> {
+    assert_eq!(&flac[..4], b"fLaC");
+    // STREAMINFO is always the first metadata block.
+    let block_size = u16::from_be_bytes([flac[8], flac[9]]) as u64;
+    let mut first_frame = 4;
+    loop {
+        let header = &flac[first_frame..first_frame + 4];
+        first_frame += 4 + u32::from_be_bytes([0, header[1], header[2], header[3]]) as usize;
+        if header[0] & 0x80 != 0 {
+            break;
+        }
+    }
+
+    let mut seek_points = Vec::new();
+    let mut frame_number = 0;
+    for pos in first_frame..flac.len() - 16 {
+        if let Some(number) = frame_header_number(&flac[pos..pos + 16]) {
+            if number == frame_number {
+                if frame_number % 10 == 0 {
+                    seek_points.extend_from_slice(&(number * block_size).to_be_bytes());
+                    seek_points.extend_from_slice(&((pos - first_frame) as u64).to_be_bytes());
+                    seek_points.extend_from_slice(&(block_size as u16).to_be_bytes());
+                }
+                frame_number += 1;
+            }
+        }
+    }
+    assert!(frame_number > 20, "test file should have many frames");
+
+    let streaminfo_end = 4 + 4 + 34;
+    let mut out = flac[..streaminfo_end].to_vec();
+    out.push(3); // SEEKTABLE, not the last metadata block
+    out.extend_from_slice(&(seek_points.len() as u32).to_be_bytes()[1..]);
+    out.extend_from_slice(&seek_points);
+    out.extend_from_slice(&flac[streaminfo_end..]);
+    out
+}
+
+/// Returns the frame number if `bytes` starts with a valid fixed block size frame header.
+#[cfg(any(feature = "claxon", feature = "symphonia-flac"))]
+fn frame_header_number(bytes: &[u8]) -> Option<u64> {
+    if bytes[0] != 0xFF || bytes[1] != 0xF8 {
+        return None;
+    }
+    let block_size_code = bytes[2] >> 4;
+    let sample_rate_code = bytes[2] & 0x0F;
+    if block_size_code == 0 || sample_rate_code == 15 {
+        return None;
+    }
+
+    let leading_ones = bytes[4].leading_ones() as usize;
+    let (mut number, len) = match leading_ones {
+        0 => (bytes[4] as u64, 1),
+        2..=7 => ((bytes[4] & (0x7F >> leading_ones)) as u64, leading_ones),
+        _ => return None,
+    };
+    for byte in &bytes[5..4 + len] {
+        number = (number << 6) | (byte & 0x3F) as u64;
+    }
+
+    let mut header_len = 4 + len;
+    header_len += match block_size_code {
+        6 => 1,
+        7 => 2,
+        _ => 0,
+    };
+    header_len += match sample_rate_code {
+        12 => 1,
+        13 | 14 => 2,
+        _ => 0,
+    };
+
+    let crc = bytes[..header_len].iter().fold(0u8, |mut crc, byte| {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    });
+    (crc == bytes[header_len]).then_some(number)
+}
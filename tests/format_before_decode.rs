@@ -0,0 +1,70 @@
+#![allow(dead_code)]
+#![allow(unused_imports)]
+
+use std::io::BufReader;
+use std::path::Path;
+
+use rodio::{ChannelCount, Decoder, SampleRate, Source};
+
+use rstest::rstest;
+
+fn open(asset: &str) -> Decoder<BufReader<std::fs::File>> {
+    let file = std::fs::File::open(Path::new("assets").join(asset)).unwrap();
+    Decoder::try_from(file).unwrap()
+}
+
+// The expected values are taken from the headers of the files.
+#[cfg(any(
+    feature = "claxon",
+    feature = "hound",
+    feature = "lewton",
+    feature = "minimp3",
+    feature = "symphonia-flac",
+    feature = "symphonia-mp3",
+    feature = "symphonia-vorbis",
+    feature = "symphonia-wav",
+    all(feature = "symphonia-isomp4", feature = "symphonia-aac"),
+))]
+#[rstest]
+#[cfg_attr(
+    any(feature = "hound", feature = "symphonia-wav"),
+    case("music.wav", 2, 44100)
+)]
+#[cfg_attr(
+    any(feature = "hound", feature = "symphonia-wav"),
+    case("beep.wav", 1, 44100)
+)]
+#[cfg_attr(
+    any(feature = "claxon", feature = "symphonia-flac"),
+    case("music.flac", 2, 44100)
+)]
+#[cfg_attr(
+    any(feature = "minimp3", feature = "symphonia-mp3"),
+    case("music.mp3", 2, 44100)
+)]
+#[cfg_attr(
+    all(feature = "symphonia-isomp4", feature = "symphonia-aac"),
+    case("music.m4a", 2, 44100)
+)]
+#[cfg_attr(
+    any(feature = "lewton", feature = "symphonia-vorbis"),
+    case("music.ogg", 2, 44100)
+)]
+#[cfg_attr(
+    any(feature = "lewton", feature = "symphonia-vorbis"),
+    case("beep3.ogg", 1, 44100)
+)]
+fn format_known_before_first_sample(
+    #[case] asset: &str,
+    #[case] channels: u16,
+    #[case] sample_rate: u32,
+) {
+    let decoder = open(asset);
+    assert_eq!(decoder.channels(), ChannelCount::new(channels).unwrap());
+    assert_eq!(decoder.sample_rate(), SampleRate::new(sample_rate).unwrap());
+
+    // Querying the format does not consume any samples.
+    let queried: Vec<f32> = decoder.take(1000).collect();
+    let fresh: Vec<f32> = open(asset).take(1000).collect();
+    assert_eq!(queried, fresh);
+}
@@ -0,0 +1,107 @@
+#[cfg(all(feature = "symphonia-ogg", feature = "symphonia-vorbis"))]
+#[test]
+fn test_ogg_chained_streams() {
+    use rodio::Source;
+    use std::io::Cursor;
+
+    // Mono and stereo files, so the chain changes format half-way.
+    let first = std::fs::read("assets/beep3.ogg").unwrap();
+    let second = std::fs::read("assets/music.ogg").unwrap();
+    let count = |bytes: Vec<u8>| {
+        rodio::Decoder::try_from(Cursor::new(bytes))
+            .unwrap()
+            .count()
+    };
+    let first_len = count(first.clone());
+    let second_len = count(second.clone());
+
+    // Concatenated Ogg files form a chained stream, each file is a link of the chain.
+    let mut decoder = rodio::Decoder::try_from(Cursor::new([first, second].concat())).unwrap();
+    assert_eq!(decoder.channels().get(), 1);
+
+    let mut decoded = 0;
+    let mut remaining_in_span = decoder.current_span_len().unwrap();
+    while decoder.next().is_some() {
+        if remaining_in_span == 0 {
+            remaining_in_span = decoder.current_span_len().unwrap();
+        }
+        remaining_in_span -= 1;
+        decoded += 1;
+        if decoded == first_len {
+            // The first link ends exactly at a span boundary.
+            assert_eq!(remaining_in_span, 0);
+            assert_eq!(decoder.channels().get(), 1);
+        }
+    }
+    assert_eq!(decoder.channels().get(), 2);
+    assert_eq!(decoded, first_len + second_len);
+}
+
+#[cfg(all(feature = "symphonia-ogg", feature = "symphonia-vorbis"))]
+#[test]
+fn test_ogg_reports_gap() {
+    use std::io::Cursor;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    // Splits an Ogg file into its pages, with the granule position ending each page.
+    fn pages(bytes: &[u8]) -> Vec<(&[u8], u64)> {
+        let mut pages = Vec::new();
+        let mut rest = bytes;
+        while !rest.is_empty() {
+            assert_eq!(&rest[..4], b"OggS");
+            let segments = rest[26] as usize;
+            let body: usize = rest[27..27 + segments].iter().map(|s| *s as usize).sum();
+            let (page, tail) = rest.split_at(27 + segments + body);
+            let granule = u64::from_le_bytes(page[6..14].try_into().unwrap());
+            pages.push((page, granule));
+            rest = tail;
+        }
+        pages
+    }
+
+    let decode = |bytes: Vec<u8>| {
+        let gaps = Arc::new(Mutex::new(Vec::new()));
+        let reported = gaps.clone();
+        let decoder = rodio::Decoder::builder()
+            .with_data(Cursor::new(bytes))
+            .with_gap_callback(move |gap| reported.lock().unwrap().push(gap))
+            .build()
+            .unwrap();
+        let sample_rate = rodio::Source::sample_rate(&decoder).get();
+        decoder.for_each(drop);
+        let gaps = gaps.lock().unwrap().clone();
+        (gaps, sample_rate)
+    };
+
+    let original = std::fs::read("assets/music.ogg").unwrap();
+    let (gaps, sample_rate) = decode(original.clone());
+    assert!(gaps.is_empty(), "{gaps:?}");
+
+    // Drop an audio page that neither continues a packet nor has its last packet continued.
+    let pages = pages(&original);
+    let cut = (10..pages.len() - 1)
+        .find(|&i| pages[i].0[5] & 1 == 0 && pages[i + 1].0[5] & 1 == 0)
+        .unwrap();
+    let damaged: Vec<u8> = pages
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != cut)
+        .flat_map(|(_, (page, _))| page.iter().copied())
+        .collect();
+
+    let (gaps, _) = decode(damaged);
+    assert_eq!(gaps.len(), 1, "{gaps:?}");
+    let frames = |granule: u64| Duration::from_secs_f64(granule as f64 / sample_rate as f64);
+    let lost = frames(pages[cut].1 - pages[cut - 1].1);
+    let gap = gaps[0];
+    let tolerance = Duration::from_millis(25);
+    assert!(
+        gap.missing.abs_diff(lost) < tolerance,
+        "{gap:?}, lost {lost:?}"
+    );
+    assert!(
+        gap.position.abs_diff(frames(pages[cut - 1].1)) < tolerance,
+        "{gap:?}"
+    );
+}
@@ -37,7 +37,7 @@ use std::time::Duration;
 )]
 #[cfg_attr(
     all(feature = "claxon", not(feature = "symphonia-flac")),
-    case("flac", false, "claxon")
+    case("flac", true, "claxon")
 )]
 #[cfg_attr(feature = "symphonia-mp3", case("mp3", true, "symphonia"))]
 #[cfg_attr(
@@ -54,6 +54,7 @@ fn all_decoders(
 }
 
 #[cfg(any(
+    feature = "claxon",
     feature = "symphonia-flac",
     feature = "symphonia-mp3",
     feature = "symphonia-isomp4",
@@ -71,6 +72,10 @@ fn all_decoders(
     all(feature = "hound", not(feature = "symphonia-wav")),
     case("wav", "hound")
 )]
+#[cfg_attr(
+    all(feature = "claxon", not(feature = "symphonia-flac")),
+    case("flac", "claxon")
+)]
 #[cfg_attr(feature = "symphonia-mp3", case("mp3", "symphonia"))]
 #[cfg_attr(
     all(feature = "symphonia-isomp4", feature = "symphonia-aac"),
@@ -103,6 +108,7 @@ fn seek_returns_err_if_unsupported(
 }
 
 #[cfg(any(
+    feature = "claxon",
     feature = "symphonia-flac",
     feature = "symphonia-mp3",
     feature = "symphonia-isomp4",
@@ -122,6 +128,7 @@ fn seek_beyond_end_saturates(#[case] format: &'static str, #[case] decoder_name:
 }
 
 #[cfg(any(
+    feature = "claxon",
     feature = "symphonia-flac",
     feature = "symphonia-mp3",
     feature = "symphonia-isomp4",
@@ -158,6 +165,7 @@ fn seek_results_in_correct_remaining_playtime(
 }
 
 #[cfg(any(
+    feature = "claxon",
     feature = "symphonia-flac",
     feature = "symphonia-mp3",
     feature = "symphonia-isomp4",
@@ -180,6 +188,7 @@ fn seek_possible_after_exausting_source(
 }
 
 #[cfg(any(
+    feature = "claxon",
     feature = "symphonia-flac",
     feature = "symphonia-mp3",
     feature = "symphonia-isomp4",
@@ -275,6 +284,24 @@ fn random_access_seeks() {
     );
 }
 
+#[cfg(any(feature = "symphonia-wav", feature = "hound"))]
+#[test]
+fn seek_to_exact_frame() {
+    let full: Vec<f32> = get_music("wav").collect();
+    let mut decoder = get_music("wav");
+    let channels = decoder.channels().get() as usize;
+
+    for frame in [12_345, 777, 100_003] {
+        decoder.try_seek_samples(frame as u64).unwrap();
+        let next: Vec<f32> = decoder.by_ref().take(2 * channels).collect();
+        assert_eq!(
+            next,
+            full[frame * channels..(frame + 2) * channels],
+            "after seeking to frame {frame}"
+        );
+    }
+}
+
 fn second_channel_beep_range<R: rodio::Source>(source: &mut R) -> std::ops::Range<usize> {
     let channels = source.channels().get() as usize;
     let samples: Vec<f32> = source.by_ref().collect();
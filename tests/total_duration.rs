@@ -105,3 +105,38 @@ fn decoder_returns_total_duration(
         "decoder got {res}, correct is: {correct_duration}"
     );
 }
+
+#[cfg(feature = "symphonia-vorbis")]
+#[test]
+fn estimates_duration_of_stream_with_content_length() {
+    // Like an internet radio stream where the server only reports the content length.
+    let data = std::fs::read("assets/music.ogg").unwrap();
+    let len = data.len() as u64;
+    let mut decoder = rodio::Decoder::builder()
+        .with_data(std::io::Cursor::new(data.clone()))
+        .with_byte_len(len)
+        .with_seekable(false)
+        .build()
+        .unwrap();
+    let channels = decoder.channels().get() as f64;
+    let rate = decoder.sample_rate().get() as f64;
+
+    // The estimate is available once a bit of the stream has been decoded, and gets more
+    // accurate as the average bitrate of this VBR stream settles.
+    let played = decoder.by_ref().take(rate as usize * 2).count();
+    assert!(decoder.total_duration().is_some());
+    let samples = (played + decoder.by_ref().count()) as f64;
+    let actual = samples / channels / rate;
+    let refined = decoder.total_duration().unwrap().as_secs_f64();
+    assert!(
+        (refined - actual).abs() < actual * 0.05,
+        "{refined} vs {actual}"
+    );
+
+    let unknown_len = rodio::Decoder::builder()
+        .with_data(std::io::Cursor::new(data))
+        .with_seekable(false)
+        .build()
+        .unwrap();
+    assert_eq!(unknown_len.total_duration(), None);
+}
@@ -31,3 +31,149 @@ fn test_wav_encodings() {
     let mut decoder = rodio::Decoder::try_from(file).unwrap();
     assert!(decoder.any(|x| x != 0.0));
 }
+
+/// Builds a mono 24-bit WAV file in memory. With `extensible` the `fmt ` chunk uses the
+/// `WAVE_FORMAT_EXTENSIBLE` layout instead of plain PCM.
+#[cfg(any(feature = "hound", feature = "wav"))]
+fn wav_24bit(samples: &[i32], extensible: bool) -> Vec<u8> {
+    const SAMPLE_RATE: u32 = 8000;
+    let data: Vec<u8> = samples
+        .iter()
+        .flat_map(|s| s.to_le_bytes()[..3].to_vec())
+        .collect();
+
+    let mut fmt = Vec::new();
+    fmt.extend_from_slice(&(if extensible { 0xFFFEu16 } else { 1 }).to_le_bytes());
+    fmt.extend_from_slice(&1u16.to_le_bytes()); // channels
+    fmt.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    fmt.extend_from_slice(&(SAMPLE_RATE * 3).to_le_bytes()); // byte rate
+    fmt.extend_from_slice(&3u16.to_le_bytes()); // block align
+    fmt.extend_from_slice(&24u16.to_le_bytes()); // bits per sample
+    if extensible {
+        fmt.extend_from_slice(&22u16.to_le_bytes()); // extension size
+        fmt.extend_from_slice(&24u16.to_le_bytes()); // valid bits per sample
+        fmt.extend_from_slice(&4u32.to_le_bytes()); // channel mask: front center
+        fmt.extend_from_slice(&[
+            // KSDATAFORMAT_SUBTYPE_PCM
+            0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38,
+            0x9B, 0x71,
+        ]);
+    }
+
+    let mut wav = Vec::new();
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(4 + 8 + fmt.len() as u32 + 8 + data.len() as u32).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&(fmt.len() as u32).to_le_bytes());
+    wav.extend_from_slice(&fmt);
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    wav.extend_from_slice(&data);
+    wav
+}
+
+#[cfg(any(feature = "hound", feature = "wav"))]
+#[test]
+fn test_wav_24bit_ramp() {
+    const FULL_SCALE: i32 = 1 << 23;
+    // A ramp over the complete 24-bit range, including both extremes.
+    let ramp: Vec<i32> = (0..=64)
+        .map(|i| (-FULL_SCALE + i * (FULL_SCALE / 32)).min(FULL_SCALE - 1))
+        .collect();
+
+    for extensible in [false, true] {
+        let wav = wav_24bit(&ramp, extensible);
+        let decoder = rodio::Decoder::new_wav(std::io::Cursor::new(wav)).unwrap();
+        let decoded: Vec<f32> = decoder.collect();
+
+        assert_eq!(decoded.len(), ramp.len());
+        for (decoded, expected) in decoded.iter().zip(&ramp) {
+            let expected = *expected as f32 / FULL_SCALE as f32;
+            assert!(
+                (decoded - expected).abs() < 1e-6,
+                "expected {expected}, got {decoded} (extensible: {extensible})"
+            );
+        }
+        assert_eq!(decoded[0], -1.0);
+        assert_eq!(decoded[32], 0.0);
+    }
+}
+
+#[cfg(any(feature = "hound", feature = "wav"))]
+#[test]
+fn test_wav_from_embedded_bytes() {
+    use rodio::{Sink, Source};
+
+    static BEEP: &[u8] = include_bytes!("../assets/beep.wav");
+    let decoder = rodio::Decoder::new_from_bytes(BEEP).unwrap();
+    assert!(decoder.total_duration().is_some());
+
+    let (sink, queue_rx) = Sink::new();
+    sink.append(decoder);
+    assert!(queue_rx.take(100_000).any(|s| s != 0.0));
+}
+
+/// Builds a mono WAV file in memory holding one byte per sample with the given format tag.
+#[cfg(any(feature = "hound", feature = "wav"))]
+fn wav_8bit(format: u16, codes: &[u8]) -> Vec<u8> {
+    const SAMPLE_RATE: u32 = 8000;
+    let mut fmt = Vec::new();
+    fmt.extend_from_slice(&format.to_le_bytes());
+    fmt.extend_from_slice(&1u16.to_le_bytes()); // channels
+    fmt.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    fmt.extend_from_slice(&SAMPLE_RATE.to_le_bytes()); // byte rate
+    fmt.extend_from_slice(&1u16.to_le_bytes()); // block align
+    fmt.extend_from_slice(&8u16.to_le_bytes()); // bits per sample
+    fmt.extend_from_slice(&0u16.to_le_bytes()); // extension size
+
+    let mut wav = Vec::new();
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(4 + 8 + fmt.len() as u32 + 8 + codes.len() as u32).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&(fmt.len() as u32).to_le_bytes());
+    wav.extend_from_slice(&fmt);
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&(codes.len() as u32).to_le_bytes());
+    wav.extend_from_slice(codes);
+    wav
+}
+
+#[cfg(any(feature = "hound", feature = "wav"))]
+#[test]
+fn test_wav_mulaw_ramp() {
+    // µ-law codes from the most negative to the most positive value: the negative half counts
+    // up towards zero, the positive half counts down from zero.
+    let codes: Vec<u8> = (0x00..=0x7F).chain((0x80..=0xFF).rev()).collect();
+    let wav = wav_8bit(7, &codes);
+    let decoder = rodio::Decoder::new_wav(std::io::Cursor::new(wav)).unwrap();
+    let decoded: Vec<f32> = decoder.collect();
+
+    assert_eq!(decoded.len(), 256);
+    assert!(decoded.windows(2).all(|w| w[0] <= w[1]), "{decoded:?}");
+    // Values of the G.711 expansion table.
+    let expected = [
+        (0x00, -32124),
+        (0x0F, -16764),
+        (0x70, -120),
+        (0x7F, 0),
+        (0x8F, 16764),
+        (0xEF, 132),
+        (0xFE, 8),
+        (0xFF, 0),
+    ];
+    for (code, linear) in expected {
+        let index = codes.iter().position(|c| *c == code).unwrap();
+        assert_eq!(decoded[index], linear as f32 / 32768.0, "code {code:#x}");
+    }
+}
+
+#[cfg(any(feature = "hound", feature = "wav"))]
+#[test]
+fn test_wav_alaw() {
+    let wav = wav_8bit(6, &[0xD5, 0x55, 0xAA, 0x2A, 0x80]);
+    let decoder = rodio::Decoder::new_wav(std::io::Cursor::new(wav)).unwrap();
+    let decoded: Vec<f32> = decoder.map(|s| s * 32768.0).collect();
+    assert_eq!(decoded, [8.0, -8.0, 32256.0, -32256.0, 5504.0]);
+}